@@ -0,0 +1,102 @@
+//! Measures a frame of a UI made of 1000 widgets: building and drawing the
+//! widget tree, then preparing the vertices, glyphs and buffers like
+//! `IcedPass::prepare` does.
+//!
+//! A UI which looks the same as in the previous frame isn't uploaded again,
+//! the "changing" cases measure frames which do have to be uploaded.
+//!
+//! Run with `cargo bench --bench frame`.
+
+use amethyst_iced::headless::{IcedTestHarness, Tessellator};
+use amethyst_iced::{Cached, Column, Container, ContainerStyle, Element, Row, Sandbox, Text};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const ROWS: usize = 50;
+const COLUMNS: usize = 20;
+
+/// A grid of 1000 panels holding a text, e.g. a large inventory
+struct Inventory {
+    /// Whether the rows are wrapped in Cached widgets
+    cached: bool,
+    spacing: u16,
+}
+
+impl Sandbox for Inventory {
+    type UIMessage = ();
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let (cached, spacing) = (self.cached, self.spacing);
+        (0..ROWS)
+            .fold(Column::new().spacing(spacing), |column, y| {
+                let row = (0..COLUMNS).fold(Row::new().spacing(spacing), |row, x| {
+                    row.push(
+                        Container::new(Text::new(format!("{}", y * COLUMNS + x)).size(12))
+                            .padding(4)
+                            .style(ContainerStyle::panel()),
+                    )
+                });
+                if cached {
+                    column.push(Cached::new(row))
+                } else {
+                    column.push(row)
+                }
+            })
+            .into()
+    }
+}
+
+fn harness(cached: bool, spacing: u16) -> IcedTestHarness<Inventory> {
+    IcedTestHarness::new(Inventory { cached, spacing }).with_size(1600., 1200.)
+}
+
+fn frame(c: &mut Criterion) {
+    let mut ui = harness(false, 2);
+    c.bench_function("draw 1000 widgets", |b| b.iter(|| ui.draw()));
+
+    for &cached in &[false, true] {
+        let mut ui = harness(cached, 2);
+        let primitive = ui.draw();
+        let mut tessellator = Tessellator::default();
+        let name = if cached {
+            "prepare 1000 widgets in cached rows"
+        } else {
+            "prepare 1000 widgets"
+        };
+        c.bench_function(name, |b| b.iter(|| tessellator.prepare(&primitive)));
+    }
+
+    for &cached in &[false, true] {
+        // Two layouts taking turns, so that every frame is uploaded. Each
+        // iteration prepares both of them.
+        let frames = [harness(cached, 2).draw(), harness(cached, 3).draw()];
+        let mut tessellator = Tessellator::default();
+        let name = if cached {
+            "prepare 1000 widgets in cached rows, changing"
+        } else {
+            "prepare 1000 widgets, changing"
+        };
+        c.bench_function(name, |b| {
+            b.iter(|| frames.iter().map(|frame| tessellator.prepare(frame)).last())
+        });
+    }
+
+    for &cached in &[false, true] {
+        let mut ui = harness(cached, 2);
+        let mut tessellator = Tessellator::default();
+        let name = if cached {
+            "frame of 1000 widgets in cached rows"
+        } else {
+            "frame of 1000 widgets"
+        };
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                let primitive = ui.draw();
+                tessellator.prepare(&primitive)
+            })
+        });
+    }
+}
+
+criterion_group!(benches, frame);
+criterion_main!(benches);
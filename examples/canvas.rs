@@ -0,0 +1,146 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Button, ButtonState, Canvas, CanvasCache, Column, Container, Element, IcedBundle,
+    IcedUI, LineCap, Length, Path, Point, Sandbox, SandboxContainer, Stroke, Text,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<SkillTreeUIState>::default())?;
+
+    let mut game = Application::new(assets, SkillTreeState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct SkillTreeState;
+
+impl SimpleState for SkillTreeState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(SkillTreeUIState::default()));
+    }
+}
+
+/// Position of each skill, relative to the canvas, and the skill it requires
+const SKILLS: [((f32, f32), Option<usize>); 6] = [
+    ((200., 40.), None),
+    ((100., 140.), Some(0)),
+    ((300., 140.), Some(0)),
+    ((50., 250.), Some(1)),
+    ((150., 250.), Some(1)),
+    ((300., 250.), Some(2)),
+];
+
+#[derive(Default)]
+struct SkillTreeUIState {
+    tree: CanvasCache,
+    unlock: ButtonState,
+    unlocked: usize,
+}
+
+#[derive(Clone)]
+enum SkillTreeUIMessage {
+    Unlock,
+}
+
+impl Sandbox for SkillTreeUIState {
+    type UIMessage = SkillTreeUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let unlocked = self.unlocked;
+        let tree = Canvas::new(move |frame| {
+            let point = |index: usize| Point::new((SKILLS[index].0).0, (SKILLS[index].0).1);
+            let color = |index: usize| {
+                if index < unlocked {
+                    [1., 0.8, 0.3, 1.]
+                } else {
+                    [0.4, 0.4, 0.4, 1.]
+                }
+            };
+
+            for (index, (_, parent)) in SKILLS.iter().enumerate() {
+                if let Some(parent) = parent {
+                    let (from, to) = (point(*parent), point(index));
+                    let link = Path::new(|path| {
+                        path.move_to(from);
+                        path.bezier_curve_to(
+                            Point::new(from.x, (from.y + to.y) / 2.),
+                            Point::new(to.x, (from.y + to.y) / 2.),
+                            to,
+                        );
+                    });
+                    frame.stroke(
+                        &link,
+                        Stroke {
+                            line_cap: LineCap::Round,
+                            ..Stroke::new(color(index), 4.)
+                        },
+                    );
+                }
+            }
+            for index in 0..SKILLS.len() {
+                let node = Path::circle(point(index), 18.);
+                frame.fill(&node, [0.15, 0.15, 0.18, 1.]);
+                frame.stroke(&node, Stroke::new(color(index), 3.));
+            }
+        })
+        .width(Length::Units(350))
+        .height(Length::Units(300))
+        .cache(&self.tree);
+
+        let col = Column::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(tree)
+            .push(Text::new(format!("{} / {} skills unlocked", self.unlocked, SKILLS.len())))
+            .push(
+                Button::new(&mut self.unlock, Text::new("Unlock next skill"))
+                    .on_press(SkillTreeUIMessage::Unlock),
+            );
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            SkillTreeUIMessage::Unlock => {
+                self.unlocked = (self.unlocked + 1).min(SKILLS.len());
+                // The tree looks different now, it must be tessellated again
+                self.tree.clear();
+            }
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,134 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, ApplicationSandbox, Button, ButtonState, Column, Command, Container, Element,
+    IcedApplicationBundle, IcedUI, Length, SandboxContainer, Text,
+};
+
+use std::path::PathBuf;
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedApplicationBundle::<SaveListUIState>::default())?;
+
+    let mut game = Application::new(assets.clone(), SaveListState(assets), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+struct SaveListState(PathBuf);
+
+impl SimpleState for SaveListState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        let directory = self.0.clone();
+        // Lists the saves as soon as the game starts, without blocking the first frames
+        let sandbox = ApplicationSandbox::new(SaveListUIState {
+            directory: directory.clone(),
+            saves: None,
+            refresh: ButtonState::default(),
+        })
+        .with_command(Command::perform(
+            list_saves(directory),
+            SaveListUIMessage::Listed,
+        ));
+        world.insert(SandboxContainer::new(sandbox));
+    }
+}
+
+/// Lists the files of a directory, as a stand-in for slow IO
+async fn list_saves(directory: PathBuf) -> Vec<String> {
+    std::fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+struct SaveListUIState {
+    directory: PathBuf,
+    saves: Option<Vec<String>>,
+    refresh: ButtonState,
+}
+
+#[derive(Clone, Debug)]
+enum SaveListUIMessage {
+    Refresh,
+    Listed(Vec<String>),
+}
+
+// Not to be confused with the amethyst Application running the game
+impl amethyst_iced::Application for SaveListUIState {
+    type UIMessage = SaveListUIMessage;
+    type GameMessage = ();
+
+    fn update(
+        &mut self,
+        message: &SaveListUIMessage,
+        _game_messages: &mut Vec<()>,
+    ) -> Command<SaveListUIMessage> {
+        match message {
+            SaveListUIMessage::Refresh => {
+                self.saves = None;
+                Command::perform(list_saves(self.directory.clone()), SaveListUIMessage::Listed)
+            }
+            SaveListUIMessage::Listed(saves) => {
+                self.saves = Some(saves.clone());
+                Command::none()
+            }
+        }
+    }
+
+    fn view(&mut self) -> Element<SaveListUIMessage> {
+        let saves = match &self.saves {
+            None => Column::new().push(Text::new("Loading...")),
+            Some(saves) => saves
+                .iter()
+                .fold(Column::new().spacing(5), |column, save| {
+                    column.push(Text::new(save.as_str()))
+                }),
+        };
+
+        let col = Column::new()
+            .align_items(Align::Center)
+            .spacing(10)
+            .push(saves)
+            .push(
+                Button::new(&mut self.refresh, Text::new("Refresh"))
+                    .on_press(SaveListUIMessage::Refresh),
+            );
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
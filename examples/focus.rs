@@ -0,0 +1,124 @@
+use amethyst::{
+    input::{InputBundle, StringBindings},
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Button, ButtonState, Checkbox, Column, Container, Element, FocusActions, IcedBundle,
+    IcedUI, Length, Sandbox, SandboxContainer, Text,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+    let input_config = assets.join("input.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(InputBundle::<StringBindings>::new().with_bindings_from_file(input_config)?)?
+        .with_bundle(
+            IcedBundle::<FocusUIState>::default().with_focus_actions(FocusActions {
+                up: "ui_up".to_string(),
+                down: "ui_down".to_string(),
+                left: "ui_left".to_string(),
+                right: "ui_right".to_string(),
+                confirm: "ui_confirm".to_string(),
+                cancel: "ui_cancel".to_string(),
+            }),
+        )?;
+
+    let mut game = Application::new(assets, FocusState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct FocusState;
+
+impl SimpleState for FocusState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(FocusUIState::default()));
+    }
+}
+
+#[derive(Default, Debug)]
+struct FocusUIState {
+    new_game: ButtonState,
+    load_game: ButtonState,
+    quit: ButtonState,
+    fullscreen: bool,
+    last_pressed: &'static str,
+}
+
+#[derive(Clone)]
+enum FocusUIMessage {
+    Pressed(&'static str),
+    ToggleFullscreen(bool),
+}
+
+impl Sandbox for FocusUIState {
+    type UIMessage = FocusUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let col = Column::new()
+            .align_items(Align::Center)
+            .spacing(10)
+            .width(Length::Units(200))
+            .push(Text::new("Use the arrow keys or a d-pad, then Enter"))
+            .push(
+                Button::new(&mut self.new_game, Text::new("New game"))
+                    .width(Length::Fill)
+                    .on_press(FocusUIMessage::Pressed("New game")),
+            )
+            .push(
+                Button::new(&mut self.load_game, Text::new("Load game"))
+                    .width(Length::Fill)
+                    .on_press(FocusUIMessage::Pressed("Load game")),
+            )
+            .push(Checkbox::new(
+                self.fullscreen,
+                "Fullscreen",
+                FocusUIMessage::ToggleFullscreen,
+            ))
+            .push(
+                Button::new(&mut self.quit, Text::new("Quit"))
+                    .width(Length::Fill)
+                    .on_press(FocusUIMessage::Pressed("Quit")),
+            )
+            .push(Text::new(format!("Last pressed: {}", self.last_pressed)));
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            FocusUIMessage::Pressed(name) => self.last_pressed = name,
+            FocusUIMessage::ToggleFullscreen(v) => self.fullscreen = *v,
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,163 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{Transform, TransformBundle},
+    prelude::*,
+    renderer::{
+        plugins::{RenderFlat2D, RenderToWindow},
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        Camera, ImageFormat, RenderingBundle, Sprite, SpriteRender, SpriteSheet, Texture,
+    },
+    utils::application_root_dir,
+    window::ScreenDimensions,
+    Error,
+};
+use amethyst_iced::{
+    Align, Button, ButtonState, Column, Container, ContainerStyle, Element, IcedBundle, IcedUI,
+    Length, Sandbox, SandboxContainer, Shadow, Text, SCENE_TARGET,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(TransformBundle::new())?
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(RenderToWindow::from_config_path(display_config)?)
+                // The sprites are drawn offscreen, so that the UI can blur them
+                .with_plugin(RenderFlat2D::default().with_target(SCENE_TARGET))
+                .with_plugin(
+                    IcedUI::default()
+                        .with_backdrop_blur(16.)
+                        .with_scene_clear([0.2, 0.4, 0.3, 1.]),
+                ),
+        )?
+        .with_bundle(IcedBundle::<PauseUIState>::default())?;
+
+    let mut game = Application::new(assets, PauseState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct PauseState;
+
+impl SimpleState for PauseState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        let (width, height) = {
+            let dimensions = world.read_resource::<ScreenDimensions>();
+            (dimensions.width(), dimensions.height())
+        };
+
+        let mut camera = Transform::default();
+        camera.set_translation_xyz(width / 2., height / 2., 1.);
+        world
+            .create_entity()
+            .with(Camera::standard_2d(width, height))
+            .with(camera)
+            .build();
+
+        let sprite_sheet = {
+            let loader = world.read_resource::<Loader>();
+            let texture = loader.load(
+                "texture/test.png",
+                ImageFormat::default(),
+                (),
+                &world.read_resource::<AssetStorage<Texture>>(),
+            );
+            let sheet = SpriteSheet {
+                texture,
+                sprites: vec![Sprite::from_pixel_values(
+                    64,
+                    64,
+                    64,
+                    64,
+                    0,
+                    0,
+                    [0., 0.],
+                    false,
+                    false,
+                )],
+            };
+            loader.load_from_data(sheet, (), &world.read_resource::<AssetStorage<SpriteSheet>>())
+        };
+
+        // A checkerboard of sprites, for the panel to blur
+        for x in (0..width as u32).step_by(96) {
+            for y in (0..height as u32).step_by(96) {
+                let mut transform = Transform::default();
+                transform.set_translation_xyz(x as f32 + 32., y as f32 + 32., 0.);
+                world
+                    .create_entity()
+                    .with(SpriteRender {
+                        sprite_sheet: sprite_sheet.clone(),
+                        sprite_number: 0,
+                    })
+                    .with(transform)
+                    .build();
+            }
+        }
+
+        world.insert(SandboxContainer::new(PauseUIState::default()));
+    }
+}
+
+#[derive(Default)]
+struct PauseUIState {
+    resume: ButtonState,
+    quit: ButtonState,
+}
+
+#[derive(Clone)]
+enum PauseUIMessage {
+    Resume,
+    Quit,
+}
+
+impl Sandbox for PauseUIState {
+    type UIMessage = PauseUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let menu = Column::new()
+            .spacing(15)
+            .align_items(Align::Center)
+            .push(Text::new("Paused").size(32))
+            .push(
+                Button::new(&mut self.resume, Text::new("Resume"))
+                    .on_press(PauseUIMessage::Resume),
+            )
+            .push(Button::new(&mut self.quit, Text::new("Quit")).on_press(PauseUIMessage::Quit));
+
+        // A translucent background tints the blurred sprites
+        let panel = Container::new(menu).padding(40).style(ContainerStyle {
+            background: Some([1., 1., 1., 0.15].into()),
+            border_radius: 12,
+            text_color: Some([1., 1., 1., 1.]),
+            shadow: Some(Shadow::drop([0., 0., 0., 0.4])),
+            backdrop_blur: true,
+            ..ContainerStyle::default()
+        });
+
+        Container::new(panel)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            PauseUIMessage::Resume => log::info!("Resumed"),
+            PauseUIMessage::Quit => log::info!("Quit"),
+        }
+        vec![]
+    }
+}
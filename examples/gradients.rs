@@ -0,0 +1,121 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, BorderStyle, Button, ButtonState, ButtonStyle, Column, Container, ContainerStyle,
+    Element, IcedBundle, IcedUI, Length, LinearGradient, RadialGradient, Sandbox,
+    SandboxContainer, Text,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<ShopUIState>::default())?;
+
+    let mut game = Application::new(assets, ShopState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct ShopState;
+
+impl SimpleState for ShopState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(ShopUIState::default()));
+    }
+}
+
+#[derive(Default, Debug)]
+struct ShopUIState {
+    bought: u32,
+    buy: ButtonState,
+}
+
+#[derive(Clone)]
+enum ShopUIMessage {
+    Buy,
+}
+
+impl Sandbox for ShopUIState {
+    type UIMessage = ShopUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let panel = Container::new(
+            Column::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(Text::new("Potion shop").size(32))
+                .push(Text::new(format!("{} potions bought", self.bought)))
+                .push(
+                    Button::new(&mut self.buy, Text::new("Buy a potion"))
+                        .padding(12)
+                        .on_press(ShopUIMessage::Buy)
+                        .style(ButtonStyle::gradient([0.9, 0.6, 0.2, 1.], [0.6, 0.3, 0.05, 1.])),
+                ),
+        )
+        .padding(40)
+        .style(ContainerStyle {
+            background: Some(
+                LinearGradient::new(std::f32::consts::FRAC_PI_4)
+                    .stop(0., [0.25, 0.15, 0.35, 1.])
+                    .stop(0.5, [0.15, 0.2, 0.4, 1.])
+                    .stop(1., [0.1, 0.3, 0.3, 1.])
+                    .into(),
+            ),
+            border: BorderStyle {
+                width: 2,
+                color: [0.9, 0.8, 0.5, 1.],
+            },
+            border_radius: 12,
+            text_color: Some([1., 1., 1., 1.]),
+            ..ContainerStyle::default()
+        });
+
+        let centered = Container::new(panel)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        // Darkens the corners of the window
+        Container::new(centered)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ContainerStyle {
+                background: Some(RadialGradient::vignette([0., 0., 0., 0.8]).into()),
+                ..ContainerStyle::default()
+            })
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            ShopUIMessage::Buy => self.bought += 1,
+        }
+        vec![]
+    }
+}
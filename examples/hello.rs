@@ -10,7 +10,8 @@ use amethyst::{
     Error,
 };
 use amethyst_iced::{
-    Color, Column, Container, Element, IcedBundle, IcedUI, Length, Sandbox, SandboxContainer, Text,
+    Color, Column, Container, ContainerStyle, Element, IcedBundle, IcedUI, Length, Sandbox,
+    SandboxContainer, Text,
 };
 
 fn main() -> Result<(), Error> {
@@ -61,7 +62,12 @@ impl Sandbox for HelloUIState {
             .push(Text::new("Test white").color(Color::from_rgb(1., 1., 1.)))
             .push(Text::new("Test green").color(Color::from_rgb(0., 1., 0.)))
             .push(Text::new("Test blue").color(Color::from_rgb(0., 0., 1.)))
-            .push(Text::new("Test yellow").color(Color::from_rgb(1., 1., 0.)));
+            .push(Text::new("Test yellow").color(Color::from_rgb(1., 1., 0.)))
+            .push(
+                Container::new(Text::new("Test inherited dark"))
+                    .padding(10)
+                    .style(ContainerStyle::card()),
+            );
 
         Container::new(col)
             .width(Length::Fill)
@@ -0,0 +1,169 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Button, ButtonState, Column, Container, ContainerStyle, ContextMenu,
+    ContextMenuState, Element, IcedBundle, IcedUI, Length, Modal, PickList, PickListState, Row,
+    Sandbox, SandboxContainer, Text,
+};
+
+use std::fmt;
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<SettingsUIState>::default())?;
+
+    let mut game = Application::new(assets, SettingsState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct SettingsState;
+
+impl SimpleState for SettingsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(SettingsUIState::default()));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Default)]
+struct SettingsUIState {
+    difficulty_list: PickListState,
+    difficulty: Option<Difficulty>,
+    save_menu: ContextMenuState,
+    last_action: &'static str,
+    reset: ButtonState,
+    confirm: ButtonState,
+    cancel: ButtonState,
+    confirming: bool,
+}
+
+#[derive(Clone)]
+enum SettingsUIMessage {
+    Difficulty(Difficulty),
+    SaveAction(&'static str),
+    AskReset,
+    Reset(bool),
+}
+
+impl Sandbox for SettingsUIState {
+    type UIMessage = SettingsUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let difficulty = PickList::new(
+            &mut self.difficulty_list,
+            vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard],
+            self.difficulty,
+            SettingsUIMessage::Difficulty,
+        )
+        .placeholder("Choose a difficulty")
+        .width(Length::Units(200));
+
+        let save = ContextMenu::new(
+            &mut self.save_menu,
+            Container::new(Text::new("Save slot 1 (right click me)"))
+                .padding(10)
+                .style(ContainerStyle::card()),
+        )
+        .entry("Load", SettingsUIMessage::SaveAction("Load"))
+        .entry("Copy", SettingsUIMessage::SaveAction("Copy"))
+        .entry("Delete", SettingsUIMessage::SaveAction("Delete"));
+
+        let col = Column::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(difficulty)
+            .push(save)
+            .push(Text::new(format!("Last action: {}", self.last_action)))
+            .push(
+                Button::new(&mut self.reset, Text::new("Reset progress"))
+                    .on_press(SettingsUIMessage::AskReset),
+            );
+        let content = Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y();
+
+        let dialog = Column::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(Text::new("Reset all progress?"))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(&mut self.confirm, Text::new("Reset"))
+                            .on_press(SettingsUIMessage::Reset(true)),
+                    )
+                    .push(
+                        Button::new(&mut self.cancel, Text::new("Cancel"))
+                            .on_press(SettingsUIMessage::Reset(false)),
+                    ),
+            );
+
+        Modal::new(content, dialog)
+            .visible(self.confirming)
+            .on_dismiss(SettingsUIMessage::Reset(false))
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            SettingsUIMessage::Difficulty(difficulty) => self.difficulty = Some(*difficulty),
+            SettingsUIMessage::SaveAction(action) => self.last_action = action,
+            SettingsUIMessage::AskReset => self.confirming = true,
+            SettingsUIMessage::Reset(confirmed) => {
+                if *confirmed {
+                    self.difficulty = None;
+                    self.last_action = "Reset";
+                }
+                self.confirming = false;
+            }
+        }
+        vec![]
+    }
+}
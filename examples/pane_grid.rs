@@ -10,7 +10,7 @@ use amethyst::{
     Error,
 };
 use amethyst_iced::{
-    Align, Button, ButtonState, Column, Container, Element, IcedBundle, IcedUI, Length, Sandbox, SandboxContainer, Text, pane_grid, PaneGrid, HorizontalAlignment, VerticalAlignment,
+    Align, Button, ButtonState, ButtonStyle, Column, Container, ContainerStyle, Element, IcedBundle, IcedUI, Length, Sandbox, SandboxContainer, Text, TitleBar, pane_grid, PaneGrid,
 };
 
 fn main() -> Result<(), Error> {
@@ -54,11 +54,23 @@ struct PaneGridUIState {
 
 impl Default for PaneGridUIState {
     fn default() -> Self {
-        let (panes, _) = pane_grid::State::new(Content::new(0));
+        // Lays out a classic editor: explorer on the left,
+        // editor on the right, with a console below it.
+        let (mut panes, explorer) = pane_grid::State::new(Content::new(0, "Explorer"));
+        if let Some((editor, split)) =
+            panes.split(pane_grid::Axis::Vertical, &explorer, Content::new(1, "Editor"))
+        {
+            panes.resize(&split, 0.25);
+            if let Some((_, split)) =
+                panes.split(pane_grid::Axis::Horizontal, &editor, Content::new(2, "Console"))
+            {
+                panes.resize(&split, 0.7);
+            }
+        }
 
         PaneGridUIState {
             panes,
-            panes_created: 1,
+            panes_created: 3,
         }
     }
 }
@@ -104,7 +116,7 @@ impl Sandbox for PaneGridUIState {
                 let _ = self.panes.split(
                     *axis,
                     &pane,
-                    Content::new(self.panes_created),
+                    Content::new(self.panes_created, "Untitled"),
                 );
 
                 self.panes_created += 1;
@@ -114,7 +126,7 @@ impl Sandbox for PaneGridUIState {
                     let _ = self.panes.split(
                         *axis,
                         &pane,
-                        Content::new(self.panes_created),
+                        Content::new(self.panes_created, "Untitled"),
                     );
 
                     self.panes_created += 1;
@@ -156,15 +168,17 @@ impl Sandbox for PaneGridUIState {
 #[derive(Debug)]
 struct Content {
     id: usize,
+    title: &'static str,
     split_horizontally: ButtonState,
     split_vertically: ButtonState,
     close: ButtonState,
 }
 
 impl Content {
-    fn new(id: usize) -> Self {
+    fn new(id: usize, title: &'static str) -> Self {
         Content {
             id,
+            title,
             split_horizontally: ButtonState::new(),
             split_vertically: ButtonState::new(),
             close: ButtonState::new(),
@@ -178,12 +192,13 @@ impl Content {
     ) -> Element<PaneGridUIMessage> {
         let Content {
             id,
+            title,
             split_horizontally,
             split_vertically,
             close,
         } = self;
 
-        let mut controls = Column::new()
+        let controls = Column::new()
             .spacing(5)
             .max_width(150)
             .push(
@@ -204,18 +219,6 @@ impl Content {
                 .padding(8)
                 .on_press(PaneGridUIMessage::Split(pane_grid::Axis::Vertical, pane))
             );
-            
-            if total_panes > 1 {
-                controls = controls.push(
-                    Button::new(
-                        close, 
-                        Text::new("Close"),
-                    )
-                    .width(Length::Fill)
-                    .padding(8)
-                    .on_press(PaneGridUIMessage::Close(pane))
-            );
-        }
 
         let content = Column::new()
             .width(Length::Fill)
@@ -224,11 +227,27 @@ impl Content {
             .push(Text::new(format!("Pane {}", id)).size(30))
             .push(controls);
 
-        Container::new(content)
+        let body = Container::new(content)
             .width(Length::Fill)
             .height(Length::Fill)
             .padding(5)
-            .center_y()
+            .center_y();
+
+        let mut title_bar = TitleBar::new(format!("{} #{}", title, id))
+            .focused(focus.is_some());
+        if total_panes > 1 {
+            title_bar = title_bar.controls(
+                Button::new(close, Text::new("x"))
+                    .padding(2)
+                    .style(ButtonStyle::danger())
+                    .on_press(PaneGridUIMessage::Close(pane)),
+            );
+        }
+
+        Container::new(title_bar.view(body))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(ContainerStyle::panel())
             .into()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,117 @@
+use amethyst::{
+    assets::{AssetStorage, HotReloadBundle, Loader, RonFormat},
+    ecs::{Read, ReaderId, System, SystemData, World, Write},
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        ImageFormat, RenderingBundle, Texture,
+    },
+    shrev::EventChannel,
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    prefab::{PrefabEvent, PrefabSandbox, PrefabStyles, UiPrefab},
+    IcedBundle, IcedPrefabSystem, IcedUI, SandboxContainer,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        // Edit assets/ui/main_menu.ron while the example runs to see it reload
+        .with_bundle(HotReloadBundle::default())?
+        .with_bundle(IcedBundle::<PrefabSandbox<MenuAction>>::default())?
+        .with(IcedPrefabSystem::<MenuAction>::default(), "iced_prefab", &[])
+        .with(MenuSystem::default(), "menu", &[]);
+
+    let mut game = Application::new(assets, MenuState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Debug)]
+enum MenuAction {
+    Start,
+    Quit,
+    Volume(f32),
+    Fullscreen(bool),
+}
+
+#[derive(Default)]
+struct MenuState;
+
+impl SimpleState for MenuState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+
+        let (prefab, logo) = {
+            let loader = world.read_resource::<Loader>();
+            let prefab = loader.load(
+                "ui/main_menu.ron",
+                RonFormat,
+                (),
+                &world.read_resource::<AssetStorage<UiPrefab>>(),
+            );
+            let logo = loader.load(
+                "texture/test.png",
+                ImageFormat::default(),
+                (),
+                &world.read_resource::<AssetStorage<Texture>>(),
+            );
+            (prefab, logo)
+        };
+
+        let styles = PrefabStyles::default().with_image("logo", (logo, 200, 200));
+        let sandbox = PrefabSandbox::new(prefab, styles).with_bindings(|message| {
+            match (message.binding.as_ref().map(String::as_str), &message.event) {
+                (Some("start"), _) => vec![MenuAction::Start],
+                (Some("quit"), _) => vec![MenuAction::Quit],
+                (Some("volume"), PrefabEvent::Changed(volume)) => vec![MenuAction::Volume(*volume)],
+                (Some("fullscreen"), PrefabEvent::Toggled(checked)) => {
+                    vec![MenuAction::Fullscreen(*checked)]
+                }
+                _ => vec![],
+            }
+        });
+        world.insert(SandboxContainer::new(sandbox));
+    }
+}
+
+/// Reacts to the menu, as any game system would
+#[derive(Default)]
+struct MenuSystem {
+    reader: Option<ReaderId<MenuAction>>,
+}
+
+impl<'a> System<'a> for MenuSystem {
+    type SystemData = Read<'a, EventChannel<MenuAction>>;
+
+    fn run(&mut self, actions: Self::SystemData) {
+        let reader = self.reader.as_mut().unwrap();
+        actions
+            .read(reader)
+            .for_each(|action| println!("Menu action: {:?}", action));
+    }
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            Write::<'_, EventChannel<MenuAction>>::fetch(world).register_reader(),
+        );
+    }
+}
@@ -11,7 +11,7 @@ use amethyst::{
 };
 use amethyst_iced::{
     Align, Button, ButtonState, Column, Container, Element, IcedBundle, IcedUI, Length, Sandbox,
-    SandboxContainer, Text, ProgressBar,
+    SandboxContainer, Text, ProgressBar, ProgressBarStyle, Row,
 };
 
 fn main() -> Result<(), Error> {
@@ -68,6 +68,22 @@ impl Sandbox for ProgressBarUIState {
             .spacing(5)
             .push(Text::new(format!("Pressed {}/10 times", self.clicks)))
             .push(ProgressBar::new(0.0..=10., self.clicks as f32).width(Length::Units(400)))
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .align_items(Align::Center)
+                    .push(
+                        ProgressBar::new(0.0..=1., self.clicks as f32 / 10.)
+                            .width(Length::Units(360))
+                            .style(ProgressBarStyle::health(5)),
+                    )
+                    .push(
+                        ProgressBar::new(0.0..=0.1, self.clicks as f32 / 100.)
+                            .width(Length::Units(20))
+                            .height(Length::Units(100))
+                            .style(ProgressBarStyle::stamina()),
+                    ),
+            )
             .push(
                 Button::new(
                     &mut self.button_state,
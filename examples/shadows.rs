@@ -0,0 +1,127 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, BorderStyle, Button, ButtonState, ButtonStyle, Column, Container, ContainerStyle,
+    Element, IcedBundle, IcedUI, Length, Sandbox, SandboxContainer, Shadow, Text,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.3, 0.32, 0.36, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<QuestUIState>::default())?;
+
+    let mut game = Application::new(assets, QuestState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct QuestState;
+
+impl SimpleState for QuestState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(QuestUIState::default()));
+    }
+}
+
+#[derive(Default)]
+struct QuestUIState {
+    accept: ButtonState,
+    accepted: bool,
+}
+
+#[derive(Clone)]
+enum QuestUIMessage {
+    Accept,
+}
+
+impl Sandbox for QuestUIState {
+    type UIMessage = QuestUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        // The button glows until the quest is accepted
+        let glow = if self.accepted {
+            None
+        } else {
+            Some(Shadow::glow([1., 0.8, 0.3, 0.8], 12.))
+        };
+        let accept = Button::new(&mut self.accept, Text::new("Accept"))
+            .padding(10)
+            .on_press(QuestUIMessage::Accept)
+            .style(ButtonStyle::Custom {
+                background: [0.55, 0.35, 0.1, 1.].into(),
+                hovered_background: Some([0.65, 0.45, 0.15, 1.].into()),
+                pressed_background: None,
+                disabled_background: None,
+                text_color: [1., 1., 1., 1.],
+                border_radius: 4,
+                border: BorderStyle {
+                    width: 1,
+                    color: [1., 0.85, 0.5, 1.],
+                },
+                shadow: glow,
+            });
+
+        // A floating window, lifted above the scene by a drop shadow
+        let window = Container::new(
+            Column::new()
+                .spacing(20)
+                .align_items(Align::Center)
+                .push(Text::new("The lost sword").size(28))
+                .push(Text::new(if self.accepted {
+                    "Quest accepted, good luck!"
+                } else {
+                    "Find the sword in the old mine."
+                }))
+                .push(accept),
+        )
+        .padding(30)
+        .style(ContainerStyle {
+            shadow: Some(Shadow {
+                offset: [0., 10.],
+                blur_radius: 30.,
+                spread: 2.,
+                color: [0., 0., 0., 0.6],
+            }),
+            ..ContainerStyle::panel()
+        });
+
+        Container::new(window)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            QuestUIMessage::Accept => self.accepted = true,
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,139 @@
+use amethyst::{
+    core::Time,
+    ecs::{Read, System, Write},
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    shrev::EventChannel,
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Column, Container, Element, IcedBundle, IcedUI, Length, Sandbox, SandboxContainer,
+    Subscription, Text,
+};
+
+use std::time::{Duration, Instant};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<CountdownUIState>::default())?
+        .with(ScoutSystem::default(), "scout", &[]);
+
+    let mut game = Application::new(assets, CountdownState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct CountdownState;
+
+impl SimpleState for CountdownState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(EventChannel::<ScoutEvent>::default());
+        world.insert(SandboxContainer::new(CountdownUIState {
+            remaining: 20,
+            spotted: 0,
+        }));
+    }
+}
+
+/// An event of the game, which knows nothing about the UI
+struct ScoutEvent {
+    enemies: u32,
+}
+
+/// Spots an enemy every three seconds
+#[derive(Default)]
+struct ScoutSystem {
+    elapsed: f32,
+}
+
+impl<'a> System<'a> for ScoutSystem {
+    type SystemData = (Write<'a, EventChannel<ScoutEvent>>, Read<'a, Time>);
+
+    fn run(&mut self, (mut events, time): Self::SystemData) {
+        self.elapsed += time.delta_seconds();
+        if self.elapsed > 3. {
+            self.elapsed -= 3.;
+            events.single_write(ScoutEvent { enemies: 1 });
+        }
+    }
+}
+
+struct CountdownUIState {
+    remaining: u32,
+    spotted: u32,
+}
+
+#[derive(Clone, Debug)]
+enum CountdownUIMessage {
+    Tick(Instant),
+    Spotted(u32),
+}
+
+impl Sandbox for CountdownUIState {
+    type UIMessage = CountdownUIMessage;
+    type GameMessage = ();
+
+    fn subscription(&self) -> Subscription<CountdownUIMessage> {
+        let scouting = Subscription::events("scouting", |event: &ScoutEvent| {
+            Some(CountdownUIMessage::Spotted(event.enemies))
+        });
+        // The timer stops once the countdown is over
+        if self.remaining > 0 {
+            Subscription::batch(vec![
+                Subscription::every(
+                    "countdown",
+                    Duration::from_millis(500),
+                    CountdownUIMessage::Tick,
+                ),
+                scouting,
+            ])
+        } else {
+            scouting
+        }
+    }
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let col = Column::new()
+            .align_items(Align::Center)
+            .spacing(10)
+            .push(Text::new(format!("Reinforcements in {}", self.remaining)).size(30))
+            .push(Text::new(format!("{} enemies spotted", self.spotted)));
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            CountdownUIMessage::Tick(_) => self.remaining = self.remaining.saturating_sub(1),
+            CountdownUIMessage::Spotted(enemies) => self.spotted += enemies,
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,125 @@
+use amethyst::{
+    assets::Handle,
+    assets::{AssetStorage, Loader},
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        ImageFormat,
+        RenderingBundle,
+        Texture,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Color, Column, Container, Element, IcedBundle, IcedUI, Image, Length, Row, Sandbox,
+    SandboxContainer, Text, Tooltip,
+};
+
+use std::time::Duration;
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<InventoryUIState>::default())?;
+
+    let mut game = Application::new(assets, InventoryState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+struct Item {
+    name: &'static str,
+    description: &'static str,
+}
+
+struct InventoryUIState {
+    icon: Handle<Texture>,
+    items: Vec<Item>,
+}
+
+#[derive(Default, Debug)]
+struct InventoryState;
+
+impl SimpleState for InventoryState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+
+        let icon = {
+            let loader = world.read_resource::<Loader>();
+            let texture_storage = world.read_resource::<AssetStorage<Texture>>();
+            loader.load(
+                "texture/test.png",
+                ImageFormat::default(),
+                (),
+                &texture_storage,
+            )
+        };
+
+        let items = vec![
+            Item {
+                name: "Rusty sword",
+                description: "Deals 3 damage.\nSeen better days.",
+            },
+            Item {
+                name: "Health potion",
+                description: "Restores 20 health.",
+            },
+            Item {
+                name: "Old map",
+                description: "Marks a place nobody remembers.",
+            },
+        ];
+
+        world.insert(SandboxContainer::new(InventoryUIState { icon, items }))
+    }
+}
+
+impl Sandbox for InventoryUIState {
+    type UIMessage = ();
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let icon = &self.icon;
+        let slots = self.items.iter().fold(Row::new().spacing(10), |row, item| {
+            row.push(
+                Tooltip::new(
+                    Image::new((icon.clone(), 64, 64)),
+                    Column::new()
+                        .spacing(4)
+                        .push(Text::new(item.name).color(Color::from_rgb(1., 0.8, 0.3)))
+                        .push(Text::new(item.description).size(16)),
+                )
+                .delay(Duration::from_millis(400)),
+            )
+        });
+
+        let col = Column::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(Text::new("Hover an item"))
+            .push(slots);
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
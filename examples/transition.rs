@@ -0,0 +1,116 @@
+use amethyst::{
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Button, ButtonState, ButtonStyle, Column, Container, ContainerStyle, Easing, Element,
+    IcedBundle, IcedUI, Length, Motion, Sandbox, SandboxContainer, StateTransition, Text,
+    Transition, TransitionKind, Tween, Vector,
+};
+
+use std::time::Duration;
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<TransitionUIState>::default())?;
+
+    let mut game = Application::new(assets, TransitionState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct TransitionState;
+
+impl SimpleState for TransitionState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.insert(SandboxContainer::new(TransitionUIState::default()));
+    }
+}
+
+#[derive(Default, Debug)]
+struct TransitionUIState {
+    inventory_open: bool,
+    button_state: ButtonState,
+}
+
+#[derive(Clone)]
+enum TransitionUIMessage {
+    ToggleInventory,
+}
+
+impl Sandbox for TransitionUIState {
+    type UIMessage = TransitionUIMessage;
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        let inventory = Container::new(
+            Column::new()
+                .spacing(5)
+                .push(Text::new("Inventory"))
+                .push(Text::new("- Rusty sword"))
+                .push(Text::new("- 3 health potions")),
+        )
+        .padding(20)
+        .width(Length::Units(300))
+        // Lifts the panel a bit while it is hovered
+        .style(ContainerStyle {
+            transition: Some(StateTransition {
+                hovered: Motion::offset(0., -4.),
+                ..StateTransition::default()
+            }),
+            ..ContainerStyle::panel()
+        });
+
+        let col = Column::new()
+            .align_items(Align::Center)
+            .spacing(20)
+            .push(
+                Button::new(&mut self.button_state, Text::new("Toggle inventory"))
+                    .on_press(TransitionUIMessage::ToggleInventory)
+                    .style(ButtonStyle::primary()),
+            )
+            .push(
+                Transition::new("inventory", inventory)
+                    .kind(TransitionKind::FadeSlide(Vector::new(0., 40.)))
+                    .tween(Tween::new(Duration::from_millis(400), Easing::EaseOutBack))
+                    .visible(self.inventory_open),
+            );
+
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    fn update(&mut self, message: &Self::UIMessage) -> Vec<Self::GameMessage> {
+        match message {
+            TransitionUIMessage::ToggleInventory => self.inventory_open = !self.inventory_open,
+        }
+        vec![]
+    }
+}
@@ -0,0 +1,139 @@
+use amethyst::{
+    core::Time,
+    ecs::{Component, DenseVecStorage, Join, Read, ReadStorage, System, WriteStorage},
+    prelude::*,
+    renderer::{
+        plugins::RenderToWindow,
+        //types::DefaultBackend,
+        rendy::util::vulkan::Backend,
+        RenderingBundle,
+    },
+    utils::application_root_dir,
+    Error,
+};
+use amethyst_iced::{
+    Align, Column, Container, Element, IcedBundle, IcedUI, Length, ProgressBar, ProgressBarStyle,
+    Row, Sandbox, SandboxContainer, SandboxData, Text,
+};
+
+fn main() -> Result<(), Error> {
+    amethyst::start_logger(Default::default());
+    let app_root = application_root_dir()?;
+    let assets = app_root.join("assets");
+    let display_config = assets.join("display_config.ron");
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(
+            RenderingBundle::<Backend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config)?
+                        .with_clear([0.1, 0.1, 0.1, 1.0]),
+                )
+                .with_plugin(IcedUI::default()),
+        )?
+        .with_bundle(IcedBundle::<PartyUIState>::default().with_view_data())?
+        .with(PoisonSystem, "poison", &[]);
+
+    let mut game = Application::new(assets, PartyState::default(), game_data)?;
+    game.run();
+
+    Ok(())
+}
+
+struct Character {
+    name: &'static str,
+}
+
+impl Component for Character {
+    type Storage = DenseVecStorage<Self>;
+}
+
+struct Health {
+    current: f32,
+    max: f32,
+}
+
+impl Component for Health {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Slowly drains the health of every character
+struct PoisonSystem;
+
+impl<'a> System<'a> for PoisonSystem {
+    type SystemData = (WriteStorage<'a, Health>, Read<'a, Time>);
+
+    fn run(&mut self, (mut healths, time): Self::SystemData) {
+        for health in (&mut healths).join() {
+            health.current = (health.current - time.delta_seconds() * 2.).max(0.);
+        }
+    }
+}
+
+#[derive(Default)]
+struct PartyState;
+
+impl SimpleState for PartyState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let StateData { world, .. } = data;
+        world.register::<Character>();
+        world.register::<Health>();
+        for (name, max) in [("Warrior", 120.), ("Mage", 60.), ("Rogue", 80.)].iter() {
+            world
+                .create_entity()
+                .with(Character { name: *name })
+                .with(Health {
+                    current: *max,
+                    max: *max,
+                })
+                .build();
+        }
+        world.insert(SandboxContainer::new(PartyUIState));
+    }
+}
+
+/// Displays the health of the party, read straight from the ECS
+struct PartyUIState;
+
+impl Sandbox for PartyUIState {
+    type UIMessage = ();
+    type GameMessage = ();
+
+    fn view(&mut self) -> Element<Self::UIMessage> {
+        Text::new("Waiting for the party...").into()
+    }
+}
+
+impl<'a> SandboxData<'a> for PartyUIState {
+    type ViewData = (ReadStorage<'a, Character>, ReadStorage<'a, Health>);
+
+    fn view_with_data(
+        &mut self,
+        (characters, healths): &Self::ViewData,
+    ) -> Element<Self::UIMessage> {
+        let party = (characters, healths).join().fold(
+            Column::new().spacing(10),
+            |column, (character, health)| {
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .align_items(Align::Center)
+                        .push(Text::new(character.name).width(Length::Units(100)))
+                        .push(
+                            ProgressBar::new(0.0..=health.max, health.current)
+                                .width(Length::Units(300))
+                                .height(Length::Units(20))
+                                .style(ProgressBarStyle::health(5)),
+                        ),
+                )
+            },
+        );
+
+        Container::new(party)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+}
@@ -0,0 +1,19 @@
+#version 450
+
+// Shows the blurred scene behind a panel. The alpha of the vertices
+// carries the coverage of the antialiased edges and the opacity.
+
+layout(set = 1, binding = 0) uniform sampler2D blurred;
+
+layout(push_constant) uniform BackdropConstants {
+    vec2 inverse_target_size;
+};
+
+layout(location = 0) in vec4 o_color;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec3 color = texture(blurred, gl_FragCoord.xy * inverse_target_size).rgb;
+    out_color = vec4(color, o_color.a);
+}
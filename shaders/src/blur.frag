@@ -0,0 +1,29 @@
+#version 450
+
+// One direction of a separable gaussian blur
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(push_constant) uniform BlurConstants {
+    // Distance between two taps, in uv
+    vec2 step;
+    // Standard deviation of the blur, in taps
+    float sigma;
+};
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+const int TAPS = 6;
+
+void main() {
+    vec4 sum = vec4(0.0);
+    float total = 0.0;
+    for (int i = -TAPS; i <= TAPS; i++) {
+        float weight = exp(-float(i * i) / (2.0 * sigma * sigma));
+        sum += texture(source, uv + step * float(i)) * weight;
+        total += weight;
+    }
+    out_color = sum / total;
+}
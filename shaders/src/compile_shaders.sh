@@ -4,12 +4,19 @@
 glslc triangle.vert -o triangle.vert.spv
 glslc triangle.frag -o triangle.frag.spv
 
-# Compiles image
-glslc image.vert -o image.vert.spv
-glslc image.frag -o image.frag.spv
+# Compiles shadow
+glslc shadow.vert -o shadow.vert.spv
+glslc shadow.frag -o shadow.frag.spv
 
-# Compiles text
-glslc text.vert -o text.vert.spv
-glslc text.frag -o text.frag.spv
+# Compiles backdrop blur
+glslc fullscreen.vert -o fullscreen.vert.spv
+glslc blur.frag -o blur.frag.spv
+glslc composite.frag -o composite.frag.spv
+glslc backdrop.frag -o backdrop.frag.spv
+
+# Compiles quads, images & glyphs
+glslc quad.vert -o quad.vert.spv
+glslc quad.frag -o quad.frag.spv
+glslc textured.frag -o textured.frag.spv
 
 mv *.spv ../compiled
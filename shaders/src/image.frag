@@ -1,11 +0,0 @@
-#version 450
-
-layout(set = 1, binding = 0) uniform sampler2D tex;
-
-layout(location = 0) in vec2 uv;
-
-layout(location = 0) out vec4 o_color;
-
-void main() {
-    o_color = texture(tex, uv);
-}
\ No newline at end of file
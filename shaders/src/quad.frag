@@ -0,0 +1,48 @@
+#version 450
+
+// Rectangles with rounded corners and a border, antialiased with their distance field
+
+layout(location = 0) in vec2 local;
+layout(location = 1) in vec2 uv;
+layout(location = 2) flat in vec2 half_size;
+layout(location = 3) flat in vec4 color;
+layout(location = 4) flat in vec4 border_color;
+// x: border radius, y: border width, z: width of the antialiased edges
+layout(location = 5) flat in vec4 params;
+
+layout(location = 0) out vec4 out_color;
+
+// Signed distance to the edge of a rounded box centered on the origin
+float rounded_box(vec2 point, vec2 half_size, float radius) {
+    vec2 q = abs(point) - half_size + radius;
+    return length(max(q, 0.0)) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+// How much of the pixel is inside, the edge fading out over `feather` pixels
+float coverage(float distance, float feather) {
+    float width = max(feather * fwidth(distance), 1e-4);
+    return clamp(0.5 - distance / width, 0.0, 1.0);
+}
+
+void main() {
+    float radius = params.x;
+    float border_width = params.y;
+    float feather = params.z;
+
+    float outer = coverage(rounded_box(local, half_size, radius), feather);
+    vec4 fill = color;
+    if (border_width > 0.0) {
+        float inner = coverage(
+            rounded_box(local, max(half_size - border_width, 0.0), max(radius - border_width, 0.0)),
+            feather
+        );
+        // Mixed premultiplied, so that a transparent background doesn't darken the border
+        vec4 mixed = mix(
+            vec4(border_color.rgb * border_color.a, border_color.a),
+            vec4(color.rgb * color.a, color.a),
+            inner
+        );
+        fill = vec4(mixed.rgb / max(mixed.a, 1e-5), mixed.a);
+    }
+    out_color = vec4(fill.rgb, fill.a * outer);
+}
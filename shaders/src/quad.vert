@@ -0,0 +1,43 @@
+#version 450
+
+// Stretches the unit quad over the rectangle of an instance.
+// Shared by solid quads, images and glyphs.
+
+layout (std140, set = 0, binding = 0) uniform QuadUniform {
+    uniform mat4 u_transform;
+};
+
+// Per vertex
+layout(location = 0) in vec2 in_corner;
+// Per instance
+layout(location = 1) in vec4 in_rect;
+layout(location = 2) in vec4 in_uv;
+layout(location = 3) in vec4 in_color;
+layout(location = 4) in vec4 in_border_color;
+// x: border radius, y: border width, z: width of the antialiased edges, w: kind of quad
+layout(location = 5) in vec4 in_params;
+layout(location = 6) in vec4 in_axes;
+layout(location = 7) in vec2 in_translation;
+
+layout(location = 0) out vec2 local;
+layout(location = 1) out vec2 uv;
+layout(location = 2) flat out vec2 half_size;
+layout(location = 3) flat out vec4 color;
+layout(location = 4) flat out vec4 border_color;
+layout(location = 5) flat out vec4 params;
+
+void main() {
+    // The antialiased edges are centered on the edges of the rectangle
+    float margin = in_params.z * 0.5;
+    vec2 size = in_rect.zw + 2.0 * margin;
+    vec2 position = in_rect.xy - margin + in_corner * size;
+    vec2 transformed = in_axes.xy * position.x + in_axes.zw * position.y + in_translation;
+    gl_Position = vec4((u_transform * vec4(transformed, 0.0, 1.0)).xy, 0.0, 1.0);
+
+    half_size = in_rect.zw * 0.5;
+    local = position - in_rect.xy - half_size;
+    uv = in_uv.xy + in_corner * in_uv.zw;
+    color = in_color;
+    border_color = in_border_color;
+    params = in_params;
+}
@@ -0,0 +1,56 @@
+#version 450
+
+// Blurred rounded rectangles, after Evan Wallace's "Fast Rounded Rectangle Shadows":
+// the gaussian blur is integrated exactly along x, and sampled along y.
+
+layout(location = 0) in vec2 local;
+layout(location = 1) flat in vec2 half_size;
+layout(location = 2) flat in vec4 color;
+// x: standard deviation of the blur, y: corner radius
+layout(location = 3) flat in vec2 params;
+
+layout(location = 0) out vec4 out_color;
+
+const float PI = 3.1415926535897932384626433832795;
+const int SAMPLES = 4;
+
+float gaussian(float x, float sigma) {
+    return exp(-(x * x) / (2.0 * sigma * sigma)) / (sqrt(2.0 * PI) * sigma);
+}
+
+// Approximation of the error function, within 5e-4
+vec2 erf(vec2 x) {
+    vec2 s = sign(x);
+    vec2 a = abs(x);
+    x = 1.0 + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    x *= x;
+    return s - s / (x * x);
+}
+
+// The blurred coverage of a horizontal line of the rectangle
+float shadow_x(float x, float y, float sigma, float corner) {
+    float delta = min(half_size.y - corner - abs(y), 0.0);
+    float curved = half_size.x - corner + sqrt(max(0.0, corner * corner - delta * delta));
+    vec2 integral = 0.5 + 0.5 * erf((x + vec2(-curved, curved)) * (sqrt(0.5) / sigma));
+    return integral.y - integral.x;
+}
+
+void main() {
+    float sigma = params.x;
+    float corner = params.y;
+
+    float low = local.y - half_size.y;
+    float high = local.y + half_size.y;
+    float start = clamp(-3.0 * sigma, low, high);
+    float end = clamp(3.0 * sigma, low, high);
+
+    float step = (end - start) / float(SAMPLES);
+    float y = start + step * 0.5;
+    float value = 0.0;
+    for (int i = 0; i < SAMPLES; i++) {
+        value += shadow_x(local.x, local.y - y, sigma, corner) * gaussian(y, sigma) * step;
+        y += step;
+    }
+
+    out_color = vec4(color.rgb, color.a * value);
+}
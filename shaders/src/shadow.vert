@@ -0,0 +1,26 @@
+#version 450
+
+layout (std140, set = 0, binding = 0) uniform TriangleUniform {
+    uniform mat4 u_transform;
+};
+
+layout(location = 0) in vec2 in_pos;
+layout(location = 1) in vec2 in_local;
+layout(location = 2) in vec2 in_half_size;
+layout(location = 3) in vec4 in_color;
+layout(location = 4) in vec2 in_params;
+
+layout(location = 0) out vec2 local;
+layout(location = 1) flat out vec2 half_size;
+layout(location = 2) flat out vec4 color;
+layout(location = 3) flat out vec2 params;
+
+void main() {
+    vec4 transformed = u_transform * vec4(in_pos, 0.0, 1.0);
+    gl_Position = vec4(transformed.xy, 0.0, 1.0);
+
+    local = in_local;
+    half_size = in_half_size;
+    color = in_color;
+    params = in_params;
+}
@@ -0,0 +1,25 @@
+#version 450
+
+// Images and glyphs
+
+layout(set = 1, binding = 0) uniform sampler2D tex;
+
+layout(location = 0) in vec2 local;
+layout(location = 1) in vec2 uv;
+layout(location = 2) flat in vec2 half_size;
+layout(location = 3) flat in vec4 color;
+layout(location = 4) flat in vec4 border_color;
+// w: 1 for images, 2 for glyphs
+layout(location = 5) flat in vec4 params;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec4 texel = texture(tex, uv);
+    if (params.w > 1.5) {
+        // The glyph atlas only holds the coverage, in its alpha
+        out_color = vec4(color.rgb, color.a * texel.a);
+    } else {
+        out_color = texel * color;
+    }
+}
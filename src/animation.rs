@@ -0,0 +1,225 @@
+//! Interpolation of style values over time, for hover effects and transitions.
+//!
+//! Renderers animate values through `IcedRenderer::animate`, which keeps track
+//! of every animated value in the `IcedAnimations` resource.
+
+use iced_native::{Color, Vector};
+
+use std::time::Duration;
+
+/// How an animation progresses over its duration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseInCubic,
+    EaseOutCubic,
+    EaseInOutCubic,
+    /// Overshoots the target a bit before settling
+    EaseOutBack,
+}
+
+impl Easing {
+    /// Maps the elapsed fraction of the animation, between 0 and 1, to its progress
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.max(0.).min(1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => t * (2. - t),
+            Easing::EaseInOutQuad => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    -1. + (4. - 2. * t) * t
+                }
+            }
+            Easing::EaseInCubic => t * t * t,
+            Easing::EaseOutCubic => {
+                let t = t - 1.;
+                t * t * t + 1.
+            }
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    let t = 2. * t - 2.;
+                    0.5 * t * t * t + 1.
+                }
+            }
+            Easing::EaseOutBack => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.;
+                let t = t - 1.;
+                1. + c3 * t * t * t + c1 * t * t
+            }
+        }
+    }
+}
+
+/// The duration & easing of an animation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Tween {
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Tween { duration, easing }
+    }
+
+    /// Returns the progress of the animation, between 0 and 1 (unless the easing overshoots)
+    pub fn progress(&self, elapsed: Duration) -> f32 {
+        if self.duration == Duration::from_secs(0) {
+            return 1.;
+        }
+        self.easing
+            .apply(elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+}
+
+impl Default for Tween {
+    /// A short transition, suited to hover effects
+    fn default() -> Self {
+        Tween::new(Duration::from_millis(150), Easing::EaseOutQuad)
+    }
+}
+
+/// The look of a widget in a given state, relative to its idle look
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    /// Multiplies the opacity of the widget
+    pub opacity: f32,
+    /// Moves the widget, in pixels
+    pub offset: Vector,
+    /// Scales the widget around its center
+    pub scale: f32,
+}
+
+impl Motion {
+    /// Scales the widget, leaving it in place
+    pub fn scale(scale: f32) -> Self {
+        Motion {
+            scale,
+            ..Motion::default()
+        }
+    }
+
+    /// Moves the widget, without scaling it
+    pub fn offset(x: f32, y: f32) -> Self {
+        Motion {
+            offset: Vector::new(x, y),
+            ..Motion::default()
+        }
+    }
+
+    /// Fades the widget, leaving it in place
+    pub fn opacity(opacity: f32) -> Self {
+        Motion {
+            opacity,
+            ..Motion::default()
+        }
+    }
+}
+
+impl Default for Motion {
+    /// The idle look, which changes nothing
+    fn default() -> Self {
+        Motion {
+            opacity: 1.,
+            offset: Vector::new(0., 0.),
+            scale: 1.,
+        }
+    }
+}
+
+/// How a widget eases into its hovered and pressed looks.
+///
+/// Like `AmethystIcedPrimitive::Transform`, this is purely visual: the widget
+/// keeps reacting at its place in the layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateTransition {
+    pub tween: Tween,
+    pub hovered: Motion,
+    /// Only used by the widgets which can be pressed, e.g. the handle of a Slider
+    pub pressed: Motion,
+}
+
+impl Default for StateTransition {
+    /// Grows the widget a bit when hovered, and shrinks it when pressed
+    fn default() -> Self {
+        StateTransition {
+            tween: Tween::default(),
+            hovered: Motion::scale(1.1),
+            pressed: Motion::scale(0.95),
+        }
+    }
+}
+
+/// A value which can be interpolated by an animation
+pub trait Animatable: Copy {
+    fn to_components(self) -> [f32; 4];
+    fn from_components(components: [f32; 4]) -> Self;
+}
+
+impl Animatable for f32 {
+    fn to_components(self) -> [f32; 4] {
+        [self, 0., 0., 0.]
+    }
+
+    fn from_components(components: [f32; 4]) -> Self {
+        components[0]
+    }
+}
+
+impl Animatable for [f32; 4] {
+    fn to_components(self) -> [f32; 4] {
+        self
+    }
+
+    fn from_components(components: [f32; 4]) -> Self {
+        components
+    }
+}
+
+impl Animatable for Color {
+    fn to_components(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    fn from_components(components: [f32; 4]) -> Self {
+        Color {
+            r: components[0],
+            g: components[1],
+            b: components[2],
+            a: components[3],
+        }
+    }
+}
+
+impl Animatable for Vector {
+    fn to_components(self) -> [f32; 4] {
+        [self.x, self.y, 0., 0.]
+    }
+
+    fn from_components(components: [f32; 4]) -> Self {
+        Vector::new(components[0], components[1])
+    }
+}
+
+impl Animatable for Motion {
+    fn to_components(self) -> [f32; 4] {
+        [self.opacity, self.offset.x, self.offset.y, self.scale]
+    }
+
+    fn from_components(components: [f32; 4]) -> Self {
+        Motion {
+            opacity: components[0],
+            offset: Vector::new(components[1], components[2]),
+            scale: components[3],
+        }
+    }
+}
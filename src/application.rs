@@ -0,0 +1,115 @@
+use amethyst::ecs::SystemData;
+use iced_native::Command;
+
+use std::sync::Mutex;
+
+use crate::sandbox::{Element, Sandbox, SandboxData};
+use crate::subscription::Subscription;
+
+/// An Application is a Sandbox able to run asynchronous work.
+///
+/// Its `update` returns a Command, whose futures are run on Amethyst's thread pool.
+/// The messages they produce are then sent back to the Application, as if they came
+/// from the UI. Use it through an `ApplicationSandbox` and an `IcedApplicationBundle`.
+pub trait Application: Send + Sync + 'static {
+    type UIMessage: Send + Sync + 'static;
+    type GameMessage: Send + Sync + 'static;
+
+    /// Handles an UI message, pushing the GameMessages to send to the game systems
+    fn update(
+        &mut self,
+        _message: &Self::UIMessage,
+        _game_messages: &mut Vec<Self::GameMessage>,
+    ) -> Command<Self::UIMessage> {
+        Command::none()
+    }
+
+    fn view(&mut self) -> Element<Self::UIMessage>;
+
+    /// See `Sandbox::subscription`
+    fn subscription(&self) -> Subscription<Self::UIMessage> {
+        Subscription::none()
+    }
+}
+
+/// An Application whose view reads ECS data, see `SandboxData`.
+///
+/// Use it through `IcedApplicationBundle::with_view_data`.
+pub trait ApplicationData<'a>: Application {
+    type ViewData: SystemData<'a>;
+
+    /// Replaces `view` when the Application is drawn
+    fn view_with_data(&mut self, data: &Self::ViewData) -> Element<Self::UIMessage>;
+}
+
+/// Runs an Application as a Sandbox, keeping its commands until they are
+/// spawned by the IcedCommandSystem.
+pub struct ApplicationSandbox<A: Application> {
+    application: A,
+    // Commands are Send but not Sync, hence the Mutex
+    commands: Mutex<Vec<Command<A::UIMessage>>>,
+}
+
+impl<A: Application> ApplicationSandbox<A> {
+    pub fn new(application: A) -> Self {
+        ApplicationSandbox {
+            application,
+            commands: Mutex::new(vec![]),
+        }
+    }
+
+    /// Runs a command as soon as the game starts, e.g. to load some data
+    pub fn with_command(self, command: Command<A::UIMessage>) -> Self {
+        self.push_command(command);
+        self
+    }
+
+    pub fn application(&mut self) -> &mut A {
+        &mut self.application
+    }
+
+    fn push_command(&self, command: Command<A::UIMessage>) {
+        self.commands
+            .lock()
+            .expect("Failed to lock the pending commands")
+            .push(command);
+    }
+
+    pub(crate) fn take_commands(&self) -> Vec<Command<A::UIMessage>> {
+        std::mem::replace(
+            &mut *self
+                .commands
+                .lock()
+                .expect("Failed to lock the pending commands"),
+            vec![],
+        )
+    }
+}
+
+impl<A: Application> Sandbox for ApplicationSandbox<A> {
+    type UIMessage = A::UIMessage;
+    type GameMessage = A::GameMessage;
+
+    fn update(&mut self, message: &A::UIMessage) -> Vec<A::GameMessage> {
+        let mut game_messages = vec![];
+        let command = self.application.update(message, &mut game_messages);
+        self.push_command(command);
+        game_messages
+    }
+
+    fn view(&mut self) -> Element<A::UIMessage> {
+        self.application.view()
+    }
+
+    fn subscription(&self) -> Subscription<A::UIMessage> {
+        self.application.subscription()
+    }
+}
+
+impl<'a, A: ApplicationData<'a>> SandboxData<'a> for ApplicationSandbox<A> {
+    type ViewData = A::ViewData;
+
+    fn view_with_data(&mut self, data: &A::ViewData) -> Element<A::UIMessage> {
+        self.application.view_with_data(data)
+    }
+}
@@ -0,0 +1,368 @@
+//! The render nodes behind the backdrop blur of containers.
+//!
+//! When enabled with `IcedUI::with_backdrop_blur`, the scene is rendered in
+//! `SCENE_TARGET` instead of the window. It is then blurred at half resolution,
+//! horizontally and vertically, in two targets of its own, before being copied
+//! in the window below the UI. Containers with a backdrop sample the blurred
+//! scene under their background.
+
+use amethyst::ecs::World;
+use amethyst::renderer::{
+    bundle::Target,
+    pipeline::{PipelineDescBuilder, PipelinesBuilder},
+    rendy::{
+        command::{QueueId, RenderPassEncoder},
+        factory::Factory,
+        graph::{
+            render::{PrepareResult, RenderGroup, RenderGroupDesc},
+            GraphContext, ImageAccess, NodeBuffer, NodeImage,
+        },
+        hal::pso::{self, ShaderStageFlags},
+        hal::{self, device::Device},
+        resource::{
+            DescriptorSet, DescriptorSetLayout, Escape, Filter, Handle, ImageView, ImageViewInfo,
+            Sampler, SamplerInfo, ViewKind, WrapMode,
+        },
+        shader::{Shader, SpirvShader},
+    },
+    types::Backend,
+    util::simple_shader_set,
+};
+
+/// The target in which the scene is rendered when the backdrop blur is enabled.
+///
+/// The plugins drawing the game, e.g. `RenderFlat2D`, must draw in it instead of `Target::Main`.
+pub const SCENE_TARGET: Target = Target::Custom("iced_scene");
+pub(crate) const BLUR_X_TARGET: Target = Target::Custom("iced_blur_x");
+pub(crate) const BLUR_Y_TARGET: Target = Target::Custom("iced_blur_y");
+
+lazy_static::lazy_static! {
+    static ref FULLSCREEN_VERTEX: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../shaders/compiled/fullscreen.vert.spv"),
+        ShaderStageFlags::VERTEX,
+        "main",
+    ).unwrap();
+
+    static ref BLUR_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../shaders/compiled/blur.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
+
+    static ref COMPOSITE_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../shaders/compiled/composite.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
+}
+
+/// How an image produced by another target is read by a fragment shader
+pub(crate) fn sampled_image_access() -> ImageAccess {
+    ImageAccess {
+        access: hal::image::Access::SHADER_READ,
+        usage: hal::image::Usage::SAMPLED,
+        layout: hal::image::Layout::ShaderReadOnlyOptimal,
+        stages: pso::PipelineStage::FRAGMENT_SHADER,
+    }
+}
+
+/// An image of the graph, bound as a `sampler2D` to fragment shaders
+#[derive(Debug)]
+pub(crate) struct SampledImage<B: Backend> {
+    layout: Handle<DescriptorSetLayout<B>>,
+    set: Escape<DescriptorSet<B>>,
+    _view: Escape<ImageView<B>>,
+    _sampler: Handle<Sampler<B>>,
+}
+
+impl<B: Backend> SampledImage<B> {
+    pub(crate) fn new(
+        ctx: &GraphContext<B>,
+        factory: &Factory<B>,
+        node_image: &NodeImage,
+    ) -> Result<Self, failure::Error> {
+        let image = ctx
+            .get_image(node_image.id)
+            .ok_or_else(|| failure::format_err!("The image to sample is missing from the graph"))?;
+        let view = factory.create_image_view(
+            image.clone(),
+            ImageViewInfo {
+                view_kind: ViewKind::D2,
+                format: image.format(),
+                swizzle: hal::format::Swizzle::NO,
+                range: node_image.range.clone(),
+            },
+        )?;
+        let sampler = factory.get_sampler(SamplerInfo::new(Filter::Linear, WrapMode::Clamp))?;
+        let layout = factory.create_descriptor_set_layout(vec![pso::DescriptorSetLayoutBinding {
+            binding: 0,
+            ty: pso::DescriptorType::CombinedImageSampler,
+            count: 1,
+            stage_flags: ShaderStageFlags::FRAGMENT,
+            immutable_samplers: false,
+        }])?;
+        let set = factory.create_descriptor_set(layout.clone())?;
+        unsafe {
+            factory.device().write_descriptor_sets(Some(pso::DescriptorSetWrite {
+                set: set.raw(),
+                binding: 0,
+                array_offset: 0,
+                descriptors: Some(pso::Descriptor::CombinedImageSampler(
+                    view.raw(),
+                    hal::image::Layout::ShaderReadOnlyOptimal,
+                    sampler.raw(),
+                )),
+            }));
+        }
+        Ok(SampledImage {
+            layout,
+            set,
+            _view: view,
+            _sampler: sampler,
+        })
+    }
+
+    pub(crate) fn raw_layout(&self) -> &B::DescriptorSetLayout {
+        self.layout.raw()
+    }
+
+    pub(crate) fn bind(
+        &self,
+        pipeline_layout: &B::PipelineLayout,
+        set_id: u32,
+        encoder: &mut RenderPassEncoder<'_, B>,
+    ) {
+        unsafe {
+            encoder.bind_graphics_descriptor_sets(
+                pipeline_layout,
+                set_id,
+                Some(self.set.raw()),
+                std::iter::empty(),
+            );
+        }
+    }
+}
+
+/// Creates a pipeline drawing a triangle covering the target, with the given fragment shader
+fn fullscreen_pipeline<B: Backend>(
+    factory: &Factory<B>,
+    subpass: hal::pass::Subpass<'_, B>,
+    framebuffer_width: u32,
+    framebuffer_height: u32,
+    fragment: &SpirvShader,
+    layout: &B::PipelineLayout,
+) -> Result<B::GraphicsPipeline, failure::Error> {
+    let shader_vertex = unsafe {
+        FULLSCREEN_VERTEX
+            .module(factory)
+            .expect("Failed to create fullscreen_vertex module")
+    };
+    let shader_fragment = unsafe {
+        fragment
+            .module(factory)
+            .expect("Failed to create backdrop fragment module")
+    };
+
+    let pipes = PipelinesBuilder::new()
+        .with_pipeline(
+            PipelineDescBuilder::new()
+                .with_vertex_desc(&[])
+                .with_input_assembler(pso::InputAssemblerDesc::new(hal::Primitive::TriangleList))
+                .with_shaders(simple_shader_set(&shader_vertex, Some(&shader_fragment)))
+                .with_layout(layout)
+                .with_subpass(subpass)
+                .with_framebuffer_size(framebuffer_width, framebuffer_height)
+                .with_blend_targets(vec![pso::ColorBlendDesc {
+                    mask: pso::ColorMask::ALL,
+                    blend: None,
+                }]),
+        )
+        .build(factory, None);
+
+    unsafe {
+        factory.destroy_shader_module(shader_vertex);
+        factory.destroy_shader_module(shader_fragment);
+    }
+
+    pipes.map(|mut pipes| pipes.remove(0))
+}
+
+/// Blurs the image of another target along one axis
+#[derive(Debug)]
+pub(crate) struct BlurPassDesc {
+    /// `[1., 0.]` for a horizontal blur, `[0., 1.]` for a vertical one
+    pub direction: [f32; 2],
+    /// The standard deviation of the blur, in pixels of the window
+    pub sigma: f32,
+}
+
+impl<B: Backend> RenderGroupDesc<B, World> for BlurPassDesc {
+    fn images(&self) -> Vec<ImageAccess> {
+        vec![sampled_image_access()]
+    }
+
+    fn depth(&self) -> bool {
+        false
+    }
+
+    fn build(
+        self,
+        ctx: &GraphContext<B>,
+        factory: &mut Factory<B>,
+        _queue: QueueId,
+        _world: &World,
+        framebuffer_width: u32,
+        framebuffer_height: u32,
+        subpass: hal::pass::Subpass<'_, B>,
+        _buffers: Vec<NodeBuffer>,
+        images: Vec<NodeImage>,
+    ) -> Result<Box<dyn RenderGroup<B, World>>, failure::Error> {
+        let source = SampledImage::new(ctx, factory, &images[0])?;
+        let pipeline_layout = unsafe {
+            factory.device().create_pipeline_layout(
+                vec![source.raw_layout()],
+                vec![(ShaderStageFlags::FRAGMENT, 0..3)],
+            )
+        }?;
+        let pipeline = fullscreen_pipeline(
+            factory,
+            subpass,
+            framebuffer_width,
+            framebuffer_height,
+            &BLUR_FRAGMENT,
+            &pipeline_layout,
+        );
+        let pipeline = match pipeline {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                unsafe {
+                    factory.device().destroy_pipeline_layout(pipeline_layout);
+                }
+                return Err(e);
+            }
+        };
+
+        // The targets are half the size of the window, the blur is scaled down accordingly.
+        // Taps are spaced out for wide blurs, the linear filtering filling the gaps.
+        let sigma = self.sigma / 2.;
+        let spacing = (sigma / 3.).max(1.);
+        let constants = [
+            self.direction[0] * spacing / framebuffer_width as f32,
+            self.direction[1] * spacing / framebuffer_height as f32,
+            (sigma / spacing).max(0.5),
+        ];
+
+        Ok(Box::new(FullscreenPass {
+            pipeline,
+            pipeline_layout,
+            source,
+            constants: constants.iter().map(|value| value.to_bits()).collect(),
+        }))
+    }
+}
+
+/// Copies the image of another target, e.g. the scene in the window
+#[derive(Debug, Default)]
+pub(crate) struct CompositePassDesc;
+
+impl<B: Backend> RenderGroupDesc<B, World> for CompositePassDesc {
+    fn images(&self) -> Vec<ImageAccess> {
+        vec![sampled_image_access()]
+    }
+
+    fn build(
+        self,
+        ctx: &GraphContext<B>,
+        factory: &mut Factory<B>,
+        _queue: QueueId,
+        _world: &World,
+        framebuffer_width: u32,
+        framebuffer_height: u32,
+        subpass: hal::pass::Subpass<'_, B>,
+        _buffers: Vec<NodeBuffer>,
+        images: Vec<NodeImage>,
+    ) -> Result<Box<dyn RenderGroup<B, World>>, failure::Error> {
+        let source = SampledImage::new(ctx, factory, &images[0])?;
+        let pipeline_layout = unsafe {
+            factory
+                .device()
+                .create_pipeline_layout(vec![source.raw_layout()], None as Option<(_, _)>)
+        }?;
+        let pipeline = fullscreen_pipeline(
+            factory,
+            subpass,
+            framebuffer_width,
+            framebuffer_height,
+            &COMPOSITE_FRAGMENT,
+            &pipeline_layout,
+        );
+        match pipeline {
+            Ok(pipeline) => Ok(Box::new(FullscreenPass {
+                pipeline,
+                pipeline_layout,
+                source,
+                constants: vec![],
+            })),
+            Err(e) => {
+                unsafe {
+                    factory.device().destroy_pipeline_layout(pipeline_layout);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Draws a triangle covering the target, sampling the image of another target
+#[derive(Debug)]
+struct FullscreenPass<B: Backend> {
+    pipeline: B::GraphicsPipeline,
+    pipeline_layout: B::PipelineLayout,
+    source: SampledImage<B>,
+    /// Pushed to the fragment shader
+    constants: Vec<u32>,
+}
+
+impl<B: Backend> RenderGroup<B, World> for FullscreenPass<B> {
+    fn prepare(
+        &mut self,
+        _factory: &Factory<B>,
+        _queue: QueueId,
+        _index: usize,
+        _subpass: hal::pass::Subpass<'_, B>,
+        _world: &World,
+    ) -> PrepareResult {
+        PrepareResult::DrawReuse
+    }
+
+    fn draw_inline(
+        &mut self,
+        mut encoder: RenderPassEncoder<'_, B>,
+        _index: usize,
+        _subpass: hal::pass::Subpass<'_, B>,
+        _world: &World,
+    ) {
+        encoder.bind_graphics_pipeline(&self.pipeline);
+        self.source.bind(&self.pipeline_layout, 0, &mut encoder);
+        unsafe {
+            if !self.constants.is_empty() {
+                encoder.push_constants(
+                    &self.pipeline_layout,
+                    ShaderStageFlags::FRAGMENT,
+                    0,
+                    &self.constants,
+                );
+            }
+            encoder.draw(0..3, 0..1);
+        }
+    }
+
+    fn dispose(self: Box<Self>, factory: &mut Factory<B>, _world: &World) {
+        unsafe {
+            factory.device().destroy_graphics_pipeline(self.pipeline);
+            factory
+                .device()
+                .destroy_pipeline_layout(self.pipeline_layout);
+        }
+    }
+}
@@ -1,18 +1,123 @@
 use amethyst::assets::AssetStorage;
-use amethyst::ecs::{Read, WriteExpect};
+use amethyst::ecs::{Read, Write, WriteExpect};
 use amethyst::renderer::SpriteSheet;
 use iced_native::renderer::Renderer;
+use iced_native::input::mouse;
+use iced_native::widget::text::Renderer as TextRenderer;
+use iced_native::{Color, Event, Font, Point, Rectangle, Size};
 
+use crate::animation::{Animatable, Motion, StateTransition, Tween};
 use crate::primitive::AmethystIcedPrimitive;
 use crate::IcedGlyphBrush;
-use crate::resources::FontCache;
+use crate::resources::{FontCache, IcedAnimations, MouseCursor};
+use crate::widget::PaneGridStyle;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::Duration;
 
 pub struct IcedRenderer<'a> {
     pub textures: Read<'a, AssetStorage<SpriteSheet>>,
     pub glyph_brush: RefCell<WriteExpect<'a, IcedGlyphBrush>>,
     pub font_cache: Read<'a, FontCache>,
+    animations: Option<RefCell<Write<'a, IcedAnimations>>>,
+    mouse_cursor: Cell<MouseCursor>,
+    /// The focusable widgets, with the overlay layer they are drawn in
+    focusables: RefCell<Vec<(Rectangle, usize)>>,
+    draggables: RefCell<Vec<Rectangle>>,
+    ids: RefCell<HashMap<String, Rectangle>>,
+    mock_measurer: Option<MockMeasurer>,
+    viewport: Size,
+    overlays: RefCell<OverlayState>,
+    new_overlays: RefCell<Vec<Rectangle>>,
+    /// How many overlays hold the widgets being drawn
+    overlay_depth: Cell<usize>,
+    /// The text properties inherited by the widgets being laid out
+    layout_text: Cell<TextDefaults>,
+    held_key: RefCell<Option<Event>>,
+    pane_grid_style: PaneGridStyle,
+}
+
+/// Measures texts as if every character had the same size, relative to the
+/// font size. This keeps layouts identical whatever the font and platform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MockMeasurer {
+    pub char_width: f32,
+    pub line_height: f32,
+}
+
+impl Default for MockMeasurer {
+    fn default() -> Self {
+        MockMeasurer {
+            char_width: 0.5,
+            line_height: 1.,
+        }
+    }
+}
+
+impl MockMeasurer {
+    pub fn measure(&self, content: &str, size: u16, bounds: Size) -> (f32, f32) {
+        let size = size as f32;
+        let (lines, longest) = content
+            .lines()
+            .fold((0, 0), |(lines, longest), line| {
+                (lines + 1, longest.max(line.chars().count()))
+            });
+        (
+            (longest as f32 * size * self.char_width).min(bounds.width),
+            (lines.max(1) as f32 * size * self.line_height).min(bounds.height),
+        )
+    }
+}
+
+/// Where the cursor is hidden from the widgets lying under an overlay
+const MASKED_CURSOR: Point = Point {
+    x: -100_000.,
+    y: -100_000.,
+};
+
+/// Sent instead of the keyboard events held for the topmost overlay, see
+/// `IcedRenderer::overlay_event`. Widgets ignore it, and it doesn't move the cursor.
+const KEY_PLACEHOLDER: Event = Event::Mouse(mouse::Event::CursorEntered);
+
+/// The overlays drawn during the last frame, which capture the cursor & keyboard
+/// during the next one. Kept by the systems between frames.
+#[derive(Debug, Clone)]
+pub(crate) struct OverlayState {
+    regions: Vec<Rectangle>,
+    cursor: Point,
+    masked: bool,
+}
+
+impl Default for OverlayState {
+    fn default() -> Self {
+        OverlayState {
+            regions: vec![],
+            cursor: MASKED_CURSOR,
+            masked: false,
+        }
+    }
+}
+
+impl OverlayState {
+    fn covers(&self, point: Point) -> bool {
+        self.regions.iter().any(|region| region.contains(point))
+    }
+
+    /// Overlays are registered in the order they are drawn, so the last one is on top
+    fn topmost(&self) -> Option<Rectangle> {
+        self.regions.last().copied()
+    }
+
+    /// Whether one of the overlays drawn above the given one covers the point
+    fn covers_above(&self, overlay: Rectangle, point: Point) -> bool {
+        match self.regions.iter().position(|region| *region == overlay) {
+            Some(index) => self.regions[index + 1..]
+                .iter()
+                .any(|region| region.contains(point)),
+            None => false,
+        }
+    }
 }
 
 impl<'a> IcedRenderer<'a> {
@@ -25,11 +130,567 @@ impl<'a> IcedRenderer<'a> {
             textures,
             glyph_brush: RefCell::new(glyph_brush),
             font_cache,
+            animations: None,
+            mouse_cursor: Cell::new(MouseCursor::Idle),
+            focusables: RefCell::new(vec![]),
+            draggables: RefCell::new(vec![]),
+            ids: RefCell::new(HashMap::new()),
+            mock_measurer: None,
+            viewport: Size::new(f32::INFINITY, f32::INFINITY),
+            overlays: RefCell::new(OverlayState::default()),
+            new_overlays: RefCell::new(vec![]),
+            overlay_depth: Cell::new(0),
+            layout_text: Cell::new(Defaults::default().text),
+            held_key: RefCell::new(None),
+            pane_grid_style: PaneGridStyle::default(),
+        }
+    }
+
+    /// Animates the widgets using the given resource, see `IcedAnimations`.
+    ///
+    /// Without it, animated values jump straight to their target.
+    pub fn with_animations(mut self, animations: Write<'a, IcedAnimations>) -> Self {
+        self.animations = Some(RefCell::new(animations));
+        self
+    }
+
+    pub(crate) fn with_overlays(self, overlays: OverlayState) -> Self {
+        self.overlays.replace(overlays);
+        self
+    }
+
+    /// Registers an area drawn above the UI, see `AmethystIcedPrimitive::Overlay`.
+    ///
+    /// During the next frame, the widgets lying under it will see the cursor
+    /// as if it were out of the window whenever it is over the area. While it is
+    /// the topmost overlay, they won't receive any keyboard event either. The widget
+    /// owning the overlay should use `cursor_position` and `overlay_event` instead.
+    pub fn register_overlay(&self, bounds: Rectangle) {
+        self.new_overlays.borrow_mut().push(bounds);
+    }
+
+    /// Registers an overlay, then wraps what is drawn by `draw` in an Overlay
+    /// primitive, keeping track of the layer of the focusable widgets it holds
+    pub(crate) fn draw_overlay(
+        &mut self,
+        bounds: Rectangle,
+        draw: impl FnOnce(&mut Self) -> AmethystIcedPrimitive,
+    ) -> AmethystIcedPrimitive {
+        self.register_overlay(bounds);
+        self.overlay_depth.set(self.overlay_depth.get() + 1);
+        let primitive = draw(self);
+        self.overlay_depth.set(self.overlay_depth.get() - 1);
+        AmethystIcedPrimitive::Overlay(Box::new(primitive))
+    }
+
+    /// The position of the cursor, even when it is hidden by an overlay
+    pub fn cursor_position(&self) -> Point {
+        self.overlays.borrow().cursor
+    }
+
+    /// The position of the cursor for the widgets drawn in the overlay registered
+    /// with the given bounds, hidden whenever an overlay drawn above it covers the
+    /// cursor, e.g. the menu of a PickList opened in a Modal.
+    pub fn overlay_cursor(&self, overlay: Rectangle) -> Point {
+        let overlays = self.overlays.borrow();
+        if overlays.covers_above(overlay, overlays.cursor) {
+            MASKED_CURSOR
+        } else {
+            overlays.cursor
+        }
+    }
+
+    /// Restores an event hidden by the overlay registered with the given bounds:
+    /// the position of the cursor as seen from the overlay, see `overlay_cursor`,
+    /// and the keyboard events when the overlay is the topmost one.
+    pub fn overlay_event(&self, overlay: Rectangle, event: Event) -> Event {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let cursor = self.overlay_cursor(overlay);
+                Event::Mouse(mouse::Event::CursorMoved {
+                    x: cursor.x,
+                    y: cursor.y,
+                })
+            }
+            Event::Mouse(mouse::Event::CursorEntered)
+                if self.overlays.borrow().topmost() == Some(overlay) =>
+            {
+                self.held_key.borrow().clone().unwrap_or(KEY_PLACEHOLDER)
+            }
+            event => event,
+        }
+    }
+
+    /// The events to send before any other during this frame, moving the cursor
+    /// out of the widgets which were just covered by an overlay (or back in)
+    pub(crate) fn overlay_events(&self) -> Vec<Event> {
+        let mut overlays = self.overlays.borrow_mut();
+        let masked = overlays.covers(overlays.cursor);
+        if masked == overlays.masked {
+            return vec![];
+        }
+        overlays.masked = masked;
+        let cursor = if masked { MASKED_CURSOR } else { overlays.cursor };
+        vec![Event::Mouse(mouse::Event::CursorMoved {
+            x: cursor.x,
+            y: cursor.y,
+        })]
+    }
+
+    /// Keeps track of the cursor, hiding it when it is over an overlay, and holds
+    /// the keyboard events back for the topmost overlay.
+    ///
+    /// Each event must be sent to the UI before the next one is captured.
+    pub(crate) fn capture_event(&self, event: Event) -> Event {
+        self.held_key.replace(None);
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { x, y }) => {
+                let mut overlays = self.overlays.borrow_mut();
+                overlays.cursor = Point::new(x, y);
+                overlays.masked = overlays.covers(overlays.cursor);
+                let cursor = if overlays.masked {
+                    MASKED_CURSOR
+                } else {
+                    overlays.cursor
+                };
+                Event::Mouse(mouse::Event::CursorMoved {
+                    x: cursor.x,
+                    y: cursor.y,
+                })
+            }
+            Event::Keyboard(_) if self.overlays.borrow().topmost().is_some() => {
+                self.held_key.replace(Some(event));
+                KEY_PLACEHOLDER
+            }
+            event => event,
+        }
+    }
+
+    /// Returns the current overlays, when the UI was updated without being drawn
+    pub(crate) fn overlays(&self) -> OverlayState {
+        self.overlays.borrow().clone()
+    }
+
+    /// Returns the overlays registered while drawing, to be given to the next frame
+    pub(crate) fn take_overlays(&self) -> OverlayState {
+        let mut overlays = self.overlays.borrow().clone();
+        overlays.regions = self.new_overlays.replace(vec![]);
+        overlays
+    }
+
+    /// Measures texts with the given measurer instead of the glyph brush,
+    /// so that layouts don't depend on font rasterisation.
+    pub fn with_mock_measurer(mut self, measurer: MockMeasurer) -> Self {
+        self.mock_measurer = Some(measurer);
+        self
+    }
+
+    /// Sets the size of the window, used to keep popups on screen
+    pub fn with_viewport(mut self, viewport: Size) -> Self {
+        self.viewport = viewport;
+        self
+    }
+
+    /// Sets the style of the PaneGrids, whose renderer trait has no style of its own
+    pub fn with_pane_grid_style(mut self, style: PaneGridStyle) -> Self {
+        self.pane_grid_style = style;
+        self
+    }
+
+    pub(crate) fn pane_grid_style(&self) -> &PaneGridStyle {
+        &self.pane_grid_style
+    }
+
+    /// The size of the window the UI is drawn in
+    pub fn viewport(&self) -> Size {
+        self.viewport
+    }
+
+    pub(crate) fn mock_measurer(&self) -> Option<&MockMeasurer> {
+        self.mock_measurer.as_ref()
+    }
+
+    /// Lays out the children of a widget which sets the font or size of its
+    /// texts, so they are measured the way they will be drawn.
+    pub(crate) fn layout_with_text<T>(
+        &self,
+        font: Option<Font>,
+        size: Option<u16>,
+        layout: impl FnOnce() -> T,
+    ) -> T {
+        let parent = self.layout_text.get();
+        self.layout_text.set(parent.with(font, size));
+        let node = layout();
+        self.layout_text.set(parent);
+        node
+    }
+
+    /// The text properties inherited by the widget being laid out
+    pub(crate) fn layout_text(&self) -> TextDefaults {
+        self.layout_text.get()
+    }
+
+    /// Registers an interactive widget which can be focused & activated
+    /// without a mouse, see `FocusManager`.
+    pub fn register_focusable(&self, bounds: Rectangle) {
+        let layer = self.overlay_depth.get();
+        self.focusables.borrow_mut().push((bounds, layer));
+    }
+
+    pub(crate) fn take_focusables(&self) -> Vec<(Rectangle, usize)> {
+        self.focusables.replace(vec![])
+    }
+
+    /// Registers a widget which is used by dragging it with the left button,
+    /// like a Slider. Touch drags starting on it press the left button instead
+    /// of scrolling, see `TouchConfig::drag_scrolls`.
+    pub fn register_draggable(&self, bounds: Rectangle) {
+        self.draggables.borrow_mut().push(bounds);
+    }
+
+    pub(crate) fn take_draggables(&self) -> Vec<Rectangle> {
+        self.draggables.replace(vec![])
+    }
+
+    /// Records the bounds of a widget tagged with an id, see `Tagged`
+    pub fn register_id(&self, id: &str, bounds: Rectangle) {
+        self.ids.borrow_mut().insert(id.to_string(), bounds);
+    }
+
+    pub(crate) fn take_ids(&self) -> HashMap<String, Rectangle> {
+        self.ids.replace(HashMap::new())
+    }
+
+    /// Animates a value towards the target, see `IcedAnimations::animate`.
+    ///
+    /// The key must identify the widget, e.g. through `IcedAnimations::key`.
+    pub fn animate<T: Animatable>(&self, key: u64, initial: T, target: T, tween: Tween) -> T {
+        match &self.animations {
+            Some(animations) => animations.borrow_mut().animate(key, initial, target, tween),
+            None => target,
+        }
+    }
+
+    /// Eases the primitive of a widget towards its look in the current state,
+    /// see `StateTransition`.
+    ///
+    /// The key must identify the widget, e.g. through `IcedAnimations::widget_key`,
+    /// and the primitive is scaled around the center of the bounds.
+    pub fn animate_state(
+        &self,
+        key: u64,
+        bounds: Rectangle,
+        transition: Option<&StateTransition>,
+        is_hovered: bool,
+        is_pressed: bool,
+        primitive: AmethystIcedPrimitive,
+    ) -> AmethystIcedPrimitive {
+        let transition = match transition {
+            Some(transition) => transition,
+            None => return primitive,
+        };
+        let target = if is_pressed {
+            transition.pressed
+        } else if is_hovered {
+            transition.hovered
+        } else {
+            Motion::default()
+        };
+        let motion = self.animate(key, Motion::default(), target, transition.tween);
+        if motion == Motion::default() {
+            return primitive;
+        }
+        let center = Point::new(bounds.x + bounds.width / 2., bounds.y + bounds.height / 2.);
+        primitive
+            .scale(center, motion.scale)
+            .translate(motion.offset)
+            .fade(motion.opacity)
+    }
+
+    /// Forgets the animations which weren't drawn during the frame, see `IcedAnimations::end_frame`
+    pub(crate) fn end_animation_frame(&self) {
+        if let Some(animations) = &self.animations {
+            animations.borrow_mut().end_frame();
         }
     }
+
+    /// Returns for how long the condition has been true, see `IcedAnimations::active_for`.
+    ///
+    /// Without animations, the condition is never true for any time.
+    pub fn active_for(&self, key: u64, active: bool) -> Option<Duration> {
+        match &self.animations {
+            Some(animations) => animations.borrow_mut().active_for(key, active),
+            None if active => Some(Duration::from_secs(0)),
+            None => None,
+        }
+    }
+
+    /// Requests the window cursor to change while drawing a widget.
+    ///
+    /// Widgets are drawn from parent to children, so the last request wins.
+    pub fn request_cursor(&self, cursor: MouseCursor) {
+        self.mouse_cursor.set(cursor);
+    }
+
+    /// The cursor requested by the widgets drawn so far
+    pub fn mouse_cursor(&self) -> MouseCursor {
+        self.mouse_cursor.get()
+    }
 }
 
 impl<'a> Renderer for IcedRenderer<'a> {
     type Output = AmethystIcedPrimitive;
-    type Defaults = ();
+    type Defaults = Defaults;
+}
+
+/// Values inherited by the children of a widget, unless they override them.
+#[derive(Debug, Clone, Copy)]
+pub struct Defaults {
+    pub text: TextDefaults,
+}
+
+/// Text properties inherited from the parent widgets.
+///
+/// A Text created without a font or size, i.e. with `Font::Default` and the
+/// default size, uses the ones of its closest parent container.
+#[derive(Debug, Clone, Copy)]
+pub struct TextDefaults {
+    pub color: Color,
+    pub font: Font,
+    pub size: u16,
+}
+
+impl TextDefaults {
+    /// Returns a copy of these TextDefaults, overridden where a value is given
+    pub fn with(self, font: Option<Font>, size: Option<u16>) -> Self {
+        TextDefaults {
+            font: font.unwrap_or(self.font),
+            size: size.unwrap_or(self.size),
+            ..self
+        }
+    }
+
+    /// Replaces the font & size a Text was created with by the inherited ones,
+    /// unless they were set explicitly
+    pub(crate) fn inherit(&self, font: Font, size: u16) -> (Font, u16) {
+        let font = match font {
+            Font::Default => self.font,
+            font => font,
+        };
+        let size = if size == <IcedRenderer as TextRenderer>::DEFAULT_SIZE {
+            self.size
+        } else {
+            size
+        };
+        (font, size)
+    }
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Defaults {
+            text: TextDefaults {
+                color: Color::WHITE,
+                font: Font::Default,
+                size: <IcedRenderer as TextRenderer>::DEFAULT_SIZE,
+            },
+        }
+    }
+}
+
+impl Defaults {
+    /// Returns a copy of these Defaults with another text color
+    pub fn with_text_color(self, color: Color) -> Self {
+        Defaults {
+            text: TextDefaults { color, ..self.text },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use crate::widget::{Button, Column};
+    use crate::{Element, Modal, PickList, PickListState, Sandbox, Tagged, Text};
+    use iced_native::button;
+    use iced_native::input::{keyboard, ButtonState};
+    use iced_native::{layout, Clipboard, Hasher, Layout, Length, Widget};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Picked(&'static str),
+        Typed(&'static str, char),
+        Pressed(&'static str, keyboard::KeyCode),
+        Confirmed,
+    }
+
+    /// Sends the characters and the pressed keys it receives, with its name
+    struct KeyListener(&'static str);
+
+    impl<'r> Widget<Message, IcedRenderer<'r>> for KeyListener {
+        fn width(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn height(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn layout(&self, _renderer: &IcedRenderer<'r>, _limits: &layout::Limits) -> layout::Node {
+            layout::Node::new(Size::ZERO)
+        }
+
+        fn draw(
+            &self,
+            _renderer: &mut IcedRenderer<'r>,
+            _defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+        ) -> <IcedRenderer<'r> as Renderer>::Output {
+            AmethystIcedPrimitive::None
+        }
+
+        fn hash_layout(&self, _state: &mut Hasher) {}
+
+        fn on_event(
+            &mut self,
+            event: Event,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+            messages: &mut Vec<Message>,
+            _renderer: &IcedRenderer<'r>,
+            _clipboard: Option<&dyn Clipboard>,
+        ) {
+            match event {
+                Event::Keyboard(keyboard::Event::CharacterReceived(c)) => {
+                    messages.push(Message::Typed(self.0, c))
+                }
+                Event::Keyboard(keyboard::Event::Input {
+                    state: ButtonState::Pressed,
+                    key_code,
+                    ..
+                }) => messages.push(Message::Pressed(self.0, key_code)),
+                _ => {}
+            }
+        }
+    }
+
+    fn pick_list(state: &mut PickListState) -> Element<Message> {
+        Tagged::new(
+            "pick",
+            PickList::new(state, vec!["Sword", "Bow"], None, Message::Picked).placeholder("Weapon"),
+        )
+        .into()
+    }
+
+    #[derive(Default)]
+    struct Form {
+        weapon: PickListState,
+    }
+
+    impl Sandbox for Form {
+        type UIMessage = Message;
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<Message> {
+            Column::new()
+                .push(pick_list(&mut self.weapon))
+                .push(Element::new(KeyListener("form")))
+                .into()
+        }
+    }
+
+    #[test]
+    fn keys_only_reach_the_open_pick_list() {
+        let mut harness = IcedTestHarness::new(Form::default());
+        harness.type_text("a");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![Message::Typed("form", 'a')]
+        );
+
+        harness.click_id("pick");
+        harness.draw();
+        harness.type_text("b");
+        assert_eq!(harness.take_ui_messages(), vec![]);
+
+        // Escape closes the menu without reaching the widgets beneath
+        harness.press_key(keyboard::KeyCode::Escape);
+        assert_eq!(harness.take_ui_messages(), vec![]);
+        harness.draw();
+        harness.type_text("c");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![Message::Typed("form", 'c')]
+        );
+    }
+
+    #[derive(Default)]
+    struct Dialog {
+        weapon: PickListState,
+        confirm: button::State,
+    }
+
+    impl Sandbox for Dialog {
+        type UIMessage = Message;
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<Message> {
+            let content = Column::new()
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .push(Element::new(KeyListener("content")));
+            let confirm = Button::new(&mut self.confirm, Text::new("Ok").size(20))
+                .on_press(Message::Confirmed);
+            let dialog = Column::new()
+                .push(pick_list(&mut self.weapon))
+                .push(Tagged::new("confirm", confirm))
+                .push(Element::new(KeyListener("dialog")));
+            Modal::new(content, dialog).visible(true).into()
+        }
+    }
+
+    #[test]
+    fn keys_reach_the_topmost_overlay() {
+        let mut harness = IcedTestHarness::new(Dialog::default());
+        harness.draw();
+        harness.type_text("a");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![Message::Typed("dialog", 'a')]
+        );
+
+        // The menu opens above the dialog
+        harness.click_id("pick");
+        harness.draw();
+        harness.type_text("b");
+        harness.press_key(keyboard::KeyCode::Escape);
+        assert_eq!(harness.take_ui_messages(), vec![]);
+
+        harness.draw();
+        harness.type_text("c");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![Message::Typed("dialog", 'c')]
+        );
+    }
+
+    #[test]
+    fn menus_opened_in_a_dialog_hide_the_widgets_beneath() {
+        let mut harness = IcedTestHarness::new(Dialog::default());
+        let confirm = harness.bounds_of("confirm").unwrap();
+        let corner = Point::new(confirm.x + 1., confirm.y + 1.);
+
+        // The menu opens right below the header, over the button
+        harness.click_id("pick");
+        harness.draw();
+        harness.click(corner);
+        assert_eq!(harness.take_ui_messages(), vec![Message::Picked("Sword")]);
+
+        // Once the menu is closed, the same click reaches the button
+        harness.draw();
+        harness.click(corner);
+        assert_eq!(harness.take_ui_messages(), vec![Message::Confirmed]);
+    }
 }
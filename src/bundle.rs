@@ -5,36 +5,72 @@ use amethyst::{
     Error,
     ui::FontAsset,
     assets::Processor,
+    input::{BindingTypes, StringBindings},
 };
 use glyph_brush::GlyphBrushBuilder;
 
 use crate::{
+    application::{Application, ApplicationData, ApplicationSandbox},
+    prefab::UiPrefab,
     primitive::IcedPrimitives,
-    sandbox::Sandbox,
-    systems::{IcedDrawSystem, IcedInteropSystem, LoadFontToCacheSystem},
+    sandbox::{Sandbox, SandboxData},
+    systems::{
+        DataView, FocusActions, IcedCommandSystem, IcedCursorSystem, IcedDrawSystem,
+        IcedFocusSystem, IcedInteropSystem, LoadFontToCacheSystem, PlainView, ViewSource,
+    },
     IcedGlyphBrush,
 };
 
-pub struct IcedBundle<S: Sandbox> {
+/// Adds the systems building and drawing the UI, V deciding how its view is built
+fn add_draw_systems<S: Sandbox, V: for<'c> ViewSource<'c, S>>(
+    dispatcher: &mut DispatcherBuilder<'_, '_>,
+) {
+    dispatcher.add(IcedDrawSystem::<S, V>::default(), "iced_draw", &[]);
+    dispatcher.add(IcedCursorSystem::default(), "iced_cursor", &["iced_draw"]);
+}
+
+pub struct IcedBundle<S: Sandbox, T: BindingTypes = StringBindings> {
     _sandbox: std::marker::PhantomData<S>,
+    focus_actions: Option<FocusActions<T>>,
+    draw_systems: fn(&mut DispatcherBuilder<'_, '_>),
 }
 
-impl<S: Sandbox> Default for IcedBundle<S> {
+impl<S: Sandbox, T: BindingTypes> Default for IcedBundle<S, T> {
     fn default() -> Self {
         IcedBundle::new()
     }
 }
 
-impl<S: Sandbox> IcedBundle<S> {
+impl<S: Sandbox, T: BindingTypes> IcedBundle<S, T> {
     /// Creates a new IcedBundle containing a Sandboxed application
     pub fn new() -> Self {
         IcedBundle {
             _sandbox: std::marker::PhantomData,
+            focus_actions: None,
+            draw_systems: add_draw_systems::<S, PlainView>,
         }
     }
+
+    /// Builds the UI with `SandboxData::view_with_data` instead of `Sandbox::view`
+    pub fn with_view_data(mut self) -> Self
+    where
+        S: for<'c> SandboxData<'c>,
+    {
+        self.draw_systems = add_draw_systems::<S, DataView>;
+        self
+    }
+
+    /// Moves the focus between widgets with the given input actions,
+    /// see `IcedFocusSystem`.
+    ///
+    /// The InputBundle must be added before this bundle.
+    pub fn with_focus_actions(mut self, actions: FocusActions<T>) -> Self {
+        self.focus_actions = Some(actions);
+        self
+    }
 }
 
-impl<'a, 'b, S: Sandbox> SystemBundle<'a, 'b> for IcedBundle<S> {
+impl<'a, 'b, S: Sandbox, T: BindingTypes> SystemBundle<'a, 'b> for IcedBundle<S, T> {
     fn build(
         self,
         world: &mut World,
@@ -48,17 +84,27 @@ impl<'a, 'b, S: Sandbox> SystemBundle<'a, 'b> for IcedBundle<S> {
         world.insert::<IcedGlyphBrush>(GlyphBrushBuilder::using_font_bytes(square_ttf).build());
 
         // Adds Iced-related systems
-        dispatcher.add(IcedInteropSystem::<S>::default(), "iced_interop", &[]);
-        dispatcher.add(
-            IcedDrawSystem::<S>::default(),
-            "iced_draw",
-            &["iced_interop"],
-        );
+        if let Some(actions) = self.focus_actions {
+            dispatcher.add(
+                IcedFocusSystem::new(actions),
+                "iced_focus",
+                &["input_system"],
+            );
+        }
+        (self.draw_systems)(dispatcher);
+        // Subscriptions are read from the whole World, so the messages are handled
+        // on the main thread, once the parallel systems are done
+        dispatcher.add_thread_local(IcedInteropSystem::<S>::default());
         dispatcher.add(
             Processor::<FontAsset>::new(),
             "iced_font_processor",
             &[],
         );
+        dispatcher.add(
+            Processor::<UiPrefab>::new(),
+            "iced_prefab_processor",
+            &[],
+        );
         dispatcher.add(
             LoadFontToCacheSystem::default(),
             "iced_load_font_to_cache",
@@ -67,3 +113,63 @@ impl<'a, 'b, S: Sandbox> SystemBundle<'a, 'b> for IcedBundle<S> {
         Ok(())
     }
 }
+
+/// The IcedBundle of an Application, which also runs its commands
+pub struct IcedApplicationBundle<A: Application, T: BindingTypes = StringBindings> {
+    _application: std::marker::PhantomData<A>,
+    focus_actions: Option<FocusActions<T>>,
+    draw_systems: fn(&mut DispatcherBuilder<'_, '_>),
+}
+
+impl<A: Application, T: BindingTypes> Default for IcedApplicationBundle<A, T> {
+    fn default() -> Self {
+        IcedApplicationBundle::new()
+    }
+}
+
+impl<A: Application, T: BindingTypes> IcedApplicationBundle<A, T> {
+    /// Creates a new IcedApplicationBundle. The ApplicationSandbox still has to be
+    /// inserted in the World, in a SandboxContainer.
+    pub fn new() -> Self {
+        IcedApplicationBundle {
+            _application: std::marker::PhantomData,
+            focus_actions: None,
+            draw_systems: add_draw_systems::<ApplicationSandbox<A>, PlainView>,
+        }
+    }
+
+    /// See `IcedBundle::with_view_data`
+    pub fn with_view_data(mut self) -> Self
+    where
+        A: for<'c> ApplicationData<'c>,
+    {
+        self.draw_systems = add_draw_systems::<ApplicationSandbox<A>, DataView>;
+        self
+    }
+
+    /// See `IcedBundle::with_focus_actions`
+    pub fn with_focus_actions(mut self, actions: FocusActions<T>) -> Self {
+        self.focus_actions = Some(actions);
+        self
+    }
+}
+
+impl<'a, 'b, A: Application, T: BindingTypes> SystemBundle<'a, 'b>
+    for IcedApplicationBundle<A, T>
+{
+    fn build(
+        self,
+        world: &mut World,
+        dispatcher: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        IcedBundle::<ApplicationSandbox<A>, T> {
+            _sandbox: std::marker::PhantomData,
+            focus_actions: self.focus_actions,
+            draw_systems: self.draw_systems,
+        }
+        .build(world, dispatcher)?;
+        // Commands returned by the Application are spawned on the next frame
+        dispatcher.add(IcedCommandSystem::<A>::default(), "iced_command", &[]);
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use iced_native::{
+    layout, Clipboard, Element, Event, Hasher, Layout, Length, Point, Renderer, Widget,
+};
+
+use crate::backend::IcedRenderer;
+
+/// Keeps the tessellated geometry of its content between frames, as long as
+/// it looks the same.
+///
+/// The content is still drawn on every frame, but its backgrounds, borders,
+/// shadows and shapes are only tessellated again once they change, e.g. when
+/// the content is moved, hovered, pressed or animated.
+///
+/// Keying the geometry means hashing the subtree on every frame, so this is
+/// meant for large subtrees which rarely change, like a grid of panels.
+pub struct Cached<'a, 'r, Message> {
+    content: Element<'a, Message, IcedRenderer<'r>>,
+}
+
+impl<'a, 'r, Message> Cached<'a, 'r, Message> {
+    pub fn new<E>(content: E) -> Self
+    where
+        E: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Cached {
+            content: content.into(),
+        }
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Cached<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        self.content
+            .draw(renderer, defaults, layout, cursor_position)
+            .cached()
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Cached<'a, 'r, Message>> for Element<'a, Message, IcedRenderer<'r>> {
+    fn from(cached: Cached<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(cached)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use crate::primitive::AmethystIcedPrimitive;
+    use crate::widget::Checkbox;
+    use crate::Sandbox;
+
+    struct Toggle(bool);
+
+    impl Sandbox for Toggle {
+        type UIMessage = bool;
+        type GameMessage = ();
+
+        fn update(&mut self, checked: &bool) -> Vec<()> {
+            self.0 = *checked;
+            vec![]
+        }
+
+        fn view(&mut self) -> Element<bool, IcedRenderer> {
+            Cached::new(Checkbox::new(self.0, "Toggle", |checked| checked)).into()
+        }
+    }
+
+    fn drawn_key(harness: &mut IcedTestHarness<Toggle>) -> u64 {
+        match harness.draw() {
+            AmethystIcedPrimitive::Cached { key, .. } => key,
+            _ => panic!("The content isn't cached"),
+        }
+    }
+
+    #[test]
+    fn toggling_without_moving_the_cursor_changes_the_key() {
+        let mut harness = IcedTestHarness::new(Toggle(false));
+        let check_box = harness.layout().child(&[0]).center();
+        harness.move_cursor(check_box);
+        let unchecked = drawn_key(&mut harness);
+        assert_eq!(drawn_key(&mut harness), unchecked);
+
+        harness.click(check_box);
+        assert!(harness.sandbox().0);
+        let checked = drawn_key(&mut harness);
+        assert_ne!(checked, unchecked);
+        assert_eq!(drawn_key(&mut harness), checked);
+    }
+}
@@ -0,0 +1,358 @@
+use iced_native::Hasher;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use iced_native::{
+    layout, Element, Layout, Length, Point, Rectangle, Renderer, Size, Vector, Widget,
+};
+use lyon::geom;
+use lyon::math;
+use lyon::path::builder::{Build, FlatPathBuilder, PathBuilder as _};
+use lyon::tessellation::StrokeOptions;
+
+use crate::backend::IcedRenderer;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::tessellation;
+use crate::vertex::TriangleVertex;
+
+fn point(point: Point) -> math::Point {
+    math::Point::new(point.x, point.y)
+}
+
+/// A shape made of lines and curves, which can be filled or stroked on a Frame
+#[derive(Debug, Clone)]
+pub struct Path(lyon::path::Path);
+
+impl Path {
+    /// Builds a path by calling the closure with a PathBuilder
+    pub fn new(build: impl FnOnce(&mut PathBuilder)) -> Path {
+        let mut builder = PathBuilder {
+            raw: lyon::path::Path::builder(),
+        };
+        build(&mut builder);
+        Path(builder.raw.build())
+    }
+
+    pub fn line(from: Point, to: Point) -> Path {
+        Path::new(|path| {
+            path.move_to(from);
+            path.line_to(to);
+        })
+    }
+
+    pub fn rectangle(bounds: Rectangle) -> Path {
+        Path::new(|path| {
+            path.move_to(Point::new(bounds.x, bounds.y));
+            path.line_to(Point::new(bounds.x + bounds.width, bounds.y));
+            path.line_to(Point::new(bounds.x + bounds.width, bounds.y + bounds.height));
+            path.line_to(Point::new(bounds.x, bounds.y + bounds.height));
+            path.close();
+        })
+    }
+
+    pub fn circle(center: Point, radius: f32) -> Path {
+        Path::new(|path| {
+            path.arc(center, radius, 0., 2. * std::f32::consts::PI);
+            path.close();
+        })
+    }
+}
+
+/// Describes a Path, see `Path::new`
+pub struct PathBuilder {
+    raw: lyon::path::Builder,
+}
+
+impl PathBuilder {
+    /// Starts a new sub-path at the given point
+    pub fn move_to(&mut self, point: Point) {
+        self.raw.move_to(self::point(point));
+    }
+
+    pub fn line_to(&mut self, point: Point) {
+        self.raw.line_to(self::point(point));
+    }
+
+    pub fn quadratic_curve_to(&mut self, control: Point, to: Point) {
+        self.raw.quadratic_bezier_to(point(control), point(to));
+    }
+
+    pub fn bezier_curve_to(&mut self, control_a: Point, control_b: Point, to: Point) {
+        self.raw
+            .cubic_bezier_to(point(control_a), point(control_b), point(to));
+    }
+
+    /// Starts a new sub-path following a circle arc, going clockwise from
+    /// `start_angle` to `end_angle` (in radians, 0 pointing right)
+    pub fn arc(&mut self, center: Point, radius: f32, start_angle: f32, end_angle: f32) {
+        let arc = geom::Arc {
+            center: point(center),
+            radii: math::Vector::new(radius, radius),
+            x_rotation: math::Angle::radians(0.),
+            start_angle: math::Angle::radians(start_angle),
+            sweep_angle: math::Angle::radians(end_angle - start_angle),
+        };
+        self.raw.move_to(arc.sample(0.));
+        let raw = &mut self.raw;
+        arc.for_each_quadratic_bezier(&mut |curve| {
+            raw.quadratic_bezier_to(curve.ctrl, curve.to);
+        });
+    }
+
+    /// Closes the current sub-path with a line to its first point
+    pub fn close(&mut self) {
+        self.raw.close();
+    }
+}
+
+/// The shape of the ends of a stroke
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+/// The shape of the corners of a stroke
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How the outline of a Path is drawn
+#[derive(Debug, Clone, Copy)]
+pub struct Stroke {
+    pub color: [f32; 4],
+    pub width: f32,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke {
+            color: [0., 0., 0., 1.],
+            width: 1.,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+        }
+    }
+}
+
+impl Stroke {
+    pub fn new(color: [f32; 4], width: f32) -> Self {
+        Stroke {
+            color,
+            width,
+            ..Stroke::default()
+        }
+    }
+
+    fn options(&self) -> StrokeOptions {
+        let line_cap = match self.line_cap {
+            LineCap::Butt => lyon::tessellation::LineCap::Butt,
+            LineCap::Square => lyon::tessellation::LineCap::Square,
+            LineCap::Round => lyon::tessellation::LineCap::Round,
+        };
+        let line_join = match self.line_join {
+            LineJoin::Miter => lyon::tessellation::LineJoin::Miter,
+            LineJoin::Round => lyon::tessellation::LineJoin::Round,
+            LineJoin::Bevel => lyon::tessellation::LineJoin::Bevel,
+        };
+        StrokeOptions::default()
+            .with_line_width(self.width)
+            .with_line_cap(line_cap)
+            .with_line_join(line_join)
+    }
+}
+
+/// The surface of a Canvas, on which paths are tessellated.
+///
+/// Coordinates are relative to the top left corner of the Canvas.
+pub struct Frame {
+    size: Size,
+    antialiasing: bool,
+    vertices: Vec<TriangleVertex>,
+}
+
+impl Frame {
+    fn new(size: Size, antialiasing: bool) -> Self {
+        Frame {
+            size,
+            antialiasing,
+            vertices: vec![],
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.size.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.size.height
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.size.width / 2., self.size.height / 2.)
+    }
+
+    pub fn fill(&mut self, path: &Path, color: [f32; 4]) {
+        tessellation::fill_path(&path.0, color, self.antialiasing, &mut self.vertices);
+    }
+
+    pub fn stroke(&mut self, path: &Path, stroke: Stroke) {
+        tessellation::stroke_path(
+            &path.0,
+            stroke.color,
+            &stroke.options(),
+            self.antialiasing,
+            &mut self.vertices,
+        );
+    }
+}
+
+/// Keeps the geometry of a Canvas between frames.
+///
+/// The geometry is only tessellated again when the Canvas is resized, or after
+/// `clear` was called because what it shows changed.
+#[derive(Default)]
+pub struct CanvasCache {
+    geometry: Mutex<Option<(Size, bool, Arc<Vec<TriangleVertex>>)>>,
+}
+
+impl CanvasCache {
+    pub fn new() -> Self {
+        CanvasCache::default()
+    }
+
+    /// Forgets the geometry, so that it is drawn again during the next frame
+    pub fn clear(&self) {
+        *self.geometry.lock().unwrap() = None;
+    }
+
+    fn draw(
+        &self,
+        size: Size,
+        antialiasing: bool,
+        draw: &dyn Fn(&mut Frame),
+    ) -> Arc<Vec<TriangleVertex>> {
+        let mut geometry = self.geometry.lock().unwrap();
+        match &*geometry {
+            Some((cached_size, cached_antialiasing, vertices))
+                if *cached_size == size && *cached_antialiasing == antialiasing =>
+            {
+                vertices.clone()
+            }
+            _ => {
+                let vertices = tessellate(size, antialiasing, draw);
+                *geometry = Some((size, antialiasing, vertices.clone()));
+                vertices
+            }
+        }
+    }
+}
+
+fn tessellate(
+    size: Size,
+    antialiasing: bool,
+    draw: &dyn Fn(&mut Frame),
+) -> Arc<Vec<TriangleVertex>> {
+    let mut frame = Frame::new(size, antialiasing);
+    draw(&mut frame);
+    Arc::new(frame.vertices)
+}
+
+/// Draws vector shapes, e.g. for minimaps, graphs or the links of a skill tree.
+///
+/// The closure fills and strokes paths on a Frame the size of the Canvas. Without
+/// a cache, it is called on every frame.
+pub struct Canvas<'a> {
+    width: Length,
+    height: Length,
+    draw: Box<dyn Fn(&mut Frame) + 'a>,
+    cache: Option<&'a CanvasCache>,
+    antialiasing: bool,
+}
+
+impl<'a> Canvas<'a> {
+    pub fn new<F>(draw: F) -> Self
+    where
+        F: Fn(&mut Frame) + 'a,
+    {
+        Canvas {
+            width: Length::Fill,
+            height: Length::Fill,
+            draw: Box::new(draw),
+            cache: None,
+            antialiasing: true,
+        }
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets whether the edges of the shapes are smoothed, which is the default.
+    ///
+    /// Disabling it keeps pixel art crisp.
+    pub fn antialiasing(mut self, antialiasing: bool) -> Self {
+        self.antialiasing = antialiasing;
+        self
+    }
+
+    /// Reuses the geometry kept by the cache, see `CanvasCache`
+    pub fn cache(mut self, cache: &'a CanvasCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Canvas<'a> {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+        layout::Node::new(limits.resolve(Size::ZERO))
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut IcedRenderer<'r>,
+        _defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let bounds = layout.bounds();
+        let size = Size::new(bounds.width, bounds.height);
+        let vertices = match self.cache {
+            Some(cache) => cache.draw(size, self.antialiasing, &*self.draw),
+            None => tessellate(size, self.antialiasing, &*self.draw),
+        };
+        AmethystIcedPrimitive::Mesh(vertices).translate(Vector::new(bounds.x, bounds.y))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.width.hash(state);
+        self.height.hash(state);
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Canvas<'a>> for Element<'a, Message, IcedRenderer<'r>> {
+    fn from(canvas: Canvas<'a>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(canvas)
+    }
+}
@@ -0,0 +1,173 @@
+use iced_native::Hasher;
+
+use iced_native::input::{keyboard, mouse, ButtonState};
+use iced_native::{
+    layout, Clipboard, Element, Event, Layout, Length, Point, Rectangle, Renderer, Widget,
+};
+
+use crate::backend::IcedRenderer;
+use crate::custom_widget::menu::{keep_inside, MenuStyle};
+use crate::primitive::AmethystIcedPrimitive;
+
+/// The state of a ContextMenu, which must be kept between frames
+#[derive(Debug, Default)]
+pub struct ContextMenuState {
+    position: Option<Point>,
+}
+
+/// Opens a menu at the cursor when the content is right clicked.
+///
+/// Picking an entry sends its message. Clicking anywhere else or pressing
+/// Escape closes the menu.
+pub struct ContextMenu<'a, 'r, Message> {
+    state: &'a mut ContextMenuState,
+    content: Element<'a, Message, IcedRenderer<'r>>,
+    entries: Vec<(String, Message)>,
+    style: MenuStyle,
+}
+
+impl<'a, 'r, Message: Clone> ContextMenu<'a, 'r, Message> {
+    pub fn new<E>(state: &'a mut ContextMenuState, content: E) -> Self
+    where
+        E: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        ContextMenu {
+            state,
+            content: content.into(),
+            entries: vec![],
+            style: MenuStyle::default(),
+        }
+    }
+
+    /// Adds an entry at the bottom of the menu
+    pub fn entry<S: Into<String>>(mut self, label: S, message: Message) -> Self {
+        self.entries.push((label.into(), message));
+        self
+    }
+
+    pub fn style(mut self, style: MenuStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn labels(&self) -> Vec<String> {
+        self.entries.iter().map(|(label, _)| label.clone()).collect()
+    }
+
+    fn menu_bounds(&self, renderer: &IcedRenderer<'_>, position: Point) -> Rectangle {
+        let size = self.style.size(renderer, &self.labels(), 0.);
+        keep_inside(
+            Rectangle {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            },
+            renderer.viewport(),
+        )
+    }
+}
+
+impl<'a, 'r, Message: Clone> Widget<Message, IcedRenderer<'r>>
+    for ContextMenu<'a, 'r, Message>
+{
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let content = self.content.draw(renderer, defaults, layout, cursor_position);
+        match self.state.position {
+            Some(position) if !self.entries.is_empty() => {
+                let menu_bounds = self.menu_bounds(renderer, position);
+                let menu = self.style.draw(renderer, defaults, menu_bounds, &self.labels());
+                AmethystIcedPrimitive::Group(vec![content, menu])
+            }
+            _ => content,
+        }
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        // The content lies beneath the menu, so only the menu sees the keyboard
+        let menu_event = match self.state.position {
+            Some(position) if !self.entries.is_empty() => {
+                renderer.overlay_event(self.menu_bounds(renderer, position), event.clone())
+            }
+            _ => event.clone(),
+        };
+        match &menu_event {
+            Event::Mouse(mouse::Event::Input {
+                button: mouse::Button::Right,
+                state: ButtonState::Pressed,
+            }) if layout.bounds().contains(cursor_position) => {
+                self.state.position = Some(cursor_position);
+            }
+            Event::Mouse(mouse::Event::Input {
+                button: mouse::Button::Left,
+                state: ButtonState::Pressed,
+            }) => {
+                if let Some(position) = self.state.position.take() {
+                    let hovered = self.style.hovered_item(
+                        self.menu_bounds(renderer, position),
+                        self.entries.len(),
+                        renderer.cursor_position(),
+                    );
+                    if let Some(index) = hovered {
+                        messages.push(self.entries[index].1.clone());
+                    }
+                }
+            }
+            Event::Keyboard(keyboard::Event::Input {
+                key_code: keyboard::KeyCode::Escape,
+                state: ButtonState::Pressed,
+                ..
+            }) => {
+                self.state.position = None;
+            }
+            _ => {}
+        }
+
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: Clone + 'a> From<ContextMenu<'a, 'r, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+{
+    fn from(menu: ContextMenu<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(menu)
+    }
+}
@@ -0,0 +1,153 @@
+use iced_native::widget::text::Renderer as TextRenderer;
+use iced_native::{Font, HorizontalAlignment, Point, Rectangle, Size, VerticalAlignment};
+
+use crate::backend::{Defaults, IcedRenderer};
+use crate::primitive::AmethystIcedPrimitive;
+use crate::style::{Background, BorderStyle, Shadow};
+
+/// The style of the menus opened by PickLists and ContextMenus
+#[derive(Clone)]
+pub struct MenuStyle {
+    pub background: Background,
+    pub border: BorderStyle,
+    pub text_color: [f32; 4],
+    pub hovered_background: [f32; 4],
+    pub hovered_text_color: [f32; 4],
+    pub text_size: u16,
+    pub padding: u16,
+    pub shadow: Option<Shadow>,
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        MenuStyle {
+            background: [0.12, 0.12, 0.14, 0.98].into(),
+            border: BorderStyle {
+                width: 1,
+                color: [0.3, 0.3, 0.35, 1.],
+            },
+            text_color: [0.95, 0.95, 0.95, 1.],
+            hovered_background: [0.25, 0.45, 0.8, 1.],
+            hovered_text_color: [1., 1., 1., 1.],
+            text_size: 16,
+            padding: 6,
+            shadow: Some(Shadow {
+                offset: [0., 4.],
+                blur_radius: 10.,
+                spread: 0.,
+                color: [0., 0., 0., 0.5],
+            }),
+        }
+    }
+}
+
+impl MenuStyle {
+    fn item_height(&self) -> f32 {
+        (self.text_size + self.padding * 2) as f32
+    }
+
+    /// The size of a menu listing the labels, at least as wide as `min_width`
+    pub(crate) fn size(&self, renderer: &IcedRenderer<'_>, labels: &[String], min_width: f32) -> Size {
+        let text_width = labels
+            .iter()
+            .map(|label| {
+                TextRenderer::measure(
+                    renderer,
+                    label,
+                    self.text_size,
+                    Font::Default,
+                    Size::new(f32::INFINITY, f32::INFINITY),
+                )
+                .0
+            })
+            .fold(0., f32::max);
+        Size::new(
+            (text_width + (self.padding * 2) as f32).max(min_width),
+            self.item_height() * labels.len() as f32,
+        )
+    }
+
+    /// The index of the item under the cursor
+    pub(crate) fn hovered_item(&self, bounds: Rectangle, count: usize, cursor: Point) -> Option<usize> {
+        if !bounds.contains(cursor) {
+            return None;
+        }
+        let index = ((cursor.y - bounds.y) / self.item_height()) as usize;
+        if index < count {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Draws the menu as an overlay capturing the cursor, highlighting the hovered item
+    pub(crate) fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'_>,
+        defaults: &Defaults,
+        bounds: Rectangle,
+        labels: &[String],
+    ) -> AmethystIcedPrimitive {
+        renderer.draw_overlay(bounds, |renderer| {
+            let hovered = self.hovered_item(bounds, labels.len(), renderer.cursor_position());
+
+            let mut primitives = vec![
+                AmethystIcedPrimitive::shadow(bounds, 0, self.shadow),
+                AmethystIcedPrimitive::panel(
+                    bounds,
+                    Some(&self.background),
+                    &BorderStyle {
+                        width: 0,
+                        ..self.border.clone()
+                    },
+                    0,
+                ),
+            ];
+            for (index, label) in labels.iter().enumerate() {
+                let item_bounds = Rectangle {
+                    y: bounds.y + self.item_height() * index as f32,
+                    height: self.item_height(),
+                    ..bounds
+                };
+                let text_color = if hovered == Some(index) {
+                    primitives.push(AmethystIcedPrimitive::Quad(
+                        item_bounds,
+                        Some(self.hovered_background.into()),
+                    ));
+                    self.hovered_text_color
+                } else {
+                    self.text_color
+                };
+                let padding = self.padding as f32;
+                primitives.push(TextRenderer::draw(
+                    renderer,
+                    defaults,
+                    Rectangle {
+                        x: item_bounds.x + padding,
+                        y: item_bounds.y + padding,
+                        width: item_bounds.width - padding * 2.,
+                        height: self.text_size as f32,
+                    },
+                    label,
+                    self.text_size,
+                    Font::Default,
+                    Some(text_color.into()),
+                    HorizontalAlignment::Left,
+                    VerticalAlignment::Top,
+                ));
+            }
+            primitives.push(AmethystIcedPrimitive::border(bounds, &self.border));
+
+            AmethystIcedPrimitive::Group(primitives)
+        })
+    }
+}
+
+/// Moves an area so that it stays inside the window, if it fits
+pub(crate) fn keep_inside(bounds: Rectangle, viewport: Size) -> Rectangle {
+    Rectangle {
+        x: bounds.x.min(viewport.width - bounds.width).max(0.),
+        y: bounds.y.min(viewport.height - bounds.height).max(0.),
+        ..bounds
+    }
+}
@@ -1,3 +1,21 @@
+pub mod cached;
+pub mod canvas;
+pub mod context_menu;
 pub mod image;
+pub mod menu;
+pub mod modal;
+pub mod pick_list;
+pub mod tagged;
+pub mod tooltip;
+pub mod transition;
 
+pub use cached::*;
+pub use canvas::*;
+pub use context_menu::*;
 pub use image::*;
+pub use menu::*;
+pub use modal::*;
+pub use pick_list::*;
+pub use tagged::*;
+pub use tooltip::*;
+pub use transition::*;
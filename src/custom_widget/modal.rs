@@ -0,0 +1,241 @@
+use iced_native::Hasher;
+use std::hash::Hash;
+
+use iced_native::input::{mouse, ButtonState};
+use iced_native::widget::container::Renderer as ContainerRenderer;
+use iced_native::{
+    layout, Clipboard, Element, Event, Layout, Length, Point, Rectangle, Renderer, Size, Widget,
+};
+
+use crate::backend::IcedRenderer;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::widget::container::hash_text;
+use crate::widget::ContainerStyle;
+
+/// The style of a Modal
+#[derive(Clone)]
+pub struct ModalStyle {
+    /// The color drawn over the content while the dialog is shown
+    pub backdrop: [f32; 4],
+    pub dialog: ContainerStyle,
+}
+
+impl Default for ModalStyle {
+    fn default() -> Self {
+        ModalStyle {
+            backdrop: [0., 0., 0., 0.6],
+            dialog: ContainerStyle::panel(),
+        }
+    }
+}
+
+/// Shows a dialog centered above the content, and blocks the input to the content
+/// while it is visible.
+///
+/// The dialog is centered in the bounds of the Modal, so it should wrap the whole view.
+pub struct Modal<'a, 'r, Message> {
+    content: Element<'a, Message, IcedRenderer<'r>>,
+    dialog: Element<'a, Message, IcedRenderer<'r>>,
+    visible: bool,
+    on_dismiss: Option<Message>,
+    padding: u16,
+    style: ModalStyle,
+}
+
+impl<'a, 'r, Message: Clone> Modal<'a, 'r, Message> {
+    pub fn new<C, D>(content: C, dialog: D) -> Self
+    where
+        C: Into<Element<'a, Message, IcedRenderer<'r>>>,
+        D: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Modal {
+            content: content.into(),
+            dialog: dialog.into(),
+            visible: false,
+            on_dismiss: None,
+            padding: 16,
+            style: ModalStyle::default(),
+        }
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Sets the message sent when clicking outside of the dialog
+    pub fn on_dismiss(mut self, message: Message) -> Self {
+        self.on_dismiss = Some(message);
+        self
+    }
+
+    /// Sets the space between the border of the dialog and its content
+    pub fn padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn style(mut self, style: ModalStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// The area covered by the backdrop
+    fn backdrop_bounds(renderer: &IcedRenderer<'_>, bounds: Rectangle) -> Rectangle {
+        let viewport = renderer.viewport();
+        if viewport.width.is_finite() && viewport.height.is_finite() {
+            Rectangle {
+                x: 0.,
+                y: 0.,
+                width: viewport.width,
+                height: viewport.height,
+            }
+        } else {
+            bounds
+        }
+    }
+}
+
+impl<'a, 'r, Message: Clone> Widget<Message, IcedRenderer<'r>> for Modal<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    /// Lays out the content, along with the padded dialog centered above it
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        let content = self.content.layout(renderer, limits);
+        let size = content.size();
+
+        let padding = self.padding as f32;
+        let dialog_limits = layout::Limits::new(
+            Size::ZERO,
+            Size::new(
+                (size.width - padding * 2.).max(0.),
+                (size.height - padding * 2.).max(0.),
+            ),
+        );
+        let dialog_style = &self.style.dialog;
+        let mut dialog =
+            renderer.layout_with_text(dialog_style.font, dialog_style.text_size, || {
+                self.dialog.layout(renderer, &dialog_limits)
+            });
+        dialog.move_to(Point::new(padding, padding));
+        let dialog_size = Size::new(
+            dialog.size().width + padding * 2.,
+            dialog.size().height + padding * 2.,
+        );
+        let mut frame = layout::Node::with_children(dialog_size, vec![dialog]);
+        frame.move_to(Point::new(
+            ((size.width - dialog_size.width) / 2.).max(0.),
+            ((size.height - dialog_size.height) / 2.).max(0.),
+        ));
+
+        layout::Node::with_children(size, vec![content, frame])
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let mut children = layout.children();
+        let content_layout = children.next().unwrap();
+        let frame_layout = children.next().unwrap();
+
+        let content = self
+            .content
+            .draw(renderer, defaults, content_layout, cursor_position);
+        if !self.visible {
+            return content;
+        }
+
+        let backdrop = Self::backdrop_bounds(renderer, layout.bounds());
+        let dialog = renderer.draw_overlay(backdrop, |renderer| {
+            let cursor = renderer.overlay_cursor(backdrop);
+            let dialog = ContainerRenderer::draw(
+                renderer,
+                defaults,
+                frame_layout.bounds(),
+                cursor,
+                &self.style.dialog,
+                &self.dialog,
+                frame_layout.children().next().unwrap(),
+            );
+            AmethystIcedPrimitive::Group(vec![
+                AmethystIcedPrimitive::Quad(backdrop, Some(self.style.backdrop.into())),
+                dialog,
+            ])
+        });
+
+        AmethystIcedPrimitive::Group(vec![content, dialog])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.padding.hash(state);
+        self.content.hash_layout(state);
+        self.dialog.hash_layout(state);
+        hash_text(self.style.dialog.font, self.style.dialog.text_size, state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        let mut children = layout.children();
+        let content_layout = children.next().unwrap();
+        let frame_layout = children.next().unwrap();
+
+        if !self.visible {
+            self.content.on_event(
+                event,
+                content_layout,
+                cursor_position,
+                messages,
+                renderer,
+                clipboard,
+            );
+            return;
+        }
+
+        let backdrop = Self::backdrop_bounds(renderer, layout.bounds());
+        let cursor = renderer.overlay_cursor(backdrop);
+        if let Event::Mouse(mouse::Event::Input {
+            button: mouse::Button::Left,
+            state: ButtonState::Pressed,
+        }) = event
+        {
+            // Clicks on the overlays opened from the dialog don't dismiss it
+            let covered = cursor != renderer.cursor_position();
+            if !covered && !frame_layout.bounds().contains(cursor) {
+                messages.extend(self.on_dismiss.clone());
+            }
+        }
+        self.dialog.on_event(
+            renderer.overlay_event(backdrop, event),
+            frame_layout.children().next().unwrap(),
+            cursor,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: Clone + 'a> From<Modal<'a, 'r, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+{
+    fn from(modal: Modal<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(modal)
+    }
+}
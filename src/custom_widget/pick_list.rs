@@ -0,0 +1,265 @@
+use iced_native::Hasher;
+use std::hash::Hash;
+
+use iced_native::input::{keyboard, mouse, ButtonState};
+use iced_native::widget::text::Renderer as TextRenderer;
+use iced_native::{
+    layout, Clipboard, Element, Event, Font, HorizontalAlignment, Layout, Length, Point,
+    Rectangle, Renderer, Size, VerticalAlignment, Widget,
+};
+
+use crate::backend::IcedRenderer;
+use crate::custom_widget::menu::{keep_inside, MenuStyle};
+use crate::primitive::AmethystIcedPrimitive;
+use crate::style::{Background, BorderStyle};
+
+/// The state of a PickList, which must be kept between frames
+#[derive(Debug, Default)]
+pub struct PickListState {
+    is_open: bool,
+}
+
+/// The style of a PickList: its header, and the menu listing the options
+#[derive(Clone)]
+pub struct PickListStyle {
+    pub background: Option<Background>,
+    pub border: BorderStyle,
+    pub border_radius: u32,
+    pub text_color: [f32; 4],
+    pub placeholder_color: [f32; 4],
+    pub menu: MenuStyle,
+}
+
+impl Default for PickListStyle {
+    fn default() -> Self {
+        PickListStyle {
+            background: Some([0.2, 0.2, 0.23, 1.].into()),
+            border: BorderStyle {
+                width: 1,
+                color: [0.3, 0.3, 0.35, 1.],
+            },
+            border_radius: 4,
+            text_color: [0.95, 0.95, 0.95, 1.],
+            placeholder_color: [0.6, 0.6, 0.6, 1.],
+            menu: MenuStyle::default(),
+        }
+    }
+}
+
+/// Shows the selected option, and opens a menu above the UI to pick another one.
+///
+/// The menu opens below the PickList, or above it when there is not enough room.
+pub struct PickList<'a, T, Message> {
+    state: &'a mut PickListState,
+    options: Vec<T>,
+    selected: Option<T>,
+    on_selected: Box<dyn Fn(T) -> Message + 'a>,
+    placeholder: String,
+    width: Length,
+    style: PickListStyle,
+}
+
+impl<'a, T, Message> PickList<'a, T, Message>
+where
+    T: ToString + Clone,
+{
+    pub fn new<F>(
+        state: &'a mut PickListState,
+        options: Vec<T>,
+        selected: Option<T>,
+        on_selected: F,
+    ) -> Self
+    where
+        F: Fn(T) -> Message + 'a,
+    {
+        PickList {
+            state,
+            options,
+            selected,
+            on_selected: Box::new(on_selected),
+            placeholder: String::new(),
+            width: Length::Shrink,
+            style: PickListStyle::default(),
+        }
+    }
+
+    /// Sets the text shown when nothing is selected
+    pub fn placeholder<S: Into<String>>(mut self, placeholder: S) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn style(mut self, style: PickListStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn labels(&self) -> Vec<String> {
+        self.options.iter().map(ToString::to_string).collect()
+    }
+
+    /// Where the menu opens, given the bounds of the header
+    fn menu_bounds(&self, renderer: &IcedRenderer<'_>, header: Rectangle) -> Rectangle {
+        let size = self.style.menu.size(renderer, &self.labels(), header.width);
+        let viewport = renderer.viewport();
+        let below = header.y + header.height;
+        let y = if below + size.height > viewport.height && header.y >= size.height {
+            header.y - size.height
+        } else {
+            below
+        };
+        keep_inside(
+            Rectangle {
+                x: header.x,
+                y,
+                width: size.width,
+                height: size.height,
+            },
+            viewport,
+        )
+    }
+}
+
+impl<'a, 'r, T, Message> Widget<Message, IcedRenderer<'r>> for PickList<'a, T, Message>
+where
+    T: ToString + Clone,
+{
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    /// The header is as wide as the longest option, unless a width is set
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        let menu = &self.style.menu;
+        let height = (menu.text_size + menu.padding * 2) as f32;
+        let mut labels = self.labels();
+        labels.push(self.placeholder.clone());
+        let intrinsic = Size::new(menu.size(renderer, &labels, 0.).width, height);
+
+        let limits = limits
+            .width(self.width)
+            .height(Length::Units(height as u16));
+        layout::Node::new(limits.resolve(intrinsic))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let bounds = layout.bounds();
+        let style = &self.style;
+        let padding = style.menu.padding as f32;
+
+        let (label, color) = match &self.selected {
+            Some(selected) => (selected.to_string(), style.text_color),
+            None => (self.placeholder.clone(), style.placeholder_color),
+        };
+        let header = AmethystIcedPrimitive::Group(vec![
+            AmethystIcedPrimitive::panel(
+                bounds,
+                style.background.as_ref(),
+                &style.border,
+                style.border_radius,
+            ),
+            TextRenderer::draw(
+                renderer,
+                defaults,
+                Rectangle {
+                    x: bounds.x + padding,
+                    y: bounds.y + padding,
+                    width: bounds.width - padding * 2.,
+                    height: style.menu.text_size as f32,
+                },
+                &label,
+                style.menu.text_size,
+                Font::Default,
+                Some(color.into()),
+                HorizontalAlignment::Left,
+                VerticalAlignment::Top,
+            ),
+        ]);
+
+        if !self.state.is_open {
+            return header;
+        }
+        let menu_bounds = self.menu_bounds(renderer, bounds);
+        let menu = style.menu.draw(renderer, defaults, menu_bounds, &self.labels());
+        AmethystIcedPrimitive::Group(vec![header, menu])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.width.hash(state);
+        self.style.menu.text_size.hash(state);
+        self.style.menu.padding.hash(state);
+        self.placeholder.hash(state);
+        self.labels().hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        _clipboard: Option<&dyn Clipboard>,
+    ) {
+        // The keyboard only reaches the menu while it is the topmost overlay
+        let event = if self.state.is_open {
+            renderer.overlay_event(self.menu_bounds(renderer, layout.bounds()), event)
+        } else {
+            event
+        };
+        match event {
+            Event::Mouse(mouse::Event::Input {
+                button: mouse::Button::Left,
+                state: ButtonState::Pressed,
+            }) => {
+                if self.state.is_open {
+                    let menu_bounds = self.menu_bounds(renderer, layout.bounds());
+                    let hovered = self.style.menu.hovered_item(
+                        menu_bounds,
+                        self.options.len(),
+                        renderer.cursor_position(),
+                    );
+                    if let Some(index) = hovered {
+                        messages.push((self.on_selected)(self.options[index].clone()));
+                    }
+                    self.state.is_open = false;
+                } else if layout.bounds().contains(cursor_position) {
+                    self.state.is_open = true;
+                }
+            }
+            Event::Keyboard(keyboard::Event::Input {
+                key_code: keyboard::KeyCode::Escape,
+                state: ButtonState::Pressed,
+                ..
+            }) => {
+                self.state.is_open = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a, 'r, T, Message: 'a> From<PickList<'a, T, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+where
+    T: ToString + Clone + 'a,
+{
+    fn from(pick_list: PickList<'a, T, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(pick_list)
+    }
+}
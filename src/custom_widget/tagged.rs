@@ -0,0 +1,81 @@
+use iced_native::Hasher;
+
+use iced_native::{layout, Clipboard, Element, Event, Layout, Length, Point, Renderer, Widget};
+
+use crate::backend::IcedRenderer;
+
+/// Tags a widget with an id, so that its bounds on screen can be found
+/// in the `IcedLayoutSnapshot` resource once it has been drawn.
+///
+/// The tagged widget is otherwise left untouched.
+pub struct Tagged<'a, 'r, Message> {
+    id: String,
+    content: Element<'a, Message, IcedRenderer<'r>>,
+}
+
+impl<'a, 'r, Message> Tagged<'a, 'r, Message> {
+    pub fn new<I, E>(id: I, content: E) -> Self
+    where
+        I: Into<String>,
+        E: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Tagged {
+            id: id.into(),
+            content: content.into(),
+        }
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Tagged<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        renderer.register_id(&self.id, layout.bounds());
+        self.content.draw(renderer, defaults, layout, cursor_position)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Tagged<'a, 'r, Message>> for Element<'a, Message, IcedRenderer<'r>> {
+    fn from(tagged: Tagged<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(tagged)
+    }
+}
@@ -0,0 +1,190 @@
+use iced_native::Hasher;
+use std::hash::Hash;
+
+use iced_native::widget::container::Renderer as ContainerRenderer;
+use iced_native::{
+    layout, Clipboard, Element, Event, Layout, Length, Point, Renderer, Size, Vector, Widget,
+};
+
+use std::time::Duration;
+
+use crate::backend::IcedRenderer;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::IcedAnimations;
+use crate::widget::container::hash_text;
+use crate::widget::ContainerStyle;
+
+/// Distance between the cursor and the top left corner of the popup
+const CURSOR_OFFSET: Vector = Vector { x: 12., y: 16. };
+
+/// Shows a popup next to the cursor once the content has been hovered for a while.
+///
+/// The popup is drawn above the rest of the UI and kept inside the window: it goes
+/// on the left or above the cursor when there is not enough room. It doesn't react
+/// to events, so it can't hold buttons.
+pub struct Tooltip<'a, 'r, Message> {
+    content: Element<'a, Message, IcedRenderer<'r>>,
+    tooltip: Element<'a, Message, IcedRenderer<'r>>,
+    delay: Duration,
+    padding: u16,
+    style: ContainerStyle,
+}
+
+impl<'a, 'r, Message> Tooltip<'a, 'r, Message> {
+    pub fn new<C, T>(content: C, tooltip: T) -> Self
+    where
+        C: Into<Element<'a, Message, IcedRenderer<'r>>>,
+        T: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Tooltip {
+            content: content.into(),
+            tooltip: tooltip.into(),
+            delay: Duration::from_millis(500),
+            padding: 8,
+            style: ContainerStyle::panel(),
+        }
+    }
+
+    /// Sets how long the content must be hovered before the popup shows up
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the space between the border of the popup and the tooltip
+    pub fn padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the style of the popup
+    pub fn style(mut self, style: ContainerStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Tooltip<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    /// Lays out the content, along with the popup which is given the whole window
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        let content = self.content.layout(renderer, limits);
+
+        let padding = self.padding as f32;
+        let viewport = renderer.viewport();
+        let tooltip_limits = layout::Limits::new(
+            Size::ZERO,
+            Size::new(
+                (viewport.width - padding * 2.).max(0.),
+                (viewport.height - padding * 2.).max(0.),
+            ),
+        );
+        let mut tooltip = renderer.layout_with_text(self.style.font, self.style.text_size, || {
+            self.tooltip.layout(renderer, &tooltip_limits)
+        });
+        tooltip.move_to(Point::new(padding, padding));
+        let popup_size = Size::new(
+            tooltip.size().width + padding * 2.,
+            tooltip.size().height + padding * 2.,
+        );
+        let popup = layout::Node::with_children(popup_size, vec![tooltip]);
+
+        layout::Node::with_children(content.size(), vec![content, popup])
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let bounds = layout.bounds();
+        let mut children = layout.children();
+        let content_layout = children.next().unwrap();
+        let popup_layout = children.next().unwrap();
+
+        let content = self
+            .content
+            .draw(renderer, defaults, content_layout, cursor_position);
+
+        let hovered_for = renderer.active_for(
+            IcedAnimations::widget_key("tooltip", bounds),
+            bounds.contains(cursor_position),
+        );
+        match hovered_for {
+            Some(hovered_for) if hovered_for >= self.delay => {}
+            _ => return content,
+        }
+
+        let popup_bounds = popup_layout.bounds();
+        let viewport = renderer.viewport();
+        let mut position = cursor_position + CURSOR_OFFSET;
+        if position.x + popup_bounds.width > viewport.width {
+            position.x = (cursor_position.x - popup_bounds.width).max(0.);
+        }
+        if position.y + popup_bounds.height > viewport.height {
+            position.y = (cursor_position.y - popup_bounds.height).max(0.);
+        }
+
+        let tooltip_layout = popup_layout.children().next().unwrap();
+        let popup = ContainerRenderer::draw(
+            renderer,
+            defaults,
+            popup_bounds,
+            cursor_position,
+            &self.style,
+            &self.tooltip,
+            tooltip_layout,
+        );
+
+        AmethystIcedPrimitive::Group(vec![
+            content,
+            AmethystIcedPrimitive::Overlay(Box::new(popup.translate(Vector::new(
+                position.x - popup_bounds.x,
+                position.y - popup_bounds.y,
+            )))),
+        ])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.padding.hash(state);
+        self.content.hash_layout(state);
+        self.tooltip.hash_layout(state);
+        hash_text(self.style.font, self.style.text_size, state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        self.content.on_event(
+            event,
+            layout.children().next().unwrap(),
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Tooltip<'a, 'r, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+{
+    fn from(tooltip: Tooltip<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(tooltip)
+    }
+}
@@ -0,0 +1,257 @@
+use iced_native::Hasher;
+
+use iced_native::{
+    layout, Clipboard, Element, Event, Layout, Length, Point, Renderer, Vector, Widget,
+};
+
+use crate::animation::Tween;
+use crate::backend::IcedRenderer;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::IcedAnimations;
+
+/// How a Transition shows and hides its content
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionKind {
+    Fade,
+    /// Slides the content in from the given offset
+    Slide(Vector),
+    FadeSlide(Vector),
+    /// Grows the content from the given scale, around its center
+    Scale(f32),
+    FadeScale(f32),
+}
+
+/// Animates the appearance and disappearance of a whole subtree.
+///
+/// The content appears when the Transition is first drawn, and disappears once
+/// `visible` is set to false. It keeps its place in the layout while hidden, and
+/// only receives events while visible. The id must be unique among Transitions.
+///
+/// Note that the content is only moved visually, see `AmethystIcedPrimitive::Transform`:
+/// during a slide, the widgets still react at their final position.
+pub struct Transition<'a, 'r, Message> {
+    id: String,
+    content: Element<'a, Message, IcedRenderer<'r>>,
+    kind: TransitionKind,
+    visible: bool,
+    tween: Tween,
+}
+
+impl<'a, 'r, Message> Transition<'a, 'r, Message> {
+    pub fn new<I, E>(id: I, content: E) -> Self
+    where
+        I: Into<String>,
+        E: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Transition {
+            id: id.into(),
+            content: content.into(),
+            kind: TransitionKind::Fade,
+            visible: true,
+            tween: Tween::default(),
+        }
+    }
+
+    pub fn kind(mut self, kind: TransitionKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn tween(mut self, tween: Tween) -> Self {
+        self.tween = tween;
+        self
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Transition<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> <IcedRenderer<'r> as Renderer>::Output {
+        let target = if self.visible { 1. } else { 0. };
+        let progress = renderer.animate(
+            IcedAnimations::key(("transition", &self.id)),
+            0.,
+            target,
+            self.tween,
+        );
+        if progress <= 0. && !self.visible {
+            return AmethystIcedPrimitive::None;
+        }
+
+        let content = self.content.draw(renderer, defaults, layout, cursor_position);
+        let slide = |offset: Vector| {
+            Vector::new(offset.x * (1. - progress), offset.y * (1. - progress))
+        };
+        let bounds = layout.bounds();
+        let center = Point::new(bounds.x + bounds.width / 2., bounds.y + bounds.height / 2.);
+        let scale = |from: f32| from + (1. - from) * progress;
+        match self.kind {
+            TransitionKind::Fade => content.fade(progress.min(1.)),
+            TransitionKind::Slide(offset) => content.translate(slide(offset)),
+            TransitionKind::FadeSlide(offset) => {
+                content.translate(slide(offset)).fade(progress.min(1.))
+            }
+            TransitionKind::Scale(from) => content.scale(center, scale(from)),
+            TransitionKind::FadeScale(from) => {
+                content.scale(center, scale(from)).fade(progress.min(1.))
+            }
+        }
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        if !self.visible {
+            return;
+        }
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Transition<'a, 'r, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+{
+    fn from(transition: Transition<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(transition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use crate::widget::Button;
+    use crate::{Sandbox, Tagged, Text};
+    use iced_native::button;
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        Play,
+    }
+
+    /// A menu whose transition never progresses, since the clock of the harness
+    /// doesn't move: the content stays faded, moved or scaled
+    struct Menu {
+        kind: TransitionKind,
+        play: button::State,
+    }
+
+    impl Menu {
+        fn new(kind: TransitionKind) -> Self {
+            Menu {
+                kind,
+                play: button::State::default(),
+            }
+        }
+    }
+
+    impl Sandbox for Menu {
+        type UIMessage = Message;
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<Message, IcedRenderer> {
+            let play =
+                Button::new(&mut self.play, Text::new("Play").size(20)).on_press(Message::Play);
+            Transition::new("menu", Tagged::new("play", play))
+                .kind(self.kind)
+                .into()
+        }
+    }
+
+    /// Returns the bounds of the button, and the point inside them where it is clicked
+    fn play_button(harness: &mut IcedTestHarness<Menu>) -> (Rectangle, Point) {
+        let bounds = harness.bounds_of("play").unwrap();
+        (bounds, Point::new(bounds.x + 1., bounds.y + 1.))
+    }
+
+    #[test]
+    fn moved_content_is_clicked_at_its_layout_position() {
+        let mut harness =
+            IcedTestHarness::new(Menu::new(TransitionKind::Slide(Vector::new(0., 100.))));
+        let (bounds, corner) = play_button(&mut harness);
+        match harness.draw() {
+            AmethystIcedPrimitive::Transform(transform, _) => {
+                assert_eq!(transform.to_cols_array()[7], 100.)
+            }
+            _ => panic!("The content of the transition isn't moved"),
+        }
+
+        harness.click(corner);
+        assert_eq!(harness.take_ui_messages(), vec![Message::Play]);
+
+        // Where the button is drawn, there is nothing to click
+        harness.click(Point::new(corner.x, corner.y + 100.));
+        assert!(bounds.height < 100.);
+        assert_eq!(harness.take_ui_messages(), vec![]);
+    }
+
+    #[test]
+    fn scaled_content_is_clicked_at_its_layout_position() {
+        let mut harness = IcedTestHarness::new(Menu::new(TransitionKind::Scale(0.5)));
+        let (_, corner) = play_button(&mut harness);
+        match harness.draw() {
+            AmethystIcedPrimitive::Transform(transform, _) => {
+                assert_eq!(transform.to_cols_array()[0], 0.5)
+            }
+            _ => panic!("The content of the transition isn't scaled"),
+        }
+
+        // The corner of the layout lies outside of the button drawn at half its size
+        harness.click(corner);
+        assert_eq!(harness.take_ui_messages(), vec![Message::Play]);
+    }
+
+    #[test]
+    fn opacity_changes_nothing_to_the_events() {
+        let mut harness = IcedTestHarness::new(Menu::new(TransitionKind::Fade));
+        let (bounds, _) = play_button(&mut harness);
+        match harness.draw() {
+            AmethystIcedPrimitive::Opacity(opacity, _) => assert_eq!(opacity, 0.),
+            _ => panic!("The content of the transition isn't faded"),
+        }
+
+        harness.click(Point::new(
+            bounds.x + bounds.width / 2.,
+            bounds.y + bounds.height / 2.,
+        ));
+        assert_eq!(harness.take_ui_messages(), vec![Message::Play]);
+    }
+}
@@ -0,0 +1,179 @@
+use amethyst::assets::Handle;
+use amethyst::renderer::Texture;
+
+use std::collections::HashMap;
+
+use crate::primitive::RenderState;
+use crate::vertex::{QuadInstance, ShadowVertex, TriangleVertex};
+
+/// Where a run of shadows, followed by runs of backdrops, quads and triangles, starts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Batch {
+    pub shadows: u32,
+    pub backdrops: u32,
+    pub quads: u32,
+    pub triangles: u32,
+}
+
+/// The vertices of a primitive tree, layer by layer, before they are uploaded.
+///
+/// The vectors are cleared rather than dropped between frames, so that they
+/// keep their capacity.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Geometry {
+    pub vertices: Vec<TriangleVertex>,
+    pub shadows: Vec<ShadowVertex>,
+    pub backdrops: Vec<TriangleVertex>,
+    pub quads: Vec<QuadInstance>,
+    /// The batches of every layer
+    pub layers: Vec<Vec<Batch>>,
+    /// The textured rectangles of every layer
+    pub images: Vec<Vec<(Handle<Texture>, QuadInstance)>>,
+    /// Whether Backdrop primitives are drawn, see `IcedUI::with_backdrop_blur`
+    pub backdrop: bool,
+}
+
+impl Geometry {
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.shadows.clear();
+        self.backdrops.clear();
+        self.quads.clear();
+        self.layers.clear();
+        self.images.clear();
+    }
+
+    /// Where the next batch would start
+    fn mark(&self) -> Batch {
+        Batch {
+            shadows: self.shadows.len() as u32,
+            backdrops: self.backdrops.len() as u32,
+            quads: self.quads.len() as u32,
+            triangles: self.vertices.len() as u32,
+        }
+    }
+
+    /// Starts a new layer drawn above the previous ones, returning its depth
+    pub fn begin_layer(&mut self) -> u32 {
+        let mark = self.mark();
+        self.layers.push(vec![mark]);
+        self.images.push(vec![]);
+        (self.layers.len() - 1) as u32
+    }
+
+    /// Starts a new batch if anything drawn after the given run of the batch
+    /// was added to the current one, so that what comes next goes above it
+    fn split_batch(&mut self, is_drawn_after: impl Fn(&Batch, &Batch) -> bool) {
+        let mark = self.mark();
+        if let Some(batches) = self.layers.last_mut() {
+            if batches.last().map_or(true, |batch| is_drawn_after(batch, &mark)) {
+                batches.push(mark);
+            }
+        }
+    }
+
+    /// Adds shadows above everything added so far
+    pub fn push_shadows(&mut self, vertices: &[ShadowVertex]) {
+        self.split_batch(|batch, mark| {
+            batch.backdrops != mark.backdrops
+                || batch.quads != mark.quads
+                || batch.triangles != mark.triangles
+        });
+        self.shadows.extend_from_slice(vertices);
+    }
+
+    /// Adds triangles showing the blurred scene, above everything added so far
+    pub fn push_backdrop(&mut self, vertices: &[TriangleVertex]) {
+        self.split_batch(|batch, mark| {
+            batch.quads != mark.quads || batch.triangles != mark.triangles
+        });
+        self.backdrops.extend_from_slice(vertices);
+    }
+
+    /// Adds quads above everything added so far
+    pub fn push_quads(&mut self, quads: &[QuadInstance]) {
+        self.split_batch(|batch, mark| batch.triangles != mark.triangles);
+        self.quads.extend_from_slice(quads);
+    }
+
+    /// Queues a textured rectangle in the current layer
+    pub fn push_image(&mut self, texture: &Handle<Texture>, instance: QuadInstance) {
+        if let Some(images) = self.images.last_mut() {
+            images.push((texture.clone(), instance));
+        }
+    }
+
+    /// The batches of a layer, each one along with where the next one starts
+    pub fn batches(&self, layer: usize) -> impl Iterator<Item = (Batch, Batch)> + '_ {
+        let batches = self.layers.get(layer).map(Vec::as_slice).unwrap_or(&[]);
+        let end = self
+            .layers
+            .get(layer + 1)
+            .and_then(|batches| batches.first())
+            .copied()
+            .unwrap_or_else(|| self.mark());
+        batches
+            .iter()
+            .enumerate()
+            .map(move |(i, batch)| (*batch, batches.get(i + 1).copied().unwrap_or(end)))
+    }
+
+    /// Adds the geometry recorded in the first layer of another Geometry, applying the state
+    pub fn replay(&mut self, recorded: &Geometry, state: &RenderState) {
+        for (batch, next) in recorded.batches(0) {
+            let shadows = &recorded.shadows[batch.shadows as usize..next.shadows as usize];
+            if !shadows.is_empty() {
+                let first = self.shadows.len();
+                self.push_shadows(shadows);
+                state.apply_shadows(&mut self.shadows[first..]);
+            }
+            let backdrops = &recorded.backdrops[batch.backdrops as usize..next.backdrops as usize];
+            if !backdrops.is_empty() {
+                let first = self.backdrops.len();
+                self.push_backdrop(backdrops);
+                state.apply_triangles(&mut self.backdrops[first..]);
+            }
+            let quads = &recorded.quads[batch.quads as usize..next.quads as usize];
+            if !quads.is_empty() {
+                let first = self.quads.len();
+                self.push_quads(quads);
+                state.apply_quads(&mut self.quads[first..]);
+            }
+            let first = self.vertices.len();
+            self.vertices.extend_from_slice(
+                &recorded.vertices[batch.triangles as usize..next.triangles as usize],
+            );
+            state.apply_triangles(&mut self.vertices[first..]);
+        }
+    }
+}
+
+/// The geometry of the Cached primitives, kept between frames.
+///
+/// Entries which weren't used during a frame are dropped at the end of it.
+#[derive(Debug, Default)]
+pub(crate) struct GeometryCache {
+    entries: HashMap<u64, Geometry>,
+    used: HashMap<u64, Geometry>,
+}
+
+impl GeometryCache {
+    /// Takes the geometry recorded for the key, if any
+    pub fn take(&mut self, key: u64) -> Option<Geometry> {
+        self.entries.remove(&key).or_else(|| self.used.remove(&key))
+    }
+
+    /// Keeps the geometry recorded for the key until the end of the next frame
+    pub fn insert(&mut self, key: u64, geometry: Geometry) {
+        self.used.insert(key, geometry);
+    }
+
+    /// Drops the entries unused during the frame
+    pub fn end_frame(&mut self) {
+        self.entries = std::mem::take(&mut self.used);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.used.len()
+    }
+}
@@ -0,0 +1,534 @@
+use amethyst::assets::AssetStorage;
+use amethyst::ecs::{Read, SystemData, World, WorldExt, Write, WriteExpect};
+use amethyst::renderer::SpriteSheet;
+use glyph_brush::GlyphBrushBuilder;
+use iced_native::input::{keyboard, mouse, ButtonState};
+use iced_native::{layout, Cache, Event, Point, Rectangle, Size, UserInterface};
+use image::RgbaImage;
+
+use std::time::Duration;
+
+use crate::backend::{IcedRenderer, MockMeasurer, OverlayState};
+use crate::headless::SoftwareRenderer;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::{FontCache, IcedAnimations, IcedLayoutSnapshot};
+use crate::sandbox::{Element, Sandbox, SandboxData};
+use crate::systems::{fetch_view, DataView, PlainView};
+use crate::widget::PaneGridStyle;
+use crate::IcedGlyphBrush;
+
+/// The absolute bounds of a laid out widget and of its children
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutTree {
+    pub bounds: Rectangle,
+    pub children: Vec<LayoutTree>,
+}
+
+impl LayoutTree {
+    fn new(node: &layout::Node, offset: Point) -> Self {
+        let bounds = node.bounds();
+        let bounds = Rectangle {
+            x: bounds.x + offset.x,
+            y: bounds.y + offset.y,
+            ..bounds
+        };
+        LayoutTree {
+            children: node
+                .children()
+                .iter()
+                .map(|child| LayoutTree::new(child, Point::new(bounds.x, bounds.y)))
+                .collect(),
+            bounds,
+        }
+    }
+
+    /// Follows the given child indices, starting from this node.
+    ///
+    /// Panics if there is no such child, with the path in the message.
+    pub fn child(&self, path: &[usize]) -> &LayoutTree {
+        path.iter().enumerate().fold(self, |node, (depth, index)| {
+            node.children.get(*index).unwrap_or_else(|| {
+                panic!(
+                    "No child at {:?}, the node at depth {} has {} children",
+                    path,
+                    depth,
+                    node.children.len()
+                )
+            })
+        })
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(
+            self.bounds.x + self.bounds.width / 2.,
+            self.bounds.y + self.bounds.height / 2.,
+        )
+    }
+}
+
+/// Drives a Sandbox without any window or GPU, so that UIs can be tested with `cargo test`.
+///
+/// Messages produced by the widgets are passed to `Sandbox::update` right away, and
+/// both the UIMessages and the resulting GameMessages are kept until taken.
+pub struct IcedTestHarness<S: Sandbox> {
+    world: World,
+    sandbox: S,
+    view: for<'s, 'w> fn(&'s mut S, &'w World) -> Element<'s, 's, S::UIMessage>,
+    size: Size,
+    cache: Option<Cache>,
+    measurer: Option<MockMeasurer>,
+    layout_snapshot: IcedLayoutSnapshot,
+    overlays: OverlayState,
+    ui_messages: Vec<S::UIMessage>,
+    game_messages: Vec<S::GameMessage>,
+}
+
+impl<S: Sandbox> IcedTestHarness<S> {
+    /// Creates a 800x600 harness measuring texts with the default MockMeasurer
+    pub fn new(sandbox: S) -> Self {
+        let mut world = World::new();
+        world.insert(AssetStorage::<SpriteSheet>::default());
+        world.insert(FontCache::default());
+        world.insert(IcedAnimations::default());
+        let square_ttf: &[u8] = include_bytes!("../../font/square.ttf");
+        world.insert::<IcedGlyphBrush>(GlyphBrushBuilder::using_font_bytes(square_ttf).build());
+
+        IcedTestHarness {
+            world,
+            sandbox,
+            view: fetch_view::<S, PlainView>,
+            size: Size::new(800., 600.),
+            cache: Some(Cache::default()),
+            measurer: Some(MockMeasurer::default()),
+            layout_snapshot: IcedLayoutSnapshot::default(),
+            overlays: OverlayState::default(),
+            ui_messages: vec![],
+            game_messages: vec![],
+        }
+    }
+
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.size = Size::new(width, height);
+        self
+    }
+
+    /// Measures texts with the given MockMeasurer, or with the real
+    /// glyph brush when `None`.
+    pub fn with_measurer(mut self, measurer: Option<MockMeasurer>) -> Self {
+        self.measurer = measurer;
+        self
+    }
+
+    /// Builds the UI with `SandboxData::view_with_data`, fetching its data from `world()`
+    pub fn with_view_data(mut self) -> Self
+    where
+        S: for<'c> SandboxData<'c>,
+    {
+        self.view = fetch_view::<S, DataView>;
+        self
+    }
+
+    /// The World holding the resources used by the renderer, and the data
+    /// given to `SandboxData::view_with_data`
+    pub fn world(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn sandbox(&mut self) -> &mut S {
+        &mut self.sandbox
+    }
+
+    /// Moves the clock of the animations forward, as if some frames had been drawn
+    pub fn advance_time(&mut self, duration: Duration) {
+        let mut animations = self.world.write_resource::<IcedAnimations>();
+        let now = animations.now() + duration;
+        animations.set_time(now);
+    }
+
+    /// Sends raw events to the UI, then updates the Sandbox with the produced messages
+    pub fn send_events(&mut self, events: Vec<Event>) {
+        let messages = {
+            let mut renderer = build_renderer(&self.world, self.measurer, self.size)
+                .with_overlays(self.overlays.clone());
+            let cache = self.cache.take().unwrap_or_default();
+            let mut user_interface = UserInterface::build(
+                (self.view)(&mut self.sandbox, &self.world),
+                self.size,
+                cache,
+                &mut renderer,
+            );
+            // Each event is captured right before being sent, see `capture_event`
+            let messages = renderer
+                .overlay_events()
+                .into_iter()
+                .chain(
+                    events
+                        .into_iter()
+                        .map(|event| renderer.capture_event(event)),
+                )
+                .flat_map(|event| user_interface.update(vec![event], None, &renderer))
+                .collect::<Vec<_>>();
+            self.overlays = renderer.overlays();
+            self.cache = Some(user_interface.into_cache());
+            messages
+        };
+        for message in messages {
+            self.game_messages.extend(self.sandbox.update(&message));
+            self.ui_messages.push(message);
+        }
+    }
+
+    pub fn move_cursor(&mut self, position: Point) {
+        self.send_events(vec![Event::Mouse(mouse::Event::CursorMoved {
+            x: position.x,
+            y: position.y,
+        })]);
+    }
+
+    /// Moves the cursor to the given position, then presses and releases the left button
+    pub fn click(&mut self, position: Point) {
+        self.click_with(position, mouse::Button::Left);
+    }
+
+    /// Moves the cursor to the given position, then presses and releases the right button
+    pub fn right_click(&mut self, position: Point) {
+        self.click_with(position, mouse::Button::Right);
+    }
+
+    fn click_with(&mut self, position: Point, button: mouse::Button) {
+        self.send_events(vec![
+            Event::Mouse(mouse::Event::CursorMoved {
+                x: position.x,
+                y: position.y,
+            }),
+            Event::Mouse(mouse::Event::Input {
+                state: ButtonState::Pressed,
+                button,
+            }),
+            Event::Mouse(mouse::Event::Input {
+                state: ButtonState::Released,
+                button,
+            }),
+        ]);
+    }
+
+    /// Clicks at the center of the widget found by following the child indices
+    /// from the root of the layout, see `LayoutTree::child`.
+    pub fn click_node(&mut self, path: &[usize]) {
+        let position = self.layout().child(path).center();
+        self.click(position);
+    }
+
+    /// Clicks at the center of the widget tagged with the given id.
+    ///
+    /// Panics if no such widget was drawn.
+    pub fn click_id(&mut self, id: &str) {
+        self.draw();
+        let position = self
+            .layout_snapshot
+            .center(id)
+            .unwrap_or_else(|| panic!("No widget tagged with {:?} was drawn", id));
+        self.click(position);
+    }
+
+    /// Draws the Sandbox, then returns the bounds of the widget tagged with the given id
+    pub fn bounds_of(&mut self, id: &str) -> Option<Rectangle> {
+        self.draw();
+        self.layout_snapshot.get(id)
+    }
+
+    /// The bounds of the tagged widgets, as of the last draw
+    pub fn layout_snapshot(&self) -> &IcedLayoutSnapshot {
+        &self.layout_snapshot
+    }
+
+    /// Sends every character of the text, as when typing it on a keyboard
+    pub fn type_text(&mut self, text: &str) {
+        self.send_events(
+            text.chars()
+                .map(|c| Event::Keyboard(keyboard::Event::CharacterReceived(c)))
+                .collect(),
+        );
+    }
+
+    /// Presses and releases a key
+    pub fn press_key(&mut self, key_code: keyboard::KeyCode) {
+        self.send_events(
+            [ButtonState::Pressed, ButtonState::Released]
+                .iter()
+                .map(|state| {
+                    Event::Keyboard(keyboard::Event::Input {
+                        state: *state,
+                        key_code,
+                        modifiers: keyboard::ModifiersState::default(),
+                    })
+                })
+                .collect(),
+        );
+    }
+
+    /// Returns the UIMessages produced since the last call
+    pub fn take_ui_messages(&mut self) -> Vec<S::UIMessage> {
+        std::mem::replace(&mut self.ui_messages, vec![])
+    }
+
+    /// Returns the GameMessages sent by the Sandbox since the last call
+    pub fn take_game_messages(&mut self) -> Vec<S::GameMessage> {
+        std::mem::replace(&mut self.game_messages, vec![])
+    }
+
+    /// Lays out the current view of the Sandbox
+    pub fn layout(&mut self) -> LayoutTree {
+        let renderer = build_renderer(&self.world, self.measurer, self.size);
+        let node = (self.view)(&mut self.sandbox, &self.world)
+            .layout(&renderer, &layout::Limits::new(Size::ZERO, self.size));
+        LayoutTree::new(&node, Point::new(0., 0.))
+    }
+
+    /// Draws the current view of the Sandbox, with the cursor where it was last moved
+    pub fn draw(&mut self) -> AmethystIcedPrimitive {
+        let mut renderer = build_renderer(&self.world, self.measurer, self.size)
+            .with_overlays(self.overlays.clone());
+        let cache = self.cache.take().unwrap_or_default();
+        let mut user_interface = UserInterface::build(
+            (self.view)(&mut self.sandbox, &self.world),
+            self.size,
+            cache,
+            &mut renderer,
+        );
+        let primitive = user_interface.draw(&mut renderer);
+        self.layout_snapshot.set(renderer.take_ids());
+        self.overlays = renderer.take_overlays();
+        self.cache = Some(user_interface.into_cache());
+        drop(renderer);
+        self.world.write_resource::<IcedAnimations>().end_frame();
+        primitive
+    }
+
+    /// Draws the Sandbox and rasterises it with the given renderer, for snapshot tests
+    pub fn snapshot(&mut self, renderer: &mut SoftwareRenderer) -> RgbaImage {
+        let primitive = self.draw();
+        renderer.render(&primitive)
+    }
+}
+
+fn build_renderer(world: &World, measurer: Option<MockMeasurer>, size: Size) -> IcedRenderer<'_> {
+    let pane_grid_style = world
+        .try_fetch::<PaneGridStyle>()
+        .map(|style| *style)
+        .unwrap_or_default();
+    let renderer = IcedRenderer::new(
+        Read::<AssetStorage<SpriteSheet>>::fetch(world),
+        WriteExpect::<IcedGlyphBrush>::fetch(world),
+        Read::<FontCache>::fetch(world),
+    )
+    .with_animations(Write::<IcedAnimations>::fetch(world))
+    .with_viewport(size)
+    .with_pane_grid_style(pane_grid_style);
+    match measurer {
+        Some(measurer) => renderer.with_mock_measurer(measurer),
+        None => renderer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{Button, Column};
+    use crate::{Element, Tagged, Text};
+    use iced_native::{button, Clipboard, Hasher, Layout, Length, Renderer, Widget};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Increment,
+        Decrement,
+        Typed(char),
+        Pressed(keyboard::KeyCode),
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum GameMessage {
+        Changed(i32),
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        value: i32,
+        increment: button::State,
+        decrement: button::State,
+    }
+
+    impl Sandbox for Counter {
+        type UIMessage = Message;
+        type GameMessage = GameMessage;
+
+        fn update(&mut self, message: &Message) -> Vec<GameMessage> {
+            match message {
+                Message::Increment => self.value += 1,
+                Message::Decrement => self.value -= 1,
+                _ => return vec![],
+            }
+            vec![GameMessage::Changed(self.value)]
+        }
+
+        fn view(&mut self) -> Element<Message> {
+            Column::new()
+                .push(Tagged::new(
+                    "increment",
+                    Button::new(&mut self.increment, Text::new("+").size(20))
+                        .on_press(Message::Increment),
+                ))
+                .push(Tagged::new(
+                    "decrement",
+                    Button::new(&mut self.decrement, Text::new("-").size(20))
+                        .on_press(Message::Decrement),
+                ))
+                .push(Text::new("Hello").size(20))
+                .push(Element::new(KeyListener))
+                .into()
+        }
+    }
+
+    /// Sends the characters and the pressed keys it receives
+    struct KeyListener;
+
+    impl<'r> Widget<Message, IcedRenderer<'r>> for KeyListener {
+        fn width(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn height(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn layout(&self, _renderer: &IcedRenderer<'r>, _limits: &layout::Limits) -> layout::Node {
+            layout::Node::new(Size::ZERO)
+        }
+
+        fn draw(
+            &self,
+            _renderer: &mut IcedRenderer<'r>,
+            _defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+        ) -> <IcedRenderer<'r> as Renderer>::Output {
+            AmethystIcedPrimitive::None
+        }
+
+        fn hash_layout(&self, _state: &mut Hasher) {}
+
+        fn on_event(
+            &mut self,
+            event: Event,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+            messages: &mut Vec<Message>,
+            _renderer: &IcedRenderer<'r>,
+            _clipboard: Option<&dyn Clipboard>,
+        ) {
+            match event {
+                Event::Keyboard(keyboard::Event::CharacterReceived(c)) => {
+                    messages.push(Message::Typed(c))
+                }
+                Event::Keyboard(keyboard::Event::Input {
+                    state: ButtonState::Pressed,
+                    key_code,
+                    ..
+                }) => messages.push(Message::Pressed(key_code)),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn click_id_sends_messages() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        harness.click_id("increment");
+        harness.click_id("increment");
+        harness.click_id("decrement");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![Message::Increment, Message::Increment, Message::Decrement]
+        );
+        assert_eq!(
+            harness.take_game_messages(),
+            vec![
+                GameMessage::Changed(1),
+                GameMessage::Changed(2),
+                GameMessage::Changed(1)
+            ]
+        );
+        assert_eq!(harness.sandbox().value, 1);
+    }
+
+    #[test]
+    fn messages_are_taken_once() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        harness.click_id("increment");
+        assert_eq!(harness.take_ui_messages().len(), 1);
+        assert_eq!(harness.take_game_messages().len(), 1);
+        assert_eq!(harness.take_ui_messages(), vec![]);
+        assert_eq!(harness.take_game_messages(), vec![]);
+    }
+
+    #[test]
+    fn clicks_outside_of_widgets_do_nothing() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        harness.click(Point::new(700., 500.));
+        assert_eq!(harness.take_ui_messages(), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No widget tagged with \"missing\" was drawn")]
+    fn click_id_panics_on_unknown_ids() {
+        IcedTestHarness::new(Counter::default()).click_id("missing");
+    }
+
+    #[test]
+    fn typing_sends_characters_and_keys() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        harness.type_text("ab");
+        harness.press_key(keyboard::KeyCode::Enter);
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![
+                Message::Typed('a'),
+                Message::Typed('b'),
+                Message::Pressed(keyboard::KeyCode::Enter)
+            ]
+        );
+        assert_eq!(harness.take_game_messages(), vec![]);
+    }
+
+    #[test]
+    fn layout_uses_the_mock_measurer() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        let layout = harness.layout();
+        assert_eq!(layout.children.len(), 4);
+
+        // Every character is half as wide as the font size
+        let text = layout.child(&[2]);
+        assert_eq!(text.bounds.width, 50.);
+        assert_eq!(text.bounds.height, 20.);
+
+        // The widgets of the column are stacked
+        let decrement = layout.child(&[1]);
+        assert_eq!(text.bounds.x, 0.);
+        assert_eq!(text.bounds.y, decrement.bounds.y + decrement.bounds.height);
+    }
+
+    #[test]
+    fn tagged_bounds_match_the_layout() {
+        let mut harness = IcedTestHarness::new(Counter::default());
+        let layout = harness.layout();
+        assert_eq!(
+            harness.bounds_of("increment"),
+            Some(layout.child(&[0]).bounds)
+        );
+        assert_eq!(
+            harness.bounds_of("decrement"),
+            Some(layout.child(&[1]).bounds)
+        );
+        assert_eq!(harness.bounds_of("missing"), None);
+    }
+}
@@ -0,0 +1,21 @@
+//! Running & rendering the UI without a window or GPU, mostly meant for tests on CI machines.
+//!
+//! ```ignore
+//! let mut harness = IcedTestHarness::new(MainMenu::default());
+//! harness.click_id("quit");
+//! assert_eq!(harness.take_game_messages(), vec![GameMessage::Quit]);
+//!
+//! let image = harness.snapshot(&mut SoftwareRenderer::new(800, 600));
+//! assert_snapshot(&image, "tests/snapshots/main_menu.png");
+//! ```
+
+mod harness;
+mod raster;
+mod snapshot;
+mod tessellator;
+
+pub use crate::backend::MockMeasurer;
+pub use harness::{IcedTestHarness, LayoutTree};
+pub use raster::SoftwareRenderer;
+pub use snapshot::{assert_snapshot, compare_snapshot, UPDATE_SNAPSHOTS};
+pub use tessellator::{TessellationStats, Tessellator};
@@ -0,0 +1,587 @@
+use amethyst::assets::Handle;
+use amethyst::renderer::Texture;
+use glyph_brush::{BrushAction, BrushError, FontId, GlyphBrush, GlyphBrushBuilder};
+use image::RgbaImage;
+
+use std::collections::HashMap;
+
+use crate::custom_widget::ImageHandle;
+use crate::geometry::{Geometry, GeometryCache};
+use crate::primitive::AmethystIcedPrimitive;
+use crate::vertex::{QuadInstance, ShadowVertex, TriangleVertex};
+
+/// A glyph placed on screen, along with the layer of its text
+#[derive(Clone, Copy, Debug)]
+struct GlyphQuad {
+    instance: QuadInstance,
+    layer: usize,
+}
+
+/// Rasterises primitive trees on the CPU, without any GPU.
+///
+/// Primitives are drawn the same way IcedPass does it: layer by layer, with
+/// all the triangles and quads first, then the images and finally the texts. Cached
+/// subtrees are kept between two calls to `render`, like between two frames.
+pub struct SoftwareRenderer {
+    width: u32,
+    height: u32,
+    clear_color: [f32; 4],
+    pixels: Vec<[f32; 4]>,
+    textures: HashMap<Handle<Texture>, RgbaImage>,
+    glyph_brush: GlyphBrush<'static, GlyphQuad>,
+    atlas: Vec<u8>,
+    atlas_size: (u32, u32),
+    glyphs: Vec<GlyphQuad>,
+    geometry: Geometry,
+    cache: GeometryCache,
+}
+
+impl SoftwareRenderer {
+    /// Creates a renderer using the same default font as IcedBundle
+    pub fn new(width: u32, height: u32) -> Self {
+        let square_ttf: &'static [u8] = include_bytes!("../../font/square.ttf");
+        let glyph_brush = GlyphBrushBuilder::using_font_bytes(square_ttf).build();
+        let atlas_size = glyph_brush.texture_dimensions();
+        SoftwareRenderer {
+            width,
+            height,
+            clear_color: [0., 0., 0., 0.],
+            pixels: vec![[0., 0., 0., 0.]; (width * height) as usize],
+            textures: HashMap::new(),
+            glyph_brush,
+            atlas: vec![0; (atlas_size.0 * atlas_size.1) as usize],
+            atlas_size,
+            glyphs: vec![],
+            geometry: Geometry {
+                backdrop: true,
+                ..Geometry::default()
+            },
+            cache: GeometryCache::default(),
+        }
+    }
+
+    pub fn with_clear_color(mut self, color: [f32; 4]) -> Self {
+        self.clear_color = color;
+        self
+    }
+
+    /// Adds a font, returning its id.
+    ///
+    /// Fonts must be added in the same order as they were loaded in the
+    /// FontCache, so that the ids stored in Text primitives match.
+    pub fn add_font_bytes(&mut self, bytes: &'static [u8]) -> FontId {
+        self.glyph_brush.add_font_bytes(bytes)
+    }
+
+    /// Makes the pixels of a texture available to Image and NinePatch primitives.
+    ///
+    /// Images using unknown textures are not drawn.
+    pub fn insert_texture(&mut self, handle: &ImageHandle, image: RgbaImage) {
+        match handle {
+            ImageHandle::Texture { handle, .. } => {
+                self.textures.insert(handle.clone(), image);
+            }
+        }
+    }
+
+    /// Clears the image and draws the primitive on it
+    pub fn render(&mut self, primitive: &AmethystIcedPrimitive) -> RgbaImage {
+        let clear_color = self.clear_color;
+        self.pixels.iter_mut().for_each(|pixel| *pixel = clear_color);
+
+        let mut geometry = std::mem::take(&mut self.geometry);
+        geometry.clear();
+        let glyph_brush = &mut self.glyph_brush;
+        primitive.render(&mut geometry, &mut self.cache, &mut |section| {
+            glyph_brush.queue(section)
+        });
+
+        self.process_glyphs();
+        let size = self.size();
+        for layer in 0..geometry.layers.len() {
+            for (batch, next) in geometry.batches(layer) {
+                geometry.shadows[batch.shadows as usize..next.shadows as usize]
+                    .chunks_exact(3)
+                    .for_each(|triangle| self.fill_shadow(triangle));
+                // There is no scene behind the UI here, only the clear color,
+                // which stays the same once blurred
+                let [r, g, b, a] = self.clear_color;
+                geometry.backdrops[batch.backdrops as usize..next.backdrops as usize]
+                    .chunks_exact(3)
+                    .for_each(|triangle| {
+                        let mut triangle = [triangle[0], triangle[1], triangle[2]];
+                        for vertex in &mut triangle {
+                            let alpha = AsRef::<[f32; 4]>::as_ref(&vertex.color)[3];
+                            vertex.color = [r, g, b, a * alpha].into();
+                        }
+                        self.fill_triangle(&triangle);
+                    });
+                geometry.quads[batch.quads as usize..next.quads as usize]
+                    .iter()
+                    .for_each(|quad| fill_quad(&mut self.pixels, size, quad, None));
+                geometry.vertices[batch.triangles as usize..next.triangles as usize]
+                    .chunks_exact(3)
+                    .for_each(|triangle| self.fill_triangle(triangle));
+            }
+            geometry.images[layer]
+                .iter()
+                .for_each(|(handle, instance)| self.draw_image(handle, instance));
+            self.draw_glyphs(layer);
+        }
+        self.geometry = geometry;
+
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let pixel = self.pixels[(y * self.width + x) as usize];
+            let to_u8 = |value: f32| (value.max(0.).min(1.) * 255.).round() as u8;
+            image::Rgba([
+                to_u8(pixel[0]),
+                to_u8(pixel[1]),
+                to_u8(pixel[2]),
+                to_u8(pixel[3]),
+            ])
+        })
+    }
+
+    /// Fills a triangle, interpolating the color of its vertices
+    fn fill_triangle(&mut self, triangle: &[TriangleVertex]) {
+        let position = |vertex: &TriangleVertex| *AsRef::<[f32; 2]>::as_ref(&vertex.position);
+        let color = |vertex: &TriangleVertex| *AsRef::<[f32; 4]>::as_ref(&vertex.color);
+        let [a, b, c] = [
+            position(&triangle[0]),
+            position(&triangle[1]),
+            position(&triangle[2]),
+        ];
+        let area = edge(a, b, c);
+        if area == 0. {
+            return;
+        }
+
+        let (x_range, y_range) = self.clip(
+            a[0].min(b[0]).min(c[0]),
+            a[1].min(b[1]).min(c[1]),
+            a[0].max(b[0]).max(c[0]),
+            a[1].max(b[1]).max(c[1]),
+        );
+        for y in y_range {
+            for x in x_range.clone() {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+                // Barycentric weights, positive inside whatever the winding
+                let wa = edge(b, c, p) / area;
+                let wb = edge(c, a, p) / area;
+                let wc = edge(a, b, p) / area;
+                if wa < 0. || wb < 0. || wc < 0. {
+                    continue;
+                }
+                let (ca, cb, cc) = (color(&triangle[0]), color(&triangle[1]), color(&triangle[2]));
+                let mut src = [0.; 4];
+                for i in 0..4 {
+                    src[i] = ca[i] * wa + cb[i] * wb + cc[i] * wc;
+                }
+                blend(&mut self.pixels, self.width, x, y, src);
+            }
+        }
+    }
+
+    /// Fills a triangle of a shadow, like the shadow fragment shader does
+    fn fill_shadow(&mut self, triangle: &[ShadowVertex]) {
+        let position = |vertex: &ShadowVertex| *AsRef::<[f32; 2]>::as_ref(&vertex.position);
+        let local = |vertex: &ShadowVertex| *AsRef::<[f32; 2]>::as_ref(&vertex.local);
+        let [a, b, c] = [
+            position(&triangle[0]),
+            position(&triangle[1]),
+            position(&triangle[2]),
+        ];
+        let area = edge(a, b, c);
+        if area == 0. {
+            return;
+        }
+        // Everything but the local position is the same for the whole shadow
+        let half_size = *AsRef::<[f32; 2]>::as_ref(&triangle[0].half_size);
+        let [r, g, b_, alpha] = *AsRef::<[f32; 4]>::as_ref(&triangle[0].color);
+        let [sigma, corner] = *AsRef::<[f32; 2]>::as_ref(&triangle[0].params);
+
+        let (x_range, y_range) = self.clip(
+            a[0].min(b[0]).min(c[0]),
+            a[1].min(b[1]).min(c[1]),
+            a[0].max(b[0]).max(c[0]),
+            a[1].max(b[1]).max(c[1]),
+        );
+        for y in y_range {
+            for x in x_range.clone() {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+                let wa = edge(b, c, p) / area;
+                let wb = edge(c, a, p) / area;
+                let wc = edge(a, b, p) / area;
+                if wa < 0. || wb < 0. || wc < 0. {
+                    continue;
+                }
+                let (la, lb, lc) = (local(&triangle[0]), local(&triangle[1]), local(&triangle[2]));
+                let point = [
+                    la[0] * wa + lb[0] * wb + lc[0] * wc,
+                    la[1] * wa + lb[1] * wb + lc[1] * wc,
+                ];
+                let value = shadow_intensity(point, half_size, sigma, corner);
+                blend(&mut self.pixels, self.width, x, y, [r, g, b_, alpha * value]);
+            }
+        }
+    }
+
+    /// Draws an image, using nearest sampling
+    fn draw_image(&mut self, handle: &Handle<Texture>, instance: &QuadInstance) {
+        let texture = match self.textures.get(handle) {
+            Some(texture) => texture,
+            None => {
+                log::warn!("No pixels were provided for an image, it won't be rendered.");
+                return;
+            }
+        };
+        let texel = |[u, v]: [f32; 2]| {
+            let texel = texture.get_pixel(
+                ((u * texture.width() as f32).max(0.) as u32).min(texture.width() - 1),
+                ((v * texture.height() as f32).max(0.) as u32).min(texture.height() - 1),
+            );
+            let to_f32 = |value: u8| value as f32 / 255.;
+            [
+                to_f32(texel[0]),
+                to_f32(texel[1]),
+                to_f32(texel[2]),
+                to_f32(texel[3]),
+            ]
+        };
+        let size = self.size();
+        fill_quad(&mut self.pixels, size, instance, Some(&texel));
+    }
+
+    /// Lays out the queued texts, caching the new glyphs in the CPU atlas
+    fn process_glyphs(&mut self) {
+        loop {
+            let atlas = &mut self.atlas;
+            let atlas_width = self.atlas_size.0;
+            let action = self.glyph_brush.process_queued(
+                |rect, data| {
+                    for (row, line) in data.chunks(rect.width() as usize).enumerate() {
+                        let start = ((rect.min.y + row as u32) * atlas_width + rect.min.x) as usize;
+                        atlas[start..start + line.len()].copy_from_slice(line);
+                    }
+                },
+                |glyph| GlyphQuad {
+                    instance: QuadInstance::glyph(glyph.pixel_coords, glyph.tex_coords, glyph.color),
+                    layer: glyph.z as usize,
+                },
+            );
+            match action {
+                Ok(BrushAction::Draw(glyphs)) => {
+                    self.glyphs = glyphs;
+                    return;
+                }
+                Ok(BrushAction::ReDraw) => return,
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    // The queue is kept, so the texts are processed again with the bigger atlas
+                    self.glyph_brush.resize_texture(suggested.0, suggested.1);
+                    self.atlas_size = suggested;
+                    self.atlas = vec![0; (suggested.0 * suggested.1) as usize];
+                }
+            }
+        }
+    }
+
+    fn draw_glyphs(&mut self, layer: usize) {
+        let (atlas_width, atlas_height) = self.atlas_size;
+        let atlas = &self.atlas;
+        // The atlas only holds the coverage, like the alpha of the glyph texture
+        let texel = |[u, v]: [f32; 2]| {
+            let tx = ((u * atlas_width as f32).max(0.) as u32).min(atlas_width - 1);
+            let ty = ((v * atlas_height as f32).max(0.) as u32).min(atlas_height - 1);
+            [0., 0., 0., atlas[(ty * atlas_width + tx) as usize] as f32 / 255.]
+        };
+        let size = self.size();
+        for glyph in self.glyphs.iter().filter(|glyph| glyph.layer == layer) {
+            fill_quad(&mut self.pixels, size, &glyph.instance, Some(&texel));
+        }
+    }
+
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the pixels covered by the given area which are inside the image
+    fn clip(
+        &self,
+        min_x: f32,
+        min_y: f32,
+        max_x: f32,
+        max_y: f32,
+    ) -> (std::ops::Range<u32>, std::ops::Range<u32>) {
+        clip(self.size(), min_x, min_y, max_x, max_y)
+    }
+}
+
+fn clip(
+    (width, height): (u32, u32),
+    min_x: f32,
+    min_y: f32,
+    max_x: f32,
+    max_y: f32,
+) -> (std::ops::Range<u32>, std::ops::Range<u32>) {
+    let clamp = |value: f32, max: u32| value.max(0.).min(max as f32) as u32;
+    (
+        clamp(min_x.floor(), width)..clamp(max_x.ceil(), width),
+        clamp(min_y.floor(), height)..clamp(max_y.ceil(), height),
+    )
+}
+
+/// Fills a quad like shaders/src/quad.frag, or like shaders/src/textured.frag
+/// when `texel` samples the texture of an image or a glyph at a uv
+fn fill_quad(
+    pixels: &mut [[f32; 4]],
+    size: (u32, u32),
+    quad: &QuadInstance,
+    texel: Option<&dyn Fn([f32; 2]) -> [f32; 4]>,
+) {
+    let [x, y, width, height] = *AsRef::<[f32; 4]>::as_ref(&quad.rect);
+    let [u, v, uv_width, uv_height] = *AsRef::<[f32; 4]>::as_ref(&quad.uv);
+    let color = *AsRef::<[f32; 4]>::as_ref(&quad.color);
+    let border_color = *AsRef::<[f32; 4]>::as_ref(&quad.border_color);
+    let [radius, border_width, feather, kind] = *AsRef::<[f32; 4]>::as_ref(&quad.params);
+    let [xx, xy, yx, yy] = *AsRef::<[f32; 4]>::as_ref(&quad.axes);
+    let [tx, ty] = *AsRef::<[f32; 2]>::as_ref(&quad.translation);
+    let determinant = xx * yy - yx * xy;
+    if determinant == 0. || width <= 0. || height <= 0. {
+        return;
+    }
+
+    let margin = feather / 2.;
+    let (left, top) = (x - margin, y - margin);
+    let (right, bottom) = (x + width + margin, y + height + margin);
+    let corners: Vec<[f32; 2]> = [[left, top], [right, top], [left, bottom], [right, bottom]]
+        .iter()
+        .map(|&[px, py]| [xx * px + yx * py + tx, xy * px + yy * py + ty])
+        .collect();
+    let (x_range, y_range) = clip(
+        size,
+        corners.iter().map(|c| c[0]).fold(f32::MAX, f32::min),
+        corners.iter().map(|c| c[1]).fold(f32::MAX, f32::min),
+        corners.iter().map(|c| c[0]).fold(f32::MIN, f32::max),
+        corners.iter().map(|c| c[1]).fold(f32::MIN, f32::max),
+    );
+    // The size of a pixel in the space of the quad, like fwidth in the shader
+    let pixel = 1. / determinant.abs().sqrt();
+    let half_size = [width / 2., height / 2.];
+    let coverage = |distance: f32| {
+        let feather = (feather * pixel).max(1e-4);
+        (0.5 - distance / feather).max(0.).min(1.)
+    };
+
+    for py in y_range {
+        for px in x_range.clone() {
+            let dx = px as f32 + 0.5 - tx;
+            let dy = py as f32 + 0.5 - ty;
+            let local_x = (yy * dx - yx * dy) / determinant;
+            let local_y = (xx * dy - xy * dx) / determinant;
+            if local_x < left || local_x > right || local_y < top || local_y > bottom {
+                continue;
+            }
+
+            let src = if let (Some(texel), true) = (texel, kind != QuadInstance::SOLID) {
+                let uv = [
+                    u + (local_x - x) / width * uv_width,
+                    v + (local_y - y) / height * uv_height,
+                ];
+                let texel = texel(uv);
+                if kind == QuadInstance::GLYPH {
+                    [color[0], color[1], color[2], color[3] * texel[3]]
+                } else {
+                    [
+                        texel[0] * color[0],
+                        texel[1] * color[1],
+                        texel[2] * color[2],
+                        texel[3] * color[3],
+                    ]
+                }
+            } else {
+                let point = [local_x - x - half_size[0], local_y - y - half_size[1]];
+                let outer = coverage(rounded_box(point, half_size, radius));
+                let fill = if border_width > 0. {
+                    let inner = coverage(rounded_box(
+                        point,
+                        [
+                            (half_size[0] - border_width).max(0.),
+                            (half_size[1] - border_width).max(0.),
+                        ],
+                        (radius - border_width).max(0.),
+                    ));
+                    // Mixed premultiplied, so that a transparent background doesn't darken the border
+                    let alpha = border_color[3] + (color[3] - border_color[3]) * inner;
+                    let mut mixed = [0., 0., 0., alpha];
+                    for i in 0..3 {
+                        let border = border_color[i] * border_color[3];
+                        let background = color[i] * color[3];
+                        mixed[i] = (border + (background - border) * inner) / alpha.max(1e-5);
+                    }
+                    mixed
+                } else {
+                    color
+                };
+                [fill[0], fill[1], fill[2], fill[3] * outer]
+            };
+            blend(pixels, size.0, px, py, src);
+        }
+    }
+}
+
+/// Signed distance to the edge of a rounded box centered on the origin
+fn rounded_box(point: [f32; 2], half_size: [f32; 2], radius: f32) -> f32 {
+    let q = [
+        point[0].abs() - half_size[0] + radius,
+        point[1].abs() - half_size[1] + radius,
+    ];
+    q[0].max(0.).hypot(q[1].max(0.)) + q[0].max(q[1]).min(0.) - radius
+}
+
+/// Blends a color over a pixel, like the ALPHA blend state of the pipelines
+fn blend(pixels: &mut [[f32; 4]], width: u32, x: u32, y: u32, src: [f32; 4]) {
+    let dst = &mut pixels[(y * width + x) as usize];
+    let alpha = src[3];
+    for i in 0..3 {
+        dst[i] = src[i] * alpha + dst[i] * (1. - alpha);
+    }
+    dst[3] = alpha + dst[3] * (1. - alpha);
+}
+
+/// Twice the signed area of the abc triangle
+fn edge(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// The coverage of a blurred rounded box at a point relative to its center,
+/// see shaders/src/shadow.frag
+fn shadow_intensity(point: [f32; 2], half_size: [f32; 2], sigma: f32, corner: f32) -> f32 {
+    const SAMPLES: usize = 4;
+    let gaussian = |x: f32| {
+        (-(x * x) / (2. * sigma * sigma)).exp() / ((2. * std::f32::consts::PI).sqrt() * sigma)
+    };
+    let erf = |x: f32| {
+        let a = x.abs();
+        let t = 1. + (0.278_393 + (0.230_389 + 0.078_108 * (a * a)) * a) * a;
+        let t = t * t;
+        x.signum() * (1. - 1. / (t * t))
+    };
+    let shadow_x = |x: f32, y: f32| {
+        let delta = (half_size[1] - corner - y.abs()).min(0.);
+        let curved = half_size[0] - corner + (corner * corner - delta * delta).max(0.).sqrt();
+        let scale = std::f32::consts::FRAC_1_SQRT_2 / sigma;
+        let low = 0.5 + 0.5 * erf((x - curved) * scale);
+        let high = 0.5 + 0.5 * erf((x + curved) * scale);
+        high - low
+    };
+
+    let low = point[1] - half_size[1];
+    let high = point[1] + half_size[1];
+    let start = (-3. * sigma).max(low).min(high);
+    let end = (3. * sigma).max(low).min(high);
+
+    let step = (end - start) / SAMPLES as f32;
+    let mut y = start + step * 0.5;
+    let mut value = 0.;
+    for _ in 0..SAMPLES {
+        value += shadow_x(point[0], point[1] - y) * gaussian(y) * step;
+        y += step;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::assert_snapshot;
+    use amethyst::assets::{AssetStorage, Loader};
+    use amethyst::core::rayon::ThreadPoolBuilder;
+    use amethyst::renderer::rendy::texture::TextureBuilder;
+    use amethyst::renderer::types::TextureData;
+    use iced_native::{Color, HorizontalAlignment, Rectangle};
+
+    use std::sync::Arc;
+
+    fn snapshot_path(name: &str) -> String {
+        format!(
+            "{}/tests/snapshots/{}.png",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        )
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// A texture handle without any data, the pixels are given to the renderer
+    fn texture_handle() -> Handle<Texture> {
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(1).build().unwrap());
+        let loader = Loader::new(".", pool);
+        let storage = AssetStorage::<Texture>::new();
+        loader.load_from_data(TextureData(TextureBuilder::new()), (), &storage)
+    }
+
+    #[test]
+    fn renders_quads() {
+        let mut renderer = SoftwareRenderer::new(64, 32).with_clear_color([0.1, 0.1, 0.1, 1.]);
+        let primitive = AmethystIcedPrimitive::Group(vec![
+            AmethystIcedPrimitive::Quad(rect(4., 4., 24., 24.), Some(Color::from_rgb(1., 0., 0.))),
+            AmethystIcedPrimitive::RoundedQuad {
+                bounds: rect(34., 4., 26., 24.),
+                background: Some(Color::from_rgb(0., 0.5, 1.)),
+                border_radius: 8.,
+                border_width: 2.,
+                border_color: Color::WHITE,
+            },
+            AmethystIcedPrimitive::Opacity(
+                0.5,
+                Box::new(AmethystIcedPrimitive::Quad(
+                    rect(14., 14., 30., 12.),
+                    Some(Color::from_rgb(0., 1., 0.)),
+                )),
+            ),
+        ]);
+        assert_snapshot(&renderer.render(&primitive), snapshot_path("raster_quads"));
+    }
+
+    #[test]
+    fn renders_textures() {
+        let mut renderer = SoftwareRenderer::new(48, 48).with_clear_color([0., 0., 0., 1.]);
+        let handle = texture_handle();
+        // Nearest sampling stretches each texel over 8x8 pixels
+        let checker = RgbaImage::from_fn(4, 4, |x, y| {
+            if (x + y) % 2 == 0 {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                image::Rgba([255, 255, 255, 128])
+            }
+        });
+        let image = ImageHandle::from((handle, 4, 4));
+        renderer.insert_texture(&image, checker);
+        let primitive = AmethystIcedPrimitive::Image(rect(8., 8., 32., 32.), image);
+        assert_snapshot(
+            &renderer.render(&primitive),
+            snapshot_path("raster_textures"),
+        );
+    }
+
+    #[test]
+    fn renders_glyphs() {
+        let mut renderer = SoftwareRenderer::new(96, 32).with_clear_color([0., 0., 0., 1.]);
+        let primitive = AmethystIcedPrimitive::Text {
+            bounds: rect(4., 4., 88., 24.),
+            content: "Hello".to_string(),
+            size: 20,
+            color: [1., 1., 1., 1.],
+            horizontal_alignment: HorizontalAlignment::Left,
+            font_id: FontId::default(),
+        };
+        assert_snapshot(&renderer.render(&primitive), snapshot_path("raster_glyphs"));
+    }
+}
@@ -0,0 +1,87 @@
+use failure::{format_err, Error};
+use image::RgbaImage;
+
+use std::path::{Path, PathBuf};
+
+/// Environment variable which, when set, writes or overwrites the golden
+/// files with the rendered images instead of comparing them.
+pub const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// Compares an image with the golden PNG file at the given path.
+///
+/// A missing golden file is an error, unless `UPDATE_SNAPSHOTS` is set, in
+/// which case it is written. On mismatch or when the golden file is missing,
+/// the rendered image is saved next to it, with an `.actual.png` extension.
+pub fn compare_snapshot<P: AsRef<Path>>(image: &RgbaImage, path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        image.save(path)?;
+        return Ok(());
+    }
+    if !path.exists() {
+        let actual = actual_path(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        image.save(&actual)?;
+        return Err(format_err!(
+            "Snapshot {} doesn't exist, run the test with {} set to create it. \
+             The rendered image was saved to {}",
+            path.display(),
+            UPDATE_SNAPSHOTS,
+            actual.display()
+        ));
+    }
+
+    let golden = image::open(path)?.to_rgba();
+    let mismatch = if golden.dimensions() != image.dimensions() {
+        Some(format!(
+            "expected a {:?} image, got {:?}",
+            golden.dimensions(),
+            image.dimensions()
+        ))
+    } else {
+        let different = golden
+            .pixels()
+            .zip(image.pixels())
+            .filter(|(expected, actual)| expected != actual)
+            .count();
+        if different > 0 {
+            Some(format!("{} pixels are different", different))
+        } else {
+            None
+        }
+    };
+
+    match mismatch {
+        None => Ok(()),
+        Some(mismatch) => {
+            let actual = actual_path(path);
+            image.save(&actual)?;
+            Err(format_err!(
+                "Snapshot {} doesn't match: {}. The rendered image was saved to {}",
+                path.display(),
+                mismatch,
+                actual.display()
+            ))
+        }
+    }
+}
+
+/// Panics if the image doesn't match its golden file, see `compare_snapshot`
+pub fn assert_snapshot<P: AsRef<Path>>(image: &RgbaImage, path: P) {
+    if let Err(error) = compare_snapshot(image, path) {
+        panic!("{}", error);
+    }
+}
+
+fn actual_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.actual.png", stem))
+}
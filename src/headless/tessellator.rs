@@ -0,0 +1,183 @@
+use amethyst::renderer::util::slice_as_bytes;
+use glyph_brush::{BrushAction, BrushError, GlyphBrush, GlyphBrushBuilder};
+
+use crate::geometry::{Geometry, GeometryCache};
+use crate::primitive::AmethystIcedPrimitive;
+use crate::vertex::QuadInstance;
+
+/// What a Tessellator produced for a primitive
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TessellationStats {
+    pub triangle_vertices: usize,
+    pub shadow_vertices: usize,
+    pub backdrop_vertices: usize,
+    pub quads: usize,
+    pub images: usize,
+    pub texts: usize,
+    pub layers: usize,
+    /// How many Cached subtrees are kept for the next frame
+    pub cached: usize,
+    /// Whether the buffers were written, i.e. the frame looked different
+    pub uploaded: bool,
+}
+
+/// Does what IcedPass does to prepare a frame, without a GPU. It is mostly
+/// meant for benchmarks.
+///
+/// The primitive is turned into vertices, its texts are laid out like
+/// IcedDrawGlyphSystem does it, and everything is then copied to buffers
+/// standing for the vertex buffers of the pass.
+///
+/// Like IcedPass, it keeps its buffers and the geometry of Cached primitives
+/// between calls, and only writes the buffers when the frame looks different,
+/// so rendering the same primitive twice measures a steady frame.
+#[derive(Debug)]
+pub struct Tessellator {
+    geometry: Geometry,
+    /// The geometry of the previous frame
+    uploaded: Geometry,
+    cache: GeometryCache,
+    glyph_brush: GlyphBrush<'static, QuadInstance>,
+    glyphs: Vec<QuadInstance>,
+    /// The triangle, shadow, backdrop, quad, image and glyph buffers
+    buffers: [Vec<u8>; 6],
+}
+
+impl Default for Tessellator {
+    fn default() -> Self {
+        let square_ttf: &'static [u8] = include_bytes!("../../font/square.ttf");
+        Tessellator {
+            geometry: Geometry {
+                backdrop: true,
+                ..Geometry::default()
+            },
+            uploaded: Geometry {
+                backdrop: true,
+                ..Geometry::default()
+            },
+            cache: GeometryCache::default(),
+            glyph_brush: GlyphBrushBuilder::using_font_bytes(square_ttf).build(),
+            glyphs: vec![],
+            buffers: Default::default(),
+        }
+    }
+}
+
+impl Tessellator {
+    /// Turns the primitive into vertices, leaving its texts aside
+    pub fn tessellate(&mut self, primitive: &AmethystIcedPrimitive) -> TessellationStats {
+        self.geometry.clear();
+        let mut texts = 0;
+        primitive.render(&mut self.geometry, &mut self.cache, &mut |_| texts += 1);
+        self.stats(texts, false)
+    }
+
+    /// Turns the primitive into vertices and glyphs, then uploads them
+    pub fn prepare(&mut self, primitive: &AmethystIcedPrimitive) -> TessellationStats {
+        self.geometry.clear();
+        let mut texts = 0;
+        let glyph_brush = &mut self.glyph_brush;
+        primitive.render(&mut self.geometry, &mut self.cache, &mut |section| {
+            texts += 1;
+            glyph_brush.queue(section)
+        });
+        let glyphs_changed = self.process_glyphs();
+
+        // Like IcedPass, the buffers are left as they are when nothing changed
+        let geometry_changed = self.geometry != self.uploaded;
+        if geometry_changed {
+            let geometry = &self.geometry;
+            let images = geometry.images.iter().flatten();
+            let images = images.map(|(_, instance)| *instance).collect::<Vec<_>>();
+            let buffers = &mut self.buffers;
+            upload(&mut buffers[0], &geometry.vertices);
+            upload(&mut buffers[1], &geometry.shadows);
+            upload(&mut buffers[2], &geometry.backdrops);
+            upload(&mut buffers[3], &geometry.quads);
+            upload(&mut buffers[4], &images);
+        }
+        if glyphs_changed {
+            upload(&mut self.buffers[5], &self.glyphs);
+        }
+        let stats = self.stats(texts, geometry_changed || glyphs_changed);
+        if geometry_changed {
+            // The previous geometry keeps its memory for the next frame
+            std::mem::swap(&mut self.geometry, &mut self.uploaded);
+        }
+        stats
+    }
+
+    /// Lays out the queued texts, the glyphs themselves aren't rasterised anywhere.
+    ///
+    /// Returns whether the glyphs changed.
+    fn process_glyphs(&mut self) -> bool {
+        loop {
+            let action = self.glyph_brush.process_queued(
+                |_, _| {},
+                |glyph| QuadInstance::glyph(glyph.pixel_coords, glyph.tex_coords, glyph.color),
+            );
+            match action {
+                Ok(BrushAction::Draw(glyphs)) => {
+                    self.glyphs = glyphs;
+                    return true;
+                }
+                Ok(BrushAction::ReDraw) => return false,
+                Err(BrushError::TextureTooSmall { suggested }) => {
+                    self.glyph_brush.resize_texture(suggested.0, suggested.1)
+                }
+            }
+        }
+    }
+
+    fn stats(&self, texts: usize, uploaded: bool) -> TessellationStats {
+        TessellationStats {
+            triangle_vertices: self.geometry.vertices.len(),
+            shadow_vertices: self.geometry.shadows.len(),
+            backdrop_vertices: self.geometry.backdrops.len(),
+            quads: self.geometry.quads.len(),
+            images: self.geometry.images.iter().map(Vec::len).sum(),
+            texts,
+            layers: self.geometry.layers.len(),
+            cached: self.cache.len(),
+            uploaded,
+        }
+    }
+}
+
+/// Copies the items to the buffer, which only grows when they don't fit, like
+/// the vertex buffers of IcedPass
+fn upload<T>(buffer: &mut Vec<u8>, items: &[T]) {
+    let bytes = slice_as_bytes(items);
+    if buffer.len() < bytes.len() {
+        buffer.resize(bytes.len().next_power_of_two(), 0);
+    }
+    buffer[..bytes.len()].copy_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced_native::{Color, Rectangle};
+
+    fn quad(x: f32) -> AmethystIcedPrimitive {
+        let bounds = Rectangle {
+            x,
+            y: 0.,
+            width: 10.,
+            height: 10.,
+        };
+        AmethystIcedPrimitive::Quad(bounds, Some(Color::WHITE))
+    }
+
+    #[test]
+    fn unchanged_frames_are_not_uploaded_again() {
+        let mut tessellator = Tessellator::default();
+        assert!(tessellator.prepare(&quad(0.)).uploaded);
+        let steady = tessellator.prepare(&quad(0.));
+        assert!(!steady.uploaded);
+        assert_eq!(steady.quads, 1);
+
+        assert!(tessellator.prepare(&quad(5.)).uploaded);
+        assert!(!tessellator.prepare(&quad(5.)).uploaded);
+    }
+}
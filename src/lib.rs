@@ -1,30 +1,48 @@
+pub mod animation;
+pub mod application;
+mod backdrop;
 mod backend;
 mod bundle;
 mod custom_widget;
+mod geometry;
+pub mod headless;
 mod pass;
 mod pipelines;
 mod plugin;
+pub mod prefab;
 mod primitive;
 mod resources;
 pub mod sandbox;
+mod subscription;
 mod systems;
+mod tessellation;
 mod uniform;
 mod vertex;
 pub mod widget;
 pub mod style;
 
-pub use bundle::IcedBundle;
+pub use animation::{Easing, Motion, StateTransition, Tween};
+pub use application::{Application, ApplicationData, ApplicationSandbox};
+pub use backdrop::SCENE_TARGET;
+pub use backend::{Defaults, TextDefaults};
+pub use bundle::{IcedApplicationBundle, IcedBundle};
 pub use custom_widget::*;
 pub use plugin::IcedUI;
-pub use sandbox::{Element, Sandbox, SandboxContainer};
+pub use primitive::AmethystIcedPrimitive;
+pub use sandbox::{Element, Sandbox, SandboxContainer, SandboxData};
+pub use subscription::Subscription;
+pub use systems::{FocusActions, IcedFocusSystem, IcedPrefabSystem};
 
 // Conveniently re-exports iced's Widget types
 pub use iced_native::{
-    button::State as ButtonState, slider::State as SliderState, pane_grid::self, Align, Color, Length, Text, HorizontalAlignment, VerticalAlignment, Font
+    button::State as ButtonState, slider::State as SliderState, pane_grid::self, Align, Color, Command, Length, Text, HorizontalAlignment, VerticalAlignment, Font, Point, Vector
 };
 
+// Used by AmethystIcedPrimitive::Transform
+pub use glam::Mat3;
+
 pub use widget::*;
 pub use style::*;
 pub use resources::*;
 
-pub type IcedGlyphBrush = glyph_brush::GlyphBrush<'static, (u32, Vec<crate::vertex::TextVertex>)>;
+pub type IcedGlyphBrush = glyph_brush::GlyphBrush<'static, (u32, crate::vertex::QuadInstance)>;
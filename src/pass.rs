@@ -1,29 +1,44 @@
-use amethyst::ecs::{Read, SystemData, World, Write};
+use amethyst::ecs::{Read, SystemData, World, Write, WriteExpect};
 use amethyst::renderer::{
     rendy::{
         command::{QueueId, RenderPassEncoder},
         factory::Factory,
         graph::{
             render::{PrepareResult, RenderGroup, RenderGroupDesc},
-            GraphContext, NodeBuffer, NodeImage,
+            GraphContext, ImageAccess, NodeBuffer, NodeImage,
         },
         hal::{self},
     },
     types::Backend,
+    util::ChangeDetection,
 };
 use glsl_layout::AsStd140;
 
-use crate::pipelines::{ImagePipeline, TextPipeline, TrianglePipeline};
-use crate::systems::TextVertexContainer;
-use crate::{primitive::IcedPrimitives, vertex::TriangleVertex};
+use crate::backdrop::{sampled_image_access, SampledImage};
+use crate::geometry::{Geometry, GeometryCache};
+use crate::pipelines::{ImagePipeline, TextPipeline, TrianglePipeline, UnitQuad};
+use crate::primitive::IcedPrimitives;
+use crate::systems::GlyphInstances;
+use crate::IcedGlyphBrush;
 
 #[derive(Default, Debug)]
-pub struct IcedPassDesc;
+pub struct IcedPassDesc {
+    /// Whether the blurred scene is given as an image, see `crate::backdrop`
+    pub backdrop: bool,
+}
 
 impl<B: Backend> RenderGroupDesc<B, World> for IcedPassDesc {
+    fn images(&self) -> Vec<ImageAccess> {
+        if self.backdrop {
+            vec![sampled_image_access()]
+        } else {
+            vec![]
+        }
+    }
+
     fn build(
         self,
-        _ctx: &GraphContext<B>,
+        ctx: &GraphContext<B>,
         factory: &mut Factory<B>,
         _queue: QueueId,
         _world: &World,
@@ -31,13 +46,18 @@ impl<B: Backend> RenderGroupDesc<B, World> for IcedPassDesc {
         framebuffer_height: u32,
         subpass: hal::pass::Subpass<'_, B>,
         _buffers: Vec<NodeBuffer>,
-        _images: Vec<NodeImage>,
+        images: Vec<NodeImage>,
     ) -> Result<Box<dyn RenderGroup<B, World>>, failure::Error> {
+        let blurred = match images.first() {
+            Some(image) => Some(SampledImage::new(ctx, factory, image)?),
+            None => None,
+        };
         let triangle_pipeline = TrianglePipeline::create_pipeline(
             factory,
             subpass,
             framebuffer_width,
             framebuffer_height,
+            blurred,
         )?;
 
         let image_pipeline = ImagePipeline::create_pipeline(
@@ -50,21 +70,78 @@ impl<B: Backend> RenderGroupDesc<B, World> for IcedPassDesc {
         let text_pipeline =
             TextPipeline::create_pipeline(factory, subpass, framebuffer_width, framebuffer_height)?;
 
+        let backdrop = triangle_pipeline.has_backdrop();
         Ok(Box::new(IcedPass {
+            unit_quad: UnitQuad::new(factory)?,
+            geometry: Geometry {
+                backdrop,
+                ..Geometry::default()
+            },
+            next_geometry: Geometry {
+                backdrop,
+                ..Geometry::default()
+            },
+            cache: GeometryCache::default(),
+            generation: 0,
+            missing_images: false,
+            written: vec![],
+            change: ChangeDetection::default(),
             triangle_pipeline,
             image_pipeline,
             text_pipeline,
-            prev_hash_layout: vec![0, 0, 0, 0, 0],
         }))
     }
 }
 
 #[derive(Debug)]
 pub struct IcedPass<B: Backend> {
+    /// Stretched over every quad, image and glyph
+    unit_quad: UnitQuad<B>,
+    /// The vertices of the frame being drawn
+    geometry: Geometry,
+    /// Where the next frame is rendered, to be compared with the current one.
+    /// The two are swapped when it differs, so both keep their memory.
+    next_geometry: Geometry,
+    cache: GeometryCache,
+    /// Changes whenever the geometry does
+    generation: u64,
+    /// Whether some images weren't loaded yet, so they are queued again next frame
+    missing_images: bool,
+    /// The geometry & glyph generations held by the buffers of each frame in flight
+    written: Vec<Option<(u64, u64)>>,
+    change: ChangeDetection,
     pub triangle_pipeline: TrianglePipeline<B>,
     pub image_pipeline: ImagePipeline<B>,
     pub text_pipeline: TextPipeline<B>,
-    pub prev_hash_layout: Vec<u64>,
+}
+
+impl<B: Backend> IcedPass<B> {
+    /// Queues the images of every layer in the image pipeline, returning
+    /// whether their textures changed
+    fn insert_images(&mut self, factory: &Factory<B>, world: &World) -> bool {
+        let mut changed = false;
+        self.missing_images = false;
+        self.image_pipeline.clear();
+        for images in &self.geometry.images {
+            self.image_pipeline.begin_layer();
+            for (handle, instance) in images {
+                let info = self.image_pipeline.textures.insert(
+                    factory,
+                    world,
+                    handle,
+                    hal::image::Layout::ShaderReadOnlyOptimal,
+                );
+                match info {
+                    Some((id, texture_changed)) => {
+                        changed |= texture_changed;
+                        self.image_pipeline.insert(id, *instance);
+                    }
+                    None => self.missing_images = true,
+                }
+            }
+        }
+        changed
+    }
 }
 
 impl<B: Backend> RenderGroup<B, World> for IcedPass<B> {
@@ -78,57 +155,56 @@ impl<B: Backend> RenderGroup<B, World> for IcedPass<B> {
     ) -> PrepareResult {
         let mut iced_primitives = Write::<'_, IcedPrimitives>::fetch(world);
 
-        self.image_pipeline.reset(factory, index);
-        self.text_pipeline.reset(factory, index, world);
-
-        self.triangle_pipeline.vertices = vec![];
         self.triangle_pipeline.uniforms.write(
             factory,
             index,
             self.triangle_pipeline.transform.std140(),
         );
+        self.image_pipeline
+            .uniforms
+            .write(factory, index, self.image_pipeline.transform.std140());
+        self.text_pipeline.reset(factory, index, world);
 
+        // The texts are queued on every frame, the glyph brush only lays
+        // them out again when they changed
+        self.next_geometry.clear();
         if let Some(iced_primitives) = iced_primitives.0.take() {
-            iced_primitives.render(self, factory, index, world);
+            let mut glyph_brush = WriteExpect::<'_, IcedGlyphBrush>::fetch(world);
+            iced_primitives.render(&mut self.next_geometry, &mut self.cache, &mut |section| {
+                glyph_brush.queue(section)
+            });
+        }
+        let mut changed = false;
+        if self.next_geometry != self.geometry || self.missing_images {
+            std::mem::swap(&mut self.geometry, &mut self.next_geometry);
+            self.generation = self.generation.wrapping_add(1);
+            changed |= self.insert_images(factory, world);
         }
 
-        self.triangle_pipeline.vertex.write(
-            factory,
-            index,
-            self.triangle_pipeline.vertices.len() as u64,
-            Some(
-                self.triangle_pipeline
-                    .vertices
-                    .clone()
-                    .into_iter()
-                    .collect::<Box<[TriangleVertex]>>(),
-            ),
-        );
-        self.image_pipeline.vertex.write(
-            factory,
-            index,
-            self.image_pipeline.batches.count() as u64,
-            Some(self.image_pipeline.batches.data()),
-        );
-
-        let text_vertex_container = Read::<'_, TextVertexContainer>::fetch(world);
-        self.text_pipeline.vertex.write(
-            factory,
-            index,
-            text_vertex_container.0.len() as u64,
-            Some(&(text_vertex_container.0)),
-        );
+        // The buffers of each frame in flight are only written when what
+        // they hold is out of date
+        let glyph_instances = Read::<'_, GlyphInstances>::fetch(world);
+        let generations = (self.generation, glyph_instances.generation);
+        if self.written.len() <= index {
+            self.written.resize(index + 1, None);
+        }
+        if self.written[index] != Some(generations) {
+            self.triangle_pipeline.write(factory, index, &self.geometry);
+            self.image_pipeline.write(factory, index);
+            self.text_pipeline.instances.write(
+                factory,
+                index,
+                glyph_instances.instances.len() as u64,
+                Some(&glyph_instances.instances),
+            );
+            self.written[index] = Some(generations);
+            changed = true;
+        }
 
-        self.text_pipeline.textures.maintain(factory, world);
-        self.image_pipeline.textures.maintain(factory, world);
+        changed |= self.text_pipeline.textures.maintain(factory, world);
+        changed |= self.image_pipeline.textures.maintain(factory, world);
 
-        /*
-        if let Some(hash) = self.prev_hash_layout.get(index) {
-            if iced_primitives.1 == *hash { return PrepareResult::DrawReuse; }
-        }
-        self.prev_hash_layout[index] = iced_primitives.1;
-        */
-        PrepareResult::DrawRecord
+        self.change.prepare_result(index, changed)
     }
 
     fn draw_inline(
@@ -138,9 +214,23 @@ impl<B: Backend> RenderGroup<B, World> for IcedPass<B> {
         _subpass: hal::pass::Subpass<'_, B>,
         aux: &World,
     ) {
-        self.triangle_pipeline.draw(&mut encoder, index);
-        self.image_pipeline.draw(&mut encoder, index);
-        self.text_pipeline.draw(&mut encoder, index, aux);
+        let layers = Read::<'_, GlyphInstances>::fetch(aux)
+            .layers
+            .len()
+            .max(self.geometry.layers.len());
+        for layer in 0..layers {
+            self.triangle_pipeline.draw_layer(
+                &mut encoder,
+                index,
+                &self.unit_quad,
+                &self.geometry,
+                layer,
+            );
+            self.image_pipeline
+                .draw_layer(&mut encoder, index, &self.unit_quad, layer);
+            self.text_pipeline
+                .draw_layer(&mut encoder, index, &self.unit_quad, aux, layer);
+        }
     }
 
     fn dispose(self: Box<Self>, factory: &mut Factory<B>, _aux: &World) {
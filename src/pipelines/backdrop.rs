@@ -0,0 +1,131 @@
+use amethyst::renderer::{
+    pipeline::{PipelineDescBuilder, PipelinesBuilder},
+    rendy::{
+        command::RenderPassEncoder,
+        factory::Factory,
+        hal::pso::{self, ShaderStageFlags},
+        hal::{self, device::Device},
+        mesh::AsVertex,
+        shader::{Shader, SpirvShader},
+    },
+    types::Backend,
+    util::simple_shader_set,
+};
+
+use crate::backdrop::SampledImage;
+use crate::pipelines::triangle::TRIANGLE_VERTEX;
+use crate::vertex::TriangleVertex;
+
+lazy_static::lazy_static! {
+    static ref BACKDROP_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/backdrop.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
+}
+
+/// Fills triangles with the blurred scene, see `crate::backdrop`.
+///
+/// It is owned by the TrianglePipeline, whose uniform and vertices it uses.
+#[derive(Debug)]
+pub struct BackdropPipeline<B: Backend> {
+    pipeline: B::GraphicsPipeline,
+    pipeline_layout: B::PipelineLayout,
+    blurred: SampledImage<B>,
+    /// The inverse size of the framebuffer, pushed to the fragment shader
+    constants: [u32; 2],
+}
+
+impl<B: Backend> BackdropPipeline<B> {
+    pub(crate) fn create_pipeline(
+        factory: &Factory<B>,
+        subpass: hal::pass::Subpass<'_, B>,
+        fb_width: u32,
+        fb_height: u32,
+        uniform_layout: &B::DescriptorSetLayout,
+        blurred: SampledImage<B>,
+    ) -> Result<Self, failure::Error> {
+        let layouts = vec![uniform_layout, blurred.raw_layout()];
+        let pipeline_layout = unsafe {
+            factory
+                .device()
+                .create_pipeline_layout(layouts, vec![(ShaderStageFlags::FRAGMENT, 0..2)])
+        }?;
+
+        let shader_vertex = unsafe {
+            TRIANGLE_VERTEX
+                .module(factory)
+                .expect("Failed to create triangle_vertex module")
+        };
+        let shader_fragment = unsafe {
+            BACKDROP_FRAGMENT
+                .module(factory)
+                .expect("Failed to create backdrop_fragment module")
+        };
+
+        let pipes = PipelinesBuilder::new()
+            .with_pipeline(
+                PipelineDescBuilder::new()
+                    .with_vertex_desc(&[(TriangleVertex::vertex(), pso::VertexInputRate::Vertex)])
+                    .with_input_assembler(pso::InputAssemblerDesc::new(
+                        hal::Primitive::TriangleList,
+                    ))
+                    .with_shaders(simple_shader_set(&shader_vertex, Some(&shader_fragment)))
+                    .with_layout(&pipeline_layout)
+                    .with_subpass(subpass)
+                    .with_framebuffer_size(fb_width, fb_height)
+                    .with_blend_targets(vec![pso::ColorBlendDesc {
+                        mask: pso::ColorMask::ALL,
+                        blend: Some(pso::BlendState::ALPHA),
+                    }]),
+            )
+            .build(factory, None);
+
+        unsafe {
+            factory.destroy_shader_module(shader_vertex);
+            factory.destroy_shader_module(shader_fragment);
+        }
+
+        match pipes {
+            Err(e) => {
+                unsafe {
+                    factory.device().destroy_pipeline_layout(pipeline_layout);
+                }
+                Err(e)
+            }
+            Ok(mut pipeline) => Ok(BackdropPipeline {
+                pipeline: pipeline.remove(0),
+                pipeline_layout,
+                blurred,
+                constants: [
+                    (1. / fb_width as f32).to_bits(),
+                    (1. / fb_height as f32).to_bits(),
+                ],
+            }),
+        }
+    }
+
+    pub fn dispose(self, factory: &mut Factory<B>) {
+        unsafe {
+            factory.device().destroy_graphics_pipeline(self.pipeline);
+            factory
+                .device()
+                .destroy_pipeline_layout(self.pipeline_layout);
+        }
+    }
+
+    /// Binds the pipeline and the blurred scene, the uniform and vertices are bound by the caller
+    pub fn bind(&self, encoder: &mut RenderPassEncoder<'_, B>) -> &B::PipelineLayout {
+        encoder.bind_graphics_pipeline(&self.pipeline);
+        self.blurred.bind(&self.pipeline_layout, 1, encoder);
+        unsafe {
+            encoder.push_constants(
+                &self.pipeline_layout,
+                ShaderStageFlags::FRAGMENT,
+                0,
+                &self.constants,
+            );
+        }
+        &self.pipeline_layout
+    }
+}
@@ -4,10 +4,9 @@ use amethyst::renderer::{
     rendy::{
         command::RenderPassEncoder,
         factory::Factory,
-        hal::pso::{self, ShaderStageFlags},
+        hal::pso,
         hal::{self, device::Device},
-        mesh::AsVertex,
-        shader::{Shader, SpirvShader},
+        shader::Shader,
     },
     submodules::{DynamicUniform, DynamicVertexBuffer, TextureId, TextureSub},
     types::Backend,
@@ -16,29 +15,22 @@ use amethyst::renderer::{
 use glam::{Mat4, Vec3};
 use glsl_layout::{mat4, AsStd140};
 
-use crate::vertex::image::ImageVertex;
-
-lazy_static::lazy_static! {
-     static ref IMAGE_VERTEX: SpirvShader = SpirvShader::from_bytes(
-        include_bytes!("../../shaders/compiled/image.vert.spv"),
-        ShaderStageFlags::VERTEX,
-        "main",
-    ).unwrap();
-
-    static ref IMAGE_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
-        include_bytes!("../../shaders/compiled/image.frag.spv"),
-        ShaderStageFlags::FRAGMENT,
-        "main",
-    ).unwrap();
-}
+use crate::pipelines::quad::{
+    quad_input_assembler, quad_vertex_desc, UnitQuad, QUAD_VERTEX, TEXTURED_FRAGMENT,
+};
+use crate::vertex::QuadInstance;
 
+/// Draws textured quads, one instance of the unit quad per image
 #[derive(Debug)]
 pub struct ImagePipeline<B: Backend> {
     pipeline: B::GraphicsPipeline,
     pipeline_layout: B::PipelineLayout,
     pub textures: TextureSub<B>,
-    pub vertex: DynamicVertexBuffer<B, ImageVertex>,
-    pub batches: OrderedOneLevelBatch<TextureId, ImageVertex>,
+    pub instances: DynamicVertexBuffer<B, QuadInstance>,
+    /// The images of every layer, batched by texture
+    pub layers: Vec<OrderedOneLevelBatch<TextureId, QuadInstance>>,
+    /// The first instance of every layer in the instance buffer
+    offsets: Vec<u32>,
     pub uniforms: DynamicUniform<B, ImageUniform>,
     pub transform: ImageUniform,
 }
@@ -60,27 +52,24 @@ impl<B: Backend> ImagePipeline<B> {
                 .create_pipeline_layout(layouts, None as Option<(_, _)>)
         }?;
 
-        let vertex = DynamicVertexBuffer::<B, ImageVertex>::new();
-        let batches = OrderedOneLevelBatch::<TextureId, ImageVertex>::default();
+        let instances = DynamicVertexBuffer::<B, QuadInstance>::new();
 
         let shader_vertex = unsafe {
-            IMAGE_VERTEX
+            QUAD_VERTEX
                 .module(factory)
-                .expect("Failed to create triangle_vertex module")
+                .expect("Failed to create quad_vertex module")
         };
         let shader_fragment = unsafe {
-            IMAGE_FRAGMENT
+            TEXTURED_FRAGMENT
                 .module(factory)
-                .expect("Failed to create triangle_fragment module")
+                .expect("Failed to create textured_fragment module")
         };
 
         let pipes = PipelinesBuilder::new()
             .with_pipeline(
                 PipelineDescBuilder::new()
-                    .with_vertex_desc(&[(ImageVertex::vertex(), pso::VertexInputRate::Vertex)])
-                    .with_input_assembler(pso::InputAssemblerDesc::new(
-                        hal::Primitive::TriangleList,
-                    ))
+                    .with_vertex_desc(&quad_vertex_desc())
+                    .with_input_assembler(quad_input_assembler())
                     .with_shaders(simple_shader_set(&shader_vertex, Some(&shader_fragment)))
                     .with_layout(&pipeline_layout)
                     .with_subpass(subpass)
@@ -125,8 +114,9 @@ impl<B: Backend> ImagePipeline<B> {
                     pipeline_layout,
                     textures,
                     uniforms,
-                    vertex,
-                    batches,
+                    instances,
+                    layers: vec![],
+                    offsets: vec![],
                     transform,
                 })
             }
@@ -142,24 +132,62 @@ impl<B: Backend> ImagePipeline<B> {
         }
     }
 
-    pub fn reset(&mut self, factory: &Factory<B>, index: usize) {
-        self.batches.swap_clear();
-        self.uniforms.write(factory, index, self.transform.std140());
+    /// Forgets the queued images, before queuing those of a new frame
+    pub fn clear(&mut self) {
+        self.layers.clear();
+        self.offsets.clear();
     }
 
-    pub fn draw(&self, encoder: &mut RenderPassEncoder<'_, B>, index: usize) {
-        if self.batches.count() == 0 {
-            return;
+    pub fn begin_layer(&mut self) {
+        self.layers.push(OrderedOneLevelBatch::default());
+    }
+
+    /// Queues an image in the current layer
+    pub fn insert(&mut self, texture: TextureId, instance: QuadInstance) {
+        if self.layers.is_empty() {
+            self.begin_layer();
         }
+        self.layers
+            .last_mut()
+            .unwrap()
+            .insert(texture, Some(instance));
+    }
+
+    /// Uploads the instances of every layer, one after the other
+    pub fn write(&mut self, factory: &Factory<B>, index: usize) {
+        let mut count = 0;
+        self.offsets.clear();
+        for layer in &self.layers {
+            self.offsets.push(count as u32);
+            count += layer.data().len();
+        }
+        self.instances.write(
+            factory,
+            index,
+            count as u64,
+            self.layers.iter().map(|layer| layer.data()),
+        );
+    }
+
+    pub fn draw_layer(
+        &self,
+        encoder: &mut RenderPassEncoder<'_, B>,
+        index: usize,
+        unit_quad: &UnitQuad<B>,
+        layer: usize,
+    ) {
+        let (batches, offset) = match (self.layers.get(layer), self.offsets.get(layer)) {
+            (Some(batches), Some(offset)) if batches.count() > 0 => (batches, *offset),
+            _ => return,
+        };
 
         encoder.bind_graphics_pipeline(&self.pipeline);
         self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
-        self.vertex.bind(index, 0, 0, encoder);
-        self.batches.iter().for_each(|(&tex, verts)| {
+        unit_quad.bind(encoder);
+        self.instances.bind(index, 1, 0, encoder);
+        batches.iter().for_each(|(&tex, instances)| {
             self.textures.bind(&self.pipeline_layout, 1, tex, encoder);
-            unsafe {
-                encoder.draw(verts, 0..1);
-            }
+            unit_quad.draw(encoder, instances.start + offset..instances.end + offset);
         });
     }
 }
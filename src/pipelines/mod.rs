@@ -1,7 +1,10 @@
+pub(crate) mod backdrop;
 pub(crate) mod image;
+pub(crate) mod quad;
 pub(crate) mod text;
 pub(crate) mod triangle;
 
 pub(crate) use image::ImagePipeline;
+pub(crate) use quad::UnitQuad;
 pub(crate) use text::TextPipeline;
 pub(crate) use triangle::TrianglePipeline;
@@ -0,0 +1,95 @@
+use amethyst::renderer::{
+    rendy::{
+        command::RenderPassEncoder,
+        factory::Factory,
+        hal,
+        hal::pso::{self, ShaderStageFlags},
+        memory::Dynamic,
+        mesh::{AsVertex, VertexFormat},
+        resource::{Buffer, BufferInfo, Escape},
+        shader::SpirvShader,
+    },
+    types::Backend,
+};
+
+use crate::vertex::quad::UNIT_QUAD;
+use crate::vertex::{QuadCorner, QuadInstance};
+
+lazy_static::lazy_static! {
+    pub(crate) static ref QUAD_VERTEX: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/quad.vert.spv"),
+        ShaderStageFlags::VERTEX,
+        "main",
+    ).unwrap();
+
+    pub(crate) static ref QUAD_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/quad.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
+
+    pub(crate) static ref TEXTURED_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/textured.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
+}
+
+/// The vertex inputs of the pipelines drawing QuadInstances: the corners of
+/// the unit quad in binding 0, and the instances in binding 1
+pub(crate) fn quad_vertex_desc() -> [(VertexFormat, pso::VertexInputRate); 2] {
+    [
+        (QuadCorner::vertex(), pso::VertexInputRate::Vertex),
+        (QuadInstance::vertex(), pso::VertexInputRate::Instance(1)),
+    ]
+}
+
+/// The input assembler of the pipelines drawing QuadInstances
+pub(crate) fn quad_input_assembler() -> pso::InputAssemblerDesc {
+    pso::InputAssemblerDesc::new(hal::Primitive::TriangleStrip)
+}
+
+/// The vertex buffer holding the unit quad, which is stretched over the
+/// rectangle of every QuadInstance.
+///
+/// It is written once, and shared by the quads, images and glyphs.
+#[derive(Debug)]
+pub struct UnitQuad<B: Backend> {
+    buffer: Escape<Buffer<B>>,
+}
+
+impl<B: Backend> UnitQuad<B> {
+    pub fn new(factory: &Factory<B>) -> Result<Self, failure::Error> {
+        let corners: Vec<QuadCorner> = UNIT_QUAD
+            .iter()
+            .map(|&corner| QuadCorner {
+                position: corner.into(),
+            })
+            .collect();
+        let mut buffer = factory.create_buffer(
+            BufferInfo {
+                size: (std::mem::size_of::<QuadCorner>() * corners.len()) as u64,
+                usage: hal::buffer::Usage::VERTEX,
+            },
+            Dynamic,
+        )?;
+        unsafe {
+            factory.upload_visible_buffer(&mut buffer, 0, &corners)?;
+        }
+        Ok(UnitQuad { buffer })
+    }
+
+    /// Binds the unit quad to the binding 0, the instances going in the binding 1
+    pub fn bind(&self, encoder: &mut RenderPassEncoder<'_, B>) {
+        unsafe {
+            encoder.bind_vertex_buffers(0, Some((self.buffer.raw(), 0)));
+        }
+    }
+
+    /// Draws a range of the instances bound to the binding 1
+    pub fn draw(&self, encoder: &mut RenderPassEncoder<'_, B>, instances: std::ops::Range<u32>) {
+        unsafe {
+            encoder.draw(0..UNIT_QUAD.len() as u32, instances);
+        }
+    }
+}
@@ -1,47 +1,39 @@
+use amethyst::assets::Handle;
 use amethyst::ecs::{Read, SystemData, World};
 use amethyst::renderer::{
     pipeline::{PipelineDescBuilder, PipelinesBuilder},
     rendy::{
         command::RenderPassEncoder,
         factory::Factory,
-        hal::pso::{self, ShaderStageFlags},
+        hal::pso,
         hal::{self, device::Device},
-        mesh::AsVertex,
-        shader::{Shader, SpirvShader},
+        shader::Shader,
     },
     submodules::{DynamicUniform, DynamicVertexBuffer, TextureId, TextureSub},
     types::Backend,
     util::simple_shader_set,
+    Texture,
 };
 use glam::{Mat4, Vec3};
 use glsl_layout::{mat4, AsStd140};
 
-use crate::systems::{GlyphAtlas, TextVertexContainer};
-use crate::vertex::TextVertex;
-
-lazy_static::lazy_static! {
-     static ref TEXT_VERTEX: SpirvShader = SpirvShader::from_bytes(
-        include_bytes!("../../shaders/compiled/text.vert.spv"),
-        ShaderStageFlags::VERTEX,
-        "main",
-    ).unwrap();
-
-    static ref TEXT_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
-        include_bytes!("../../shaders/compiled/text.frag.spv"),
-        ShaderStageFlags::FRAGMENT,
-        "main",
-    ).unwrap();
-}
+use crate::pipelines::quad::{
+    quad_input_assembler, quad_vertex_desc, UnitQuad, QUAD_VERTEX, TEXTURED_FRAGMENT,
+};
+use crate::systems::{GlyphAtlas, GlyphInstances};
+use crate::vertex::QuadInstance;
 
+/// Draws the glyphs laid out by the IcedDrawGlyphSystem, one instance of the unit quad per glyph
 #[derive(Debug)]
 pub struct TextPipeline<B: Backend> {
     pipeline: B::GraphicsPipeline,
     pipeline_layout: B::PipelineLayout,
     pub textures: TextureSub<B>,
-    pub vertex: DynamicVertexBuffer<B, TextVertex>,
+    pub instances: DynamicVertexBuffer<B, QuadInstance>,
     pub uniforms: DynamicUniform<B, TextUniform>,
     pub transform: TextUniform,
-    glyph_atlas_id: Option<TextureId>,
+    /// The glyph texture in use, which changes when it is resized
+    glyph_atlas: Option<(Handle<Texture>, TextureId)>,
 }
 
 impl<B: Backend> TextPipeline<B> {
@@ -61,26 +53,24 @@ impl<B: Backend> TextPipeline<B> {
                 .create_pipeline_layout(layouts, None as Option<(_, _)>)
         }?;
 
-        let vertex = DynamicVertexBuffer::<B, TextVertex>::new();
+        let instances = DynamicVertexBuffer::<B, QuadInstance>::new();
 
         let shader_vertex = unsafe {
-            TEXT_VERTEX
+            QUAD_VERTEX
                 .module(factory)
-                .expect("Failed to create triangle_vertex module")
+                .expect("Failed to create quad_vertex module")
         };
         let shader_fragment = unsafe {
-            TEXT_FRAGMENT
+            TEXTURED_FRAGMENT
                 .module(factory)
-                .expect("Failed to create triangle_fragment module")
+                .expect("Failed to create textured_fragment module")
         };
 
         let pipes = PipelinesBuilder::new()
             .with_pipeline(
                 PipelineDescBuilder::new()
-                    .with_vertex_desc(&[(TextVertex::vertex(), pso::VertexInputRate::Vertex)])
-                    .with_input_assembler(pso::InputAssemblerDesc::new(
-                        hal::Primitive::TriangleList,
-                    ))
+                    .with_vertex_desc(&quad_vertex_desc())
+                    .with_input_assembler(quad_input_assembler())
                     .with_shaders(simple_shader_set(&shader_vertex, Some(&shader_fragment)))
                     .with_layout(&pipeline_layout)
                     .with_subpass(subpass)
@@ -125,48 +115,55 @@ impl<B: Backend> TextPipeline<B> {
                     pipeline_layout,
                     textures,
                     uniforms,
-                    vertex,
+                    instances,
                     transform,
-                    glyph_atlas_id: None,
+                    glyph_atlas: None,
                 })
             }
         }
     }
 
     pub fn bind_texture_id(&mut self, factory: &Factory<B>, world: &World) {
-        if self.glyph_atlas_id.is_some() {
-            return;
-        }
         let glyph_atlas = Read::<'_, GlyphAtlas>::fetch(world);
         let tex_handle = (*glyph_atlas).0.as_ref().unwrap();
+        match &self.glyph_atlas {
+            Some((handle, _)) if handle == tex_handle => return,
+            _ => {}
+        }
         let tex_id = self
             .textures
             .insert(factory, world, tex_handle, hal::image::Layout::General)
             .unwrap()
             .0;
-        self.glyph_atlas_id = Some(tex_id);
+        self.glyph_atlas = Some((tex_handle.clone(), tex_id));
     }
 
-    pub fn draw(&self, encoder: &mut RenderPassEncoder<'_, B>, index: usize, world: &World) {
-        if self.glyph_atlas_id.is_none() {
-            return;
-        }
-        let tex_id = self.glyph_atlas_id.unwrap();
+    pub fn draw_layer(
+        &self,
+        encoder: &mut RenderPassEncoder<'_, B>,
+        index: usize,
+        unit_quad: &UnitQuad<B>,
+        world: &World,
+        layer: usize,
+    ) {
+        let tex_id = match &self.glyph_atlas {
+            Some((_, tex_id)) => *tex_id,
+            None => return,
+        };
 
-        let text_vertex_container = Read::<'_, TextVertexContainer>::fetch(world);
-        if text_vertex_container.0.len() == 0 {
-            return;
-        }
+        let glyph_instances = Read::<'_, GlyphInstances>::fetch(world);
+        let instances = match glyph_instances.layers.get(layer) {
+            Some(instances) if !instances.is_empty() => instances.clone(),
+            _ => return,
+        };
 
         encoder.bind_graphics_pipeline(&self.pipeline);
         self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
-        self.vertex.bind(index, 0, 0, encoder);
+        unit_quad.bind(encoder);
+        self.instances.bind(index, 1, 0, encoder);
         self.textures
             .bind(&self.pipeline_layout, 1, tex_id, encoder);
-        unsafe {
-            encoder.draw(0..text_vertex_container.0.len() as u32, 0..1);
-            //encoder.draw(0..6, 0..1);
-        }
+        unit_quad.draw(encoder, instances);
     }
 
     pub fn reset(&mut self, factory: &Factory<B>, index: usize, world: &World) {
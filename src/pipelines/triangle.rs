@@ -15,10 +15,16 @@ use amethyst::renderer::{
 use glam::{Mat4, Vec3};
 use glsl_layout::{mat4, AsStd140};
 
-use crate::vertex::TriangleVertex;
+use crate::backdrop::SampledImage;
+use crate::geometry::Geometry;
+use crate::pipelines::backdrop::BackdropPipeline;
+use crate::pipelines::quad::{
+    quad_input_assembler, quad_vertex_desc, UnitQuad, QUAD_FRAGMENT, QUAD_VERTEX,
+};
+use crate::vertex::{QuadInstance, ShadowVertex, TriangleVertex};
 
 lazy_static::lazy_static! {
-     static ref TRIANGLE_VERTEX: SpirvShader = SpirvShader::from_bytes(
+     pub(crate) static ref TRIANGLE_VERTEX: SpirvShader = SpirvShader::from_bytes(
         include_bytes!("../../shaders/compiled/triangle.vert.spv"),
         ShaderStageFlags::VERTEX,
         "main",
@@ -29,15 +35,37 @@ lazy_static::lazy_static! {
         ShaderStageFlags::FRAGMENT,
         "main",
     ).unwrap();
+
+    static ref SHADOW_VERTEX: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/shadow.vert.spv"),
+        ShaderStageFlags::VERTEX,
+        "main",
+    ).unwrap();
+
+    static ref SHADOW_FRAGMENT: SpirvShader = SpirvShader::from_bytes(
+        include_bytes!("../../shaders/compiled/shadow.frag.spv"),
+        ShaderStageFlags::FRAGMENT,
+        "main",
+    ).unwrap();
 }
 
+/// Draws colored triangles, along with quads and the blurred boxes of shadows.
+///
+/// Shadows, backdrops and quads have pipelines of their own, but are drawn in
+/// order with the triangles, so that they go above whatever was drawn before them.
 #[derive(Debug)]
 pub struct TrianglePipeline<B: Backend> {
     pipeline: B::GraphicsPipeline,
+    shadow_pipeline: B::GraphicsPipeline,
+    quad_pipeline: B::GraphicsPipeline,
     pipeline_layout: B::PipelineLayout,
-    pub vertex: DynamicVertexBuffer<B, TriangleVertex>,
+    vertex: DynamicVertexBuffer<B, TriangleVertex>,
+    shadow_vertex: DynamicVertexBuffer<B, ShadowVertex>,
+    quad_instances: DynamicVertexBuffer<B, QuadInstance>,
+    /// Only created when the backdrop blur is enabled
+    backdrop_pipeline: Option<BackdropPipeline<B>>,
+    backdrop_vertex: DynamicVertexBuffer<B, TriangleVertex>,
     pub uniforms: DynamicUniform<B, TriangleUniform>,
-    pub vertices: Vec<TriangleVertex>,
     pub transform: TriangleUniform,
 }
 
@@ -47,6 +75,7 @@ impl<B: Backend> TrianglePipeline<B> {
         subpass: hal::pass::Subpass<'_, B>,
         fb_width: u32,
         fb_height: u32,
+        blurred: Option<SampledImage<B>>,
     ) -> Result<Self, failure::Error> {
         let uniforms =
             DynamicUniform::<B, TriangleUniform>::new(factory, pso::ShaderStageFlags::VERTEX)?;
@@ -58,6 +87,20 @@ impl<B: Backend> TrianglePipeline<B> {
         }?;
 
         let vertex = DynamicVertexBuffer::<B, TriangleVertex>::new();
+        let shadow_vertex = DynamicVertexBuffer::<B, ShadowVertex>::new();
+        let quad_instances = DynamicVertexBuffer::<B, QuadInstance>::new();
+        let backdrop_vertex = DynamicVertexBuffer::<B, TriangleVertex>::new();
+        let backdrop_pipeline = match blurred {
+            Some(blurred) => Some(BackdropPipeline::create_pipeline(
+                factory,
+                subpass,
+                fb_width,
+                fb_height,
+                uniforms.raw_layout(),
+                blurred,
+            )?),
+            None => None,
+        };
 
         let shader_vertex = unsafe {
             TRIANGLE_VERTEX
@@ -69,6 +112,26 @@ impl<B: Backend> TrianglePipeline<B> {
                 .module(factory)
                 .expect("Failed to create triangle_fragment module")
         };
+        let shadow_shader_vertex = unsafe {
+            SHADOW_VERTEX
+                .module(factory)
+                .expect("Failed to create shadow_vertex module")
+        };
+        let shadow_shader_fragment = unsafe {
+            SHADOW_FRAGMENT
+                .module(factory)
+                .expect("Failed to create shadow_fragment module")
+        };
+        let quad_shader_vertex = unsafe {
+            QUAD_VERTEX
+                .module(factory)
+                .expect("Failed to create quad_vertex module")
+        };
+        let quad_shader_fragment = unsafe {
+            QUAD_FRAGMENT
+                .module(factory)
+                .expect("Failed to create quad_fragment module")
+        };
 
         let pipes = PipelinesBuilder::new()
             .with_pipeline(
@@ -86,11 +149,49 @@ impl<B: Backend> TrianglePipeline<B> {
                         blend: Some(pso::BlendState::ALPHA),
                     }]),
             )
+            .with_pipeline(
+                PipelineDescBuilder::new()
+                    .with_vertex_desc(&[(ShadowVertex::vertex(), pso::VertexInputRate::Vertex)])
+                    .with_input_assembler(pso::InputAssemblerDesc::new(
+                        hal::Primitive::TriangleList,
+                    ))
+                    .with_shaders(simple_shader_set(
+                        &shadow_shader_vertex,
+                        Some(&shadow_shader_fragment),
+                    ))
+                    .with_layout(&pipeline_layout)
+                    .with_subpass(subpass)
+                    .with_framebuffer_size(fb_width, fb_height)
+                    .with_blend_targets(vec![pso::ColorBlendDesc {
+                        mask: pso::ColorMask::ALL,
+                        blend: Some(pso::BlendState::ALPHA),
+                    }]),
+            )
+            .with_pipeline(
+                PipelineDescBuilder::new()
+                    .with_vertex_desc(&quad_vertex_desc())
+                    .with_input_assembler(quad_input_assembler())
+                    .with_shaders(simple_shader_set(
+                        &quad_shader_vertex,
+                        Some(&quad_shader_fragment),
+                    ))
+                    .with_layout(&pipeline_layout)
+                    .with_subpass(subpass)
+                    .with_framebuffer_size(fb_width, fb_height)
+                    .with_blend_targets(vec![pso::ColorBlendDesc {
+                        mask: pso::ColorMask::ALL,
+                        blend: Some(pso::BlendState::ALPHA),
+                    }]),
+            )
             .build(factory, None);
 
         unsafe {
             factory.destroy_shader_module(shader_vertex);
             factory.destroy_shader_module(shader_fragment);
+            factory.destroy_shader_module(shadow_shader_vertex);
+            factory.destroy_shader_module(shadow_shader_fragment);
+            factory.destroy_shader_module(quad_shader_vertex);
+            factory.destroy_shader_module(quad_shader_fragment);
         }
 
         let fb_width = fb_width as f32;
@@ -114,14 +215,21 @@ impl<B: Backend> TrianglePipeline<B> {
                 }
                 Err(e)
             }
-            Ok(mut pipeline) => {
-                let pipeline = pipeline.remove(0);
+            Ok(mut pipelines) => {
+                let pipeline = pipelines.remove(0);
+                let shadow_pipeline = pipelines.remove(0);
+                let quad_pipeline = pipelines.remove(0);
                 Ok(TrianglePipeline {
                     pipeline,
+                    shadow_pipeline,
+                    quad_pipeline,
                     pipeline_layout,
                     uniforms,
                     vertex,
-                    vertices: vec![],
+                    shadow_vertex,
+                    quad_instances,
+                    backdrop_pipeline,
+                    backdrop_vertex,
                     transform,
                 })
             }
@@ -131,22 +239,95 @@ impl<B: Backend> TrianglePipeline<B> {
     pub fn dispose(self, factory: &mut Factory<B>) {
         unsafe {
             factory.device().destroy_graphics_pipeline(self.pipeline);
+            factory.device().destroy_graphics_pipeline(self.shadow_pipeline);
+            factory.device().destroy_graphics_pipeline(self.quad_pipeline);
+            if let Some(backdrop_pipeline) = self.backdrop_pipeline {
+                backdrop_pipeline.dispose(factory);
+            }
             factory
                 .device()
                 .destroy_pipeline_layout(self.pipeline_layout);
         }
     }
 
-    pub fn draw(&self, encoder: &mut RenderPassEncoder<'_, B>, index: usize) {
-        if self.vertices.len() == 0 {
-            return;
-        }
+    /// Whether the backdrop blur is enabled, see `crate::backdrop`
+    pub fn has_backdrop(&self) -> bool {
+        self.backdrop_pipeline.is_some()
+    }
 
-        encoder.bind_graphics_pipeline(&self.pipeline);
-        self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
-        self.vertex.bind(index, 0, 0, encoder);
-        unsafe {
-            encoder.draw(0..self.vertices.len() as u32, 0..1);
+    /// Uploads the triangles, shadows, backdrops and quads of the geometry.
+    ///
+    /// The buffers are only reallocated when they grow, the vertices are copied
+    /// straight into their mapped memory.
+    pub fn write(&mut self, factory: &Factory<B>, index: usize, geometry: &Geometry) {
+        self.vertex.write(
+            factory,
+            index,
+            geometry.vertices.len() as u64,
+            Some(&geometry.vertices),
+        );
+        self.shadow_vertex.write(
+            factory,
+            index,
+            geometry.shadows.len() as u64,
+            Some(&geometry.shadows),
+        );
+        self.backdrop_vertex.write(
+            factory,
+            index,
+            geometry.backdrops.len() as u64,
+            Some(&geometry.backdrops),
+        );
+        self.quad_instances.write(
+            factory,
+            index,
+            geometry.quads.len() as u64,
+            Some(&geometry.quads),
+        );
+    }
+
+    pub fn draw_layer(
+        &self,
+        encoder: &mut RenderPassEncoder<'_, B>,
+        index: usize,
+        unit_quad: &UnitQuad<B>,
+        geometry: &Geometry,
+        layer: usize,
+    ) {
+        for (batch, next) in geometry.batches(layer) {
+            if batch.shadows < next.shadows {
+                encoder.bind_graphics_pipeline(&self.shadow_pipeline);
+                self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
+                self.shadow_vertex.bind(index, 0, 0, encoder);
+                unsafe {
+                    encoder.draw(batch.shadows..next.shadows, 0..1);
+                }
+            }
+            if let (Some(backdrop_pipeline), true) =
+                (&self.backdrop_pipeline, batch.backdrops < next.backdrops)
+            {
+                let layout = backdrop_pipeline.bind(encoder);
+                self.uniforms.bind(index, layout, 0, encoder);
+                self.backdrop_vertex.bind(index, 0, 0, encoder);
+                unsafe {
+                    encoder.draw(batch.backdrops..next.backdrops, 0..1);
+                }
+            }
+            if batch.quads < next.quads {
+                encoder.bind_graphics_pipeline(&self.quad_pipeline);
+                self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
+                unit_quad.bind(encoder);
+                self.quad_instances.bind(index, 1, 0, encoder);
+                unit_quad.draw(encoder, batch.quads..next.quads);
+            }
+            if batch.triangles < next.triangles {
+                encoder.bind_graphics_pipeline(&self.pipeline);
+                self.uniforms.bind(index, &self.pipeline_layout, 0, encoder);
+                self.vertex.bind(index, 0, 0, encoder);
+                unsafe {
+                    encoder.draw(batch.triangles..next.triangles, 0..1);
+                }
+            }
         }
     }
 }
@@ -2,19 +2,150 @@ use amethyst::{
     core::ecs::{DispatcherBuilder, World},
     error::Error,
     renderer::{
-        bundle::{RenderOrder, RenderPlan, RenderPlugin, Target},
-        rendy::{factory::Factory, graph::render::RenderGroupDesc},
+        bundle::{
+            ImageOptions, OutputColor, RenderOrder, RenderPlan, RenderPlugin, Target,
+            TargetImage, TargetPlanOutputs,
+        },
+        rendy::{
+            factory::Factory,
+            graph::render::RenderGroupDesc,
+            hal::{
+                command::{ClearDepthStencil, ClearValue},
+                format::Format,
+                image::Kind,
+            },
+        },
         types::Backend,
     },
+    window::ScreenDimensions,
 };
 
 use std::fmt::Debug;
 
+use crate::backdrop::{BlurPassDesc, CompositePassDesc, BLUR_X_TARGET, BLUR_Y_TARGET, SCENE_TARGET};
 use crate::pass::IcedPassDesc;
 use crate::systems::IcedDrawGlyphSystem;
 
 #[derive(Default, Debug)]
-pub struct IcedUI;
+pub struct IcedUI {
+    backdrop: Option<BackdropBlur>,
+    dimensions: Option<ScreenDimensions>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BackdropBlur {
+    radius: f32,
+    scene_clear: [f32; 4],
+}
+
+impl IcedUI {
+    /// Enables the backdrop blur of containers, see `ContainerStyle::backdrop_blur`.
+    ///
+    /// The scene must then be drawn in `SCENE_TARGET` rather than in the window:
+    /// it is blurred by `blur_radius` pixels, and copied in the window below the UI.
+    ///
+    /// ```ignore
+    /// RenderingBundle::<DefaultBackend>::new()
+    ///     .with_plugin(RenderToWindow::from_config_path(display_config)?)
+    ///     .with_plugin(RenderFlat2D::default().with_target(SCENE_TARGET))
+    ///     .with_plugin(IcedUI::default().with_backdrop_blur(12.))
+    /// ```
+    pub fn with_backdrop_blur(mut self, blur_radius: f32) -> Self {
+        self.backdrop = Some(BackdropBlur {
+            radius: blur_radius,
+            scene_clear: [0., 0., 0., 1.],
+        });
+        self
+    }
+
+    /// Sets the color SCENE_TARGET is cleared with, when the backdrop blur is enabled
+    pub fn with_scene_clear(mut self, color: [f32; 4]) -> Self {
+        if let Some(backdrop) = &mut self.backdrop {
+            backdrop.scene_clear = color;
+        }
+        self
+    }
+
+    /// Renders the scene offscreen, blurs it, and copies it in the window below the UI
+    fn plan_backdrop<B: Backend>(
+        &self,
+        backdrop: BackdropBlur,
+        plan: &mut RenderPlan<B>,
+    ) -> Result<(), Error> {
+        let (width, height) = self
+            .dimensions
+            .as_ref()
+            .map(|dimensions| (dimensions.width() as u32, dimensions.height() as u32))
+            .unwrap_or((1, 1));
+        let kind = Kind::D2(width.max(1), height.max(1), 1, 1);
+        // The blur hides the details anyway, halving the resolution makes it much cheaper
+        let blur_kind = Kind::D2((width / 2).max(1), (height / 2).max(1), 1, 1);
+        let blur_outputs = || TargetPlanOutputs {
+            colors: vec![OutputColor::Image(ImageOptions {
+                kind: blur_kind,
+                levels: 1,
+                format: Format::Rgba8Srgb,
+                clear: None,
+            })],
+            depth: None,
+        };
+
+        plan.define_pass(
+            SCENE_TARGET,
+            TargetPlanOutputs {
+                colors: vec![OutputColor::Image(ImageOptions {
+                    kind,
+                    levels: 1,
+                    format: Format::Rgba8Srgb,
+                    clear: Some(ClearValue::Color(backdrop.scene_clear.into())),
+                })],
+                depth: Some(ImageOptions {
+                    kind,
+                    levels: 1,
+                    format: Format::D32Sfloat,
+                    clear: Some(ClearValue::DepthStencil(ClearDepthStencil(1.0, 0))),
+                }),
+            },
+        )?;
+        plan.define_pass(BLUR_X_TARGET, blur_outputs())?;
+        plan.define_pass(BLUR_Y_TARGET, blur_outputs())?;
+
+        let passes = [
+            (BLUR_X_TARGET, SCENE_TARGET, [1., 0.]),
+            (BLUR_Y_TARGET, BLUR_X_TARGET, [0., 1.]),
+        ];
+        for &(target, source, direction) in passes.iter() {
+            plan.extend_target(target, move |ctx| {
+                let source = color_image(ctx.get_image(TargetImage::Color(source, 0))?)?;
+                let blur = BlurPassDesc {
+                    direction,
+                    sigma: backdrop.radius / 2.,
+                };
+                ctx.add(RenderOrder::Opaque, blur.builder().with_image(source))?;
+                Ok(())
+            });
+        }
+
+        plan.extend_target(Target::Main, |ctx| {
+            let scene = color_image(ctx.get_image(TargetImage::Color(SCENE_TARGET, 0))?)?;
+            let blurred = color_image(ctx.get_image(TargetImage::Color(BLUR_Y_TARGET, 0))?)?;
+            ctx.add(
+                RenderOrder::Background,
+                CompositePassDesc::default().builder().with_image(scene),
+            )?;
+            ctx.add(
+                RenderOrder::Transparent,
+                IcedPassDesc { backdrop: true }.builder().with_image(blurred),
+            )?;
+            Ok(())
+        });
+        Ok(())
+    }
+}
+
+fn color_image<T>(image: Option<T>) -> Result<T, Error> {
+    image.ok_or_else(|| Error::from_string("A target of the backdrop blur has no color image"))
+}
 
 impl<B: Backend> RenderPlugin<B> for IcedUI {
     fn on_build<'a, 'b>(
@@ -26,12 +157,32 @@ impl<B: Backend> RenderPlugin<B> for IcedUI {
         Ok(())
     }
 
+    /// The offscreen targets of the backdrop blur follow the size of the window
+    fn should_rebuild(&mut self, world: &World) -> bool {
+        if self.backdrop.is_none() {
+            return false;
+        }
+        let dimensions = world.try_fetch::<ScreenDimensions>();
+        if self.dimensions.as_ref() != dimensions.as_deref() {
+            self.dimensions = dimensions.map(|dimensions| (*dimensions).clone());
+            return true;
+        }
+        false
+    }
+
     fn on_plan(
         &mut self,
         plan: &mut RenderPlan<B>,
         _factory: &mut Factory<B>,
-        _world: &World,
+        world: &World,
     ) -> Result<(), Error> {
+        if let Some(backdrop) = self.backdrop {
+            self.dimensions = world
+                .try_fetch::<ScreenDimensions>()
+                .map(|dimensions| (*dimensions).clone());
+            return self.plan_backdrop(backdrop, plan);
+        }
+
         plan.extend_target(Target::Main, |ctx| {
             // Add our Description
             ctx.add(RenderOrder::Transparent, IcedPassDesc::default().builder())?;
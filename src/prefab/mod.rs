@@ -0,0 +1,251 @@
+//! UIs described in RON files, so that menus can be edited without recompiling.
+//!
+//! ```ron
+//! (
+//!     root: Column(
+//!         style: Some("panel"),
+//!         align: Center,
+//!         spacing: 10,
+//!         children: [
+//!             Text(content: "Main menu", size: Some(30)),
+//!             Button(id: Some("start"), label: "Start", on_press: Some("start"), style: Some("primary")),
+//!             Slider(id: "volume", range: (0., 100.), value: 50., on_change: Some("volume")),
+//!             Checkbox(id: "fullscreen", label: "Fullscreen", on_toggle: Some("fullscreen")),
+//!         ],
+//!     ),
+//! )
+//! ```
+//!
+//! Load it with `RonFormat` and hand the handle to a `PrefabSandbox`.
+//! Adding `IcedPrefabSystem` keeps the sandbox in sync with the asset, which
+//! enables hot reloading when Amethyst's `HotReloadBundle` is used.
+
+mod sandbox;
+
+use amethyst::assets::{Asset, Handle, ProcessingState};
+use amethyst::ecs::VecStorage;
+use amethyst::Error;
+use iced_native::{Align, Length};
+use serde::{Deserialize, Serialize};
+
+pub use sandbox::{PrefabEvent, PrefabMessage, PrefabSandbox, PrefabStyles};
+
+/// The root of a UI prefab file
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UiPrefab {
+    pub root: UiNode,
+}
+
+impl Asset for UiPrefab {
+    const NAME: &'static str = "amethyst_iced::UiPrefab";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+impl From<UiPrefab> for Result<ProcessingState<UiPrefab>, Error> {
+    fn from(prefab: UiPrefab) -> Result<ProcessingState<UiPrefab>, Error> {
+        Ok(ProcessingState::Loaded(prefab))
+    }
+}
+
+/// A widget of a UI prefab.
+///
+/// Styles and images are referenced by name, see `PrefabStyles`. Interactive
+/// widgets send a `PrefabMessage` carrying the name of their binding.
+/// Widgets with an id are `Tagged`, so they can be found in the `IcedLayoutSnapshot`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum UiNode {
+    Column {
+        #[serde(default)]
+        children: Vec<UiNode>,
+        #[serde(default)]
+        spacing: u16,
+        #[serde(default)]
+        padding: u16,
+        #[serde(default)]
+        align: PrefabAlign,
+        #[serde(default)]
+        width: PrefabLength,
+        #[serde(default)]
+        height: PrefabLength,
+        /// Name of a ContainerStyle wrapping the column
+        #[serde(default)]
+        style: Option<String>,
+    },
+    Row {
+        #[serde(default)]
+        children: Vec<UiNode>,
+        #[serde(default)]
+        spacing: u16,
+        #[serde(default)]
+        padding: u16,
+        #[serde(default)]
+        align: PrefabAlign,
+        #[serde(default)]
+        width: PrefabLength,
+        #[serde(default)]
+        height: PrefabLength,
+        /// Name of a ContainerStyle wrapping the row
+        #[serde(default)]
+        style: Option<String>,
+    },
+    Text {
+        content: String,
+        #[serde(default)]
+        size: Option<u16>,
+        #[serde(default)]
+        color: Option<[f32; 4]>,
+        #[serde(default)]
+        width: PrefabLength,
+    },
+    Button {
+        #[serde(default)]
+        id: Option<String>,
+        label: String,
+        /// The binding sent when pressed. The button is disabled without one.
+        #[serde(default)]
+        on_press: Option<String>,
+        /// Name of a ButtonStyle
+        #[serde(default)]
+        style: Option<String>,
+        #[serde(default)]
+        width: PrefabLength,
+    },
+    Image {
+        /// Name of an image registered in the PrefabStyles
+        image: String,
+        #[serde(default)]
+        width: PrefabLength,
+        #[serde(default)]
+        height: PrefabLength,
+    },
+    Slider {
+        /// Identifies the value of the slider, which is kept by the PrefabSandbox
+        id: String,
+        range: (f32, f32),
+        /// The initial value
+        value: f32,
+        #[serde(default)]
+        on_change: Option<String>,
+        #[serde(default)]
+        width: PrefabLength,
+    },
+    Checkbox {
+        /// Identifies the value of the checkbox, which is kept by the PrefabSandbox
+        id: String,
+        label: String,
+        /// The initial value
+        #[serde(default)]
+        checked: bool,
+        #[serde(default)]
+        on_toggle: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum PrefabLength {
+    Fill,
+    Shrink,
+    Units(u16),
+}
+
+impl Default for PrefabLength {
+    fn default() -> Self {
+        PrefabLength::Shrink
+    }
+}
+
+impl From<PrefabLength> for Length {
+    fn from(length: PrefabLength) -> Length {
+        match length {
+            PrefabLength::Fill => Length::Fill,
+            PrefabLength::Shrink => Length::Shrink,
+            PrefabLength::Units(units) => Length::Units(units),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum PrefabAlign {
+    Start,
+    Center,
+    End,
+}
+
+impl Default for PrefabAlign {
+    fn default() -> Self {
+        PrefabAlign::Start
+    }
+}
+
+impl From<PrefabAlign> for Align {
+    fn from(align: PrefabAlign) -> Align {
+        match align {
+            PrefabAlign::Start => Align::Start,
+            PrefabAlign::Center => Align::Center,
+            PrefabAlign::End => Align::End,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The RON example of the module documentation
+    fn doc_example() -> String {
+        include_str!("mod.rs")
+            .lines()
+            .skip_while(|line| *line != "//! ```ron")
+            .skip(1)
+            .take_while(|line| *line != "//! ```")
+            .map(|line| line.trim_start_matches("//!"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn round_trip(ron: &str) -> UiPrefab {
+        let prefab: UiPrefab = ron::de::from_str(ron).expect("Invalid UI prefab");
+        let serialized = ron::ser::to_string(&prefab).unwrap();
+        assert_eq!(ron::de::from_str::<UiPrefab>(&serialized).unwrap(), prefab);
+        prefab
+    }
+
+    fn children(prefab: &UiPrefab) -> &[UiNode] {
+        match &prefab.root {
+            UiNode::Column { children, .. } | UiNode::Row { children, .. } => children,
+            root => panic!("The root isn't a column or a row: {:?}", root),
+        }
+    }
+
+    #[test]
+    fn the_main_menu_round_trips() {
+        let prefab = round_trip(include_str!("../../assets/ui/main_menu.ron"));
+        assert_eq!(children(&prefab).len(), 6);
+        assert_eq!(
+            children(&prefab)[3],
+            UiNode::Slider {
+                id: "volume".into(),
+                range: (0., 100.),
+                value: 50.,
+                on_change: Some("volume".into()),
+                width: PrefabLength::Units(200),
+            }
+        );
+    }
+
+    #[test]
+    fn the_documented_example_round_trips() {
+        let prefab = round_trip(&doc_example());
+        assert_eq!(children(&prefab).len(), 4);
+        assert_eq!(
+            children(&prefab)[3],
+            UiNode::Checkbox {
+                id: "fullscreen".into(),
+                label: "Fullscreen".into(),
+                checked: false,
+                on_toggle: Some("fullscreen".into()),
+            }
+        );
+    }
+}
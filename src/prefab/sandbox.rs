@@ -0,0 +1,472 @@
+use amethyst::assets::Handle;
+
+use std::collections::HashMap;
+
+use crate::custom_widget::{Image, ImageHandle, Tagged};
+use crate::prefab::{PrefabLength, UiNode, UiPrefab};
+use crate::sandbox::{Element, Sandbox};
+use crate::widget::{Button, ButtonStyle, Checkbox, Column, Container, ContainerStyle, Row, Slider, Space};
+use crate::{ButtonState, Length, SliderState, Text};
+
+/// The styles & images which UI prefabs can refer to by name.
+///
+/// Comes with the "default", "primary" and "danger" button styles, as well as
+/// the "panel" and "card" container styles.
+pub struct PrefabStyles {
+    buttons: HashMap<String, ButtonStyle>,
+    containers: HashMap<String, ContainerStyle>,
+    images: HashMap<String, ImageHandle>,
+}
+
+impl Default for PrefabStyles {
+    fn default() -> Self {
+        PrefabStyles {
+            buttons: HashMap::new(),
+            containers: HashMap::new(),
+            images: HashMap::new(),
+        }
+        .with_button_style("default", ButtonStyle::default())
+        .with_button_style("primary", ButtonStyle::primary())
+        .with_button_style("danger", ButtonStyle::danger())
+        .with_container_style("panel", ContainerStyle::panel())
+        .with_container_style("card", ContainerStyle::card())
+    }
+}
+
+impl PrefabStyles {
+    pub fn with_button_style<N: Into<String>>(mut self, name: N, style: ButtonStyle) -> Self {
+        self.buttons.insert(name.into(), style);
+        self
+    }
+
+    pub fn with_container_style<N: Into<String>>(mut self, name: N, style: ContainerStyle) -> Self {
+        self.containers.insert(name.into(), style);
+        self
+    }
+
+    pub fn with_image<N: Into<String>, I: Into<ImageHandle>>(mut self, name: N, image: I) -> Self {
+        self.images.insert(name.into(), image.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrefabEvent {
+    Pressed,
+    Changed(f32),
+    Toggled(bool),
+}
+
+/// Sent by the widgets of a UI prefab
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefabMessage {
+    /// The binding name given in the prefab, if any
+    pub binding: Option<String>,
+    /// The id of the widget which sent the message, if any
+    pub id: Option<String>,
+    pub event: PrefabEvent,
+}
+
+/// The widget states of a prefab, mirroring its tree of nodes
+#[derive(Default)]
+struct WidgetState {
+    button: ButtonState,
+    slider: SliderState,
+    children: Vec<WidgetState>,
+}
+
+impl WidgetState {
+    fn new(node: &UiNode) -> Self {
+        let children = match node {
+            UiNode::Column { children, .. } | UiNode::Row { children, .. } => {
+                children.iter().map(WidgetState::new).collect()
+            }
+            _ => vec![],
+        };
+        WidgetState {
+            children,
+            ..WidgetState::default()
+        }
+    }
+}
+
+type Bindings<G> = Box<dyn Fn(&PrefabMessage) -> Vec<G> + Send + Sync>;
+
+/// A Sandbox displaying a UI prefab.
+///
+/// Slider & checkbox values are kept by the sandbox, keyed by the widget id,
+/// so that they survive a reload of the prefab. Messages with a binding are
+/// turned into GameMessages by the function given to `with_bindings`.
+pub struct PrefabSandbox<G> {
+    handle: Handle<UiPrefab>,
+    prefab: Option<UiPrefab>,
+    states: WidgetState,
+    values: HashMap<String, f32>,
+    checks: HashMap<String, bool>,
+    styles: PrefabStyles,
+    bindings: Bindings<G>,
+}
+
+impl<G> PrefabSandbox<G> {
+    /// Creates a sandbox for the given prefab, which is displayed once
+    /// loaded by the IcedPrefabSystem
+    pub fn new(handle: Handle<UiPrefab>, styles: PrefabStyles) -> Self {
+        PrefabSandbox {
+            handle,
+            prefab: None,
+            states: WidgetState::default(),
+            values: HashMap::new(),
+            checks: HashMap::new(),
+            styles,
+            bindings: Box::new(|_| vec![]),
+        }
+    }
+
+    /// Sets the function turning the messages of the prefab into GameMessages
+    pub fn with_bindings<F>(mut self, bindings: F) -> Self
+    where
+        F: Fn(&PrefabMessage) -> Vec<G> + Send + Sync + 'static,
+    {
+        self.bindings = Box::new(bindings);
+        self
+    }
+
+    pub fn handle(&self) -> &Handle<UiPrefab> {
+        &self.handle
+    }
+
+    /// The current value of a slider, once moved
+    pub fn slider_value(&self, id: &str) -> Option<f32> {
+        self.values.get(id).copied()
+    }
+
+    /// Whether a checkbox is checked, once toggled
+    pub fn is_checked(&self, id: &str) -> Option<bool> {
+        self.checks.get(id).copied()
+    }
+
+    /// Replaces the displayed prefab if it changed, resetting the widget states
+    pub(crate) fn reload(&mut self, prefab: &UiPrefab) {
+        if self.prefab.as_ref() != Some(prefab) {
+            self.states = WidgetState::new(&prefab.root);
+            self.prefab = Some(prefab.clone());
+        }
+    }
+}
+
+impl<G: Send + Sync + 'static> Sandbox for PrefabSandbox<G> {
+    type UIMessage = PrefabMessage;
+    type GameMessage = G;
+
+    fn update(&mut self, message: &PrefabMessage) -> Vec<G> {
+        if let Some(id) = &message.id {
+            match message.event {
+                PrefabEvent::Changed(value) => {
+                    self.values.insert(id.clone(), value);
+                }
+                PrefabEvent::Toggled(checked) => {
+                    self.checks.insert(id.clone(), checked);
+                }
+                PrefabEvent::Pressed => {}
+            }
+        }
+        (self.bindings)(message)
+    }
+
+    fn view(&mut self) -> Element<PrefabMessage> {
+        match &self.prefab {
+            Some(prefab) => build(
+                &prefab.root,
+                &mut self.states,
+                &Context {
+                    styles: &self.styles,
+                    values: &self.values,
+                    checks: &self.checks,
+                },
+            ),
+            None => Space::new(Length::Shrink, Length::Shrink).into(),
+        }
+    }
+}
+
+struct Context<'a> {
+    styles: &'a PrefabStyles,
+    values: &'a HashMap<String, f32>,
+    checks: &'a HashMap<String, bool>,
+}
+
+fn build<'a>(
+    node: &'a UiNode,
+    state: &'a mut WidgetState,
+    context: &Context<'a>,
+) -> Element<'a, 'a, PrefabMessage> {
+    match node {
+        UiNode::Column {
+            children,
+            spacing,
+            padding,
+            align,
+            width,
+            height,
+            style,
+        } => {
+            let column = children.iter().zip(state.children.iter_mut()).fold(
+                Column::new()
+                    .spacing(*spacing)
+                    .padding(*padding)
+                    .align_items((*align).into())
+                    .width((*width).into())
+                    .height((*height).into()),
+                |column, (child, state)| column.push(build(child, state, context)),
+            );
+            styled(column.into(), style, *width, *height, context)
+        }
+        UiNode::Row {
+            children,
+            spacing,
+            padding,
+            align,
+            width,
+            height,
+            style,
+        } => {
+            let row = children.iter().zip(state.children.iter_mut()).fold(
+                Row::new()
+                    .spacing(*spacing)
+                    .padding(*padding)
+                    .align_items((*align).into())
+                    .width((*width).into())
+                    .height((*height).into()),
+                |row, (child, state)| row.push(build(child, state, context)),
+            );
+            styled(row.into(), style, *width, *height, context)
+        }
+        UiNode::Text {
+            content,
+            size,
+            color,
+            width,
+        } => {
+            let mut text = Text::new(content.as_str()).width((*width).into());
+            if let Some(size) = size {
+                text = text.size(*size);
+            }
+            if let Some(color) = color {
+                text = text.color(*color);
+            }
+            text.into()
+        }
+        UiNode::Button {
+            id,
+            label,
+            on_press,
+            style,
+            width,
+        } => {
+            let mut button =
+                Button::new(&mut state.button, Text::new(label.as_str())).width((*width).into());
+            if let Some(binding) = on_press {
+                button = button.on_press(PrefabMessage {
+                    binding: Some(binding.clone()),
+                    id: id.clone(),
+                    event: PrefabEvent::Pressed,
+                });
+            }
+            if let Some(style) = style {
+                match context.styles.buttons.get(style) {
+                    Some(style) => button = button.style(style.clone()),
+                    None => log::warn!("Unknown button style {:?} in UI prefab.", style),
+                }
+            }
+            tagged(id.as_ref(), button.into())
+        }
+        UiNode::Image {
+            image,
+            width,
+            height,
+        } => match context.styles.images.get(image) {
+            Some(handle) => Image::new(handle.clone())
+                .width((*width).into())
+                .height((*height).into())
+                .into(),
+            None => {
+                log::warn!("Unknown image {:?} in UI prefab.", image);
+                Space::new((*width).into(), (*height).into()).into()
+            }
+        },
+        UiNode::Slider {
+            id,
+            range,
+            value,
+            on_change,
+            width,
+        } => {
+            let value = context.values.get(id).copied().unwrap_or(*value);
+            let (binding, message_id) = (on_change.clone(), id.clone());
+            let slider = Slider::new(&mut state.slider, range.0..=range.1, value, move |value| {
+                PrefabMessage {
+                    binding: binding.clone(),
+                    id: Some(message_id.clone()),
+                    event: PrefabEvent::Changed(value),
+                }
+            })
+            .width((*width).into());
+            tagged(Some(id), slider.into())
+        }
+        UiNode::Checkbox {
+            id,
+            label,
+            checked,
+            on_toggle,
+        } => {
+            let checked = context.checks.get(id).copied().unwrap_or(*checked);
+            let (binding, message_id) = (on_toggle.clone(), id.clone());
+            let checkbox = Checkbox::new(checked, label.as_str(), move |checked| PrefabMessage {
+                binding: binding.clone(),
+                id: Some(message_id.clone()),
+                event: PrefabEvent::Toggled(checked),
+            });
+            tagged(Some(id), checkbox.into())
+        }
+    }
+}
+
+/// Wraps a column or a row in a Container using the named style
+fn styled<'a>(
+    content: Element<'a, 'a, PrefabMessage>,
+    style: &Option<String>,
+    width: PrefabLength,
+    height: PrefabLength,
+    context: &Context<'a>,
+) -> Element<'a, 'a, PrefabMessage> {
+    match style.as_ref().map(|name| (name, context.styles.containers.get(name))) {
+        None => content,
+        Some((_, Some(style))) => Container::new(content)
+            .width(width.into())
+            .height(height.into())
+            .style(style.clone())
+            .into(),
+        Some((name, None)) => {
+            log::warn!("Unknown container style {:?} in UI prefab.", name);
+            content
+        }
+    }
+}
+
+fn tagged<'a>(
+    id: Option<&String>,
+    element: Element<'a, 'a, PrefabMessage>,
+) -> Element<'a, 'a, PrefabMessage> {
+    match id {
+        Some(id) => Tagged::new(id.as_str(), element).into(),
+        None => element,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use amethyst::assets::{AssetStorage, Loader};
+    use amethyst::core::rayon::ThreadPoolBuilder;
+
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Game {
+        Start,
+        Quit,
+        Volume(f32),
+        Fullscreen(bool),
+    }
+
+    fn main_menu() -> UiPrefab {
+        ron::de::from_str(include_str!("../../assets/ui/main_menu.ron")).unwrap()
+    }
+
+    fn menu_sandbox() -> PrefabSandbox<Game> {
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(1).build().unwrap());
+        let loader = Loader::new(".", pool);
+        let storage = AssetStorage::<UiPrefab>::new();
+        let handle = loader.load_from_data(main_menu(), (), &storage);
+
+        let mut sandbox =
+            PrefabSandbox::new(handle, PrefabStyles::default()).with_bindings(|message| {
+                match (message.binding.as_ref().map(String::as_str), &message.event) {
+                    (Some("start"), PrefabEvent::Pressed) => vec![Game::Start],
+                    (Some("quit"), PrefabEvent::Pressed) => vec![Game::Quit],
+                    (Some("volume"), PrefabEvent::Changed(volume)) => vec![Game::Volume(*volume)],
+                    (Some("fullscreen"), PrefabEvent::Toggled(on)) => vec![Game::Fullscreen(*on)],
+                    _ => vec![],
+                }
+            });
+        sandbox.reload(&main_menu());
+        sandbox
+    }
+
+    fn message(binding: Option<&str>, id: &str, event: PrefabEvent) -> PrefabMessage {
+        PrefabMessage {
+            binding: binding.map(Into::into),
+            id: Some(id.into()),
+            event,
+        }
+    }
+
+    #[test]
+    fn bindings_turn_the_messages_into_game_messages() {
+        let mut sandbox = menu_sandbox();
+        let volume = message(Some("volume"), "volume", PrefabEvent::Changed(20.));
+        assert_eq!(sandbox.update(&volume), vec![Game::Volume(20.)]);
+        let fullscreen = message(Some("fullscreen"), "fullscreen", PrefabEvent::Toggled(true));
+        assert_eq!(sandbox.update(&fullscreen), vec![Game::Fullscreen(true)]);
+
+        // Without a binding, the value is kept but nothing is sent to the game
+        let unbound = message(None, "volume", PrefabEvent::Changed(30.));
+        assert_eq!(sandbox.update(&unbound), vec![]);
+        assert_eq!(sandbox.slider_value("volume"), Some(30.));
+    }
+
+    #[test]
+    fn reloading_keeps_the_slider_and_checkbox_values() {
+        let mut harness = IcedTestHarness::new(menu_sandbox());
+        harness.click_id("fullscreen");
+        harness
+            .sandbox()
+            .update(&message(None, "volume", PrefabEvent::Changed(20.)));
+
+        let mut edited = main_menu();
+        if let UiNode::Column { children, .. } = &mut edited.root {
+            children.insert(
+                1,
+                UiNode::Text {
+                    content: "Edited".into(),
+                    size: None,
+                    color: None,
+                    width: PrefabLength::Shrink,
+                },
+            );
+        }
+        harness.sandbox().reload(&edited);
+        assert_eq!(harness.sandbox().slider_value("volume"), Some(20.));
+        assert_eq!(harness.sandbox().is_checked("fullscreen"), Some(true));
+
+        // The reloaded checkbox is drawn checked, so clicking it unchecks it
+        harness.take_game_messages();
+        harness.click_id("fullscreen");
+        assert_eq!(harness.take_game_messages(), vec![Game::Fullscreen(false)]);
+    }
+
+    #[test]
+    fn clicking_a_tagged_button_sends_its_binding() {
+        let mut harness = IcedTestHarness::new(menu_sandbox());
+        harness.click_id("start");
+        assert_eq!(
+            harness.take_ui_messages(),
+            vec![message(Some("start"), "start", PrefabEvent::Pressed)]
+        );
+        assert_eq!(harness.take_game_messages(), vec![Game::Start]);
+
+        harness.click_id("quit");
+        assert_eq!(harness.take_game_messages(), vec![Game::Quit]);
+    }
+}
@@ -1,18 +1,47 @@
 use crate::custom_widget::ImageHandle;
-use crate::pass::IcedPass;
-use crate::vertex::{ImageVertex, TriangleVertex};
-use amethyst::ecs::{SystemData, World, WriteExpect};
-use amethyst::renderer::{rendy::factory::Factory, rendy::hal, types::Backend};
-use glsl_layout::vec4;
+use crate::geometry::{Geometry, GeometryCache};
+use crate::style::{Background, BorderStyle, ColorStop, Gradient, NinePatch, Shadow};
+use crate::tessellation;
+use crate::vertex::{QuadInstance, ShadowVertex, TriangleVertex};
+use glam::Mat3;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use glyph_brush::{rusttype::Scale, HorizontalAlign, Layout, Section, VerticalAlign, FontId};
-use iced_native::{Color, HorizontalAlignment, Rectangle};
-
-use crate::IcedGlyphBrush;
+use iced_native::{Color, HorizontalAlignment, Point, Rectangle, Vector};
 
 #[allow(dead_code)]
 pub enum AmethystIcedPrimitive {
     Quad(Rectangle, Option<Color>),
+    RoundedQuad {
+        bounds: Rectangle,
+        background: Option<Color>,
+        border_radius: f32,
+        border_width: f32,
+        border_color: Color,
+    },
+    /// A rectangle filled with a gradient, with optionally rounded corners
+    Gradient {
+        bounds: Rectangle,
+        gradient: Gradient,
+        border_radius: f32,
+    },
+    /// The blurred shadow of a box with rounded corners, drawn by the shadow shader.
+    ///
+    /// Only the shadow itself is drawn, the box casting it is usually drawn right after.
+    Shadow {
+        bounds: Rectangle,
+        border_radius: f32,
+        shadow: Shadow,
+    },
+    /// Shows the blurred scene behind a box with rounded corners, see `IcedUI::with_backdrop_blur`.
+    ///
+    /// Nothing is drawn when the backdrop blur isn't enabled.
+    Backdrop {
+        bounds: Rectangle,
+        border_radius: f32,
+    },
     Image(Rectangle, ImageHandle),
+    NinePatch(Rectangle, NinePatch),
     Text {
         bounds: Rectangle,
         content: String,
@@ -22,131 +51,756 @@ pub enum AmethystIcedPrimitive {
         font_id: FontId,
     },
     Group(Vec<AmethystIcedPrimitive>),
+    /// A list of triangles tessellated beforehand, e.g. by a Canvas.
+    ///
+    /// The vertices are shared, so that cached geometry is drawn without being copied.
+    Mesh(Arc<Vec<TriangleVertex>>),
+    /// Multiplies the opacity of every primitive of the subtree, images included
+    Opacity(f32, Box<AmethystIcedPrimitive>),
+    /// Transforms the subtree, in window coordinates.
+    ///
+    /// This is purely visual: widgets keep receiving events at their place in
+    /// the layout, so a scaled or moved button still reacts where it would be
+    /// drawn without the transform, as shown by the tests of the Transition widget.
+    ///
+    /// Texts only follow the translation and scale of the transform, rotations
+    /// and skews are ignored when laying out glyphs.
+    Transform(Mat3, Box<AmethystIcedPrimitive>),
+    /// A subtree whose triangles are kept between frames, while the key stays the same.
+    ///
+    /// The images, texts and overlays of the subtree are still drawn from it on
+    /// every frame, only the tessellation is skipped. See the Cached widget.
+    Cached {
+        key: u64,
+        primitive: Box<AmethystIcedPrimitive>,
+    },
+    /// Draws the subtree above everything else, e.g. for tooltips.
+    ///
+    /// Overlays are drawn in the order they are found, each one in a layer of
+    /// its own above the rest of the UI. Overlays nested in an overlay go
+    /// one layer higher.
+    Overlay(Box<AmethystIcedPrimitive>),
     None,
 }
 
 /// Wrapper struct meant to avoid an user from interfering (accidentally or not)
 /// into amethyst_iced's primitives
-pub(crate) struct IcedPrimitives(pub(crate) Option<AmethystIcedPrimitive>, pub u64);
+pub(crate) struct IcedPrimitives(pub(crate) Option<AmethystIcedPrimitive>);
 
 impl Default for IcedPrimitives {
     fn default() -> Self {
-        IcedPrimitives(None, 0)
+        IcedPrimitives(None)
     }
 }
 
+/// Which primitives of a tree are rendered, see the Cached primitive
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Parts {
+    /// Everything drawn by the triangle pipeline, i.e. what Cached primitives keep
+    triangles: bool,
+    /// The images, texts and overlays
+    others: bool,
+}
+
+impl Parts {
+    const ALL: Parts = Parts {
+        triangles: true,
+        others: true,
+    };
+    const TRIANGLES: Parts = Parts {
+        triangles: true,
+        others: false,
+    };
+    const OTHERS: Parts = Parts {
+        triangles: false,
+        others: true,
+    };
+}
+
 impl AmethystIcedPrimitive {
-    /// Consumes the Primitive, rendering it on the pass
-    pub fn render<B: Backend>(
-        self,
-        pass: &mut IcedPass<B>,
-        factory: &Factory<B>,
-        index: usize,
-        world: &World,
+    /// Renders the primitive in the geometry, layer by layer.
+    ///
+    /// Texts are given to `queue_text` rather than laid out, and the Cached
+    /// subtrees reuse the geometry kept by the cache.
+    pub(crate) fn render(
+        &self,
+        geometry: &mut Geometry,
+        cache: &mut GeometryCache,
+        queue_text: &mut dyn FnMut(Section<'_>),
     ) {
+        let mut layer = vec![(RenderState::default(), self)];
+        while !layer.is_empty() {
+            let depth = geometry.begin_layer();
+            let mut overlays = vec![];
+            for (state, primitive) in layer {
+                let state = RenderState {
+                    layer: depth,
+                    ..state
+                };
+                let mut context = RenderContext {
+                    geometry: &mut *geometry,
+                    cache: &mut *cache,
+                    queue_text: &mut *queue_text,
+                    overlays: &mut overlays,
+                };
+                primitive.render_with(&mut context, state, Parts::ALL);
+            }
+            layer = overlays;
+        }
+        cache.end_frame();
+    }
+
+    /// Renders the primitive in the current layer, keeping its overlays for the next one
+    fn render_with<'p>(&'p self, context: &mut RenderContext<'_, 'p>, state: RenderState, parts: Parts) {
+        let geometry = &mut *context.geometry;
+        let first_vertex = geometry.vertices.len();
         match self {
-            AmethystIcedPrimitive::Group(primitives) => primitives.into_iter().for_each(|p| {
-                p.render(pass, factory, index, world);
+            AmethystIcedPrimitive::Group(primitives) => primitives.iter().for_each(|p| {
+                p.render_with(context, state, parts);
             }),
+            AmethystIcedPrimitive::Opacity(alpha, primitive) => {
+                primitive.render_with(context, state.with_opacity(*alpha), parts);
+            }
+            AmethystIcedPrimitive::Transform(transform, primitive) => {
+                primitive.render_with(context, state.with_transform(*transform), parts);
+            }
+            AmethystIcedPrimitive::Cached { key, primitive } => {
+                if parts.triangles {
+                    let recorded = match context.cache.take(*key) {
+                        Some(recorded) => recorded,
+                        None => {
+                            // Recorded without the state, which is applied when replaying
+                            let mut recorded = Geometry {
+                                backdrop: context.geometry.backdrop,
+                                ..Geometry::default()
+                            };
+                            recorded.begin_layer();
+                            let mut overlays = vec![];
+                            let mut recording = RenderContext {
+                                geometry: &mut recorded,
+                                cache: &mut *context.cache,
+                                queue_text: &mut |_: Section<'_>| {},
+                                overlays: &mut overlays,
+                            };
+                            primitive.render_with(
+                                &mut recording,
+                                RenderState {
+                                    layer: state.layer,
+                                    ..RenderState::default()
+                                },
+                                Parts::TRIANGLES,
+                            );
+                            recorded
+                        }
+                    };
+                    context.geometry.replay(&recorded, &state);
+                    context.cache.insert(*key, recorded);
+                }
+                if parts.others {
+                    primitive.render_with(context, state, Parts::OTHERS);
+                }
+            }
+            AmethystIcedPrimitive::Overlay(primitive) => {
+                if parts.others {
+                    context.overlays.push((state, primitive));
+                }
+            }
+            _ if !parts.triangles && self.is_triangles() => {}
+            _ if !parts.others && !self.is_triangles() => {}
             AmethystIcedPrimitive::Quad(bounds, color) => {
-                let iced_color = color.unwrap_or(Color::WHITE);
-                let color: vec4 = [iced_color.r, iced_color.g, iced_color.b, iced_color.a].into();
-
-                pass.triangle_pipeline.vertices.extend_from_slice(&[
-                    TriangleVertex {
-                        position: [bounds.x, bounds.y].into(),
-                        color,
-                    },
-                    TriangleVertex {
-                        position: [bounds.x + bounds.width, bounds.y].into(),
-                        color,
-                    },
-                    TriangleVertex {
-                        position: [bounds.x + bounds.width, bounds.y + bounds.height].into(),
-                        color,
-                    },
-                    TriangleVertex {
-                        position: [bounds.x, bounds.y].into(),
-                        color,
-                    },
-                    TriangleVertex {
-                        position: [bounds.x, bounds.y + bounds.height].into(),
-                        color,
-                    },
-                    TriangleVertex {
-                        position: [bounds.x + bounds.width, bounds.y + bounds.height].into(),
-                        color,
-                    },
-                ]);
-            }
-            AmethystIcedPrimitive::Image(bounds, handle) => match handle {
-                ImageHandle::Texture { handle, .. } => {
-                    let info = pass.image_pipeline.textures.insert(
-                        factory,
-                        world,
-                        &handle,
-                        hal::image::Layout::ShaderReadOnlyOptimal,
-                    );
-                    if let Some((id, _changed)) = info {
-                        let verts = vec![
-                            ImageVertex {
-                                position: [bounds.x, bounds.y].into(),
-                                uv: [0., 0.].into(),
-                            },
-                            ImageVertex {
-                                position: [bounds.x + bounds.width, bounds.y].into(),
-                                uv: [1., 0.].into(),
-                            },
-                            ImageVertex {
-                                position: [bounds.x + bounds.width, bounds.y + bounds.height]
-                                    .into(),
-                                uv: [1., 1.].into(),
-                            },
-                            ImageVertex {
-                                position: [bounds.x, bounds.y].into(),
-                                uv: [0., 0.].into(),
-                            },
-                            ImageVertex {
-                                position: [bounds.x, bounds.y + bounds.height].into(),
-                                uv: [0., 1.].into(),
-                            },
-                            ImageVertex {
-                                position: [bounds.x + bounds.width, bounds.y + bounds.height]
-                                    .into(),
-                                uv: [1., 1.].into(),
-                            },
-                        ];
-                        pass.image_pipeline.batches.insert(id, verts);
-                    }
+                let quad = QuadInstance::solid(*bounds, color.unwrap_or(Color::WHITE));
+                geometry.push_quads(&[state.apply_quad(quad)]);
+            }
+            AmethystIcedPrimitive::Mesh(vertices) => {
+                geometry.vertices.extend_from_slice(vertices);
+                state.apply_triangles(&mut geometry.vertices[first_vertex..]);
+            }
+            AmethystIcedPrimitive::RoundedQuad {
+                bounds,
+                background,
+                border_radius,
+                border_width,
+                border_color,
+            } => {
+                let quad = QuadInstance::rounded(
+                    *bounds,
+                    *background,
+                    *border_radius,
+                    *border_width,
+                    *border_color,
+                );
+                geometry.push_quads(&[state.apply_quad(quad)]);
+            }
+            AmethystIcedPrimitive::Gradient {
+                bounds,
+                gradient,
+                border_radius,
+            } => {
+                tessellation::gradient_quad(*bounds, gradient, *border_radius, &mut geometry.vertices);
+                state.apply_triangles(&mut geometry.vertices[first_vertex..]);
+            }
+            AmethystIcedPrimitive::Shadow {
+                bounds,
+                border_radius,
+                shadow,
+            } => {
+                geometry.push_shadows(&state.shadow_vertices(*bounds, *border_radius, shadow));
+            }
+            AmethystIcedPrimitive::Backdrop {
+                bounds,
+                border_radius,
+            } => {
+                if geometry.backdrop {
+                    geometry.push_backdrop(&state.backdrop_vertices(*bounds, *border_radius));
                 }
-            },
+            }
+            AmethystIcedPrimitive::Image(bounds, ImageHandle::Texture { handle, .. }) => {
+                let uv = Rectangle {
+                    x: 0.,
+                    y: 0.,
+                    width: 1.,
+                    height: 1.,
+                };
+                geometry.push_image(handle, state.apply_quad(QuadInstance::image(*bounds, uv)));
+            }
+            AmethystIcedPrimitive::NinePatch(bounds, patch) => {
+                let ImageHandle::Texture { handle, .. } = &patch.handle;
+                patch.regions(*bounds).into_iter().for_each(|(region, uv)| {
+                    geometry.push_image(handle, state.apply_quad(QuadInstance::image(region, uv)));
+                });
+            }
             AmethystIcedPrimitive::Text {
                 content,
                 color,
                 size,
                 bounds,
                 horizontal_alignment,
-                font_id, 
+                font_id,
             } => {
-                let mut iced_glyph_brush = WriteExpect::<'_, IcedGlyphBrush>::fetch(world);
-                iced_glyph_brush.queue(Section {
-                    font_id,
-                    text: &content,
-                    color,
-                    scale: Scale::uniform(size as f32),
-                    bounds: (bounds.width, bounds.height),
-                    screen_position: (bounds.x, bounds.y),
-                    layout: Layout::default()
-                        .h_align(into_h_align(horizontal_alignment))
-                        // Todo: support proper Vertical alignment
-                        .v_align(VerticalAlign::Top),
-                    //.v_align(into_v_align(vertical_alignment)),
-                    ..Default::default()
-                });
+                (context.queue_text)(state.apply_section(text_section(
+                    content,
+                    *bounds,
+                    *size,
+                    *color,
+                    *horizontal_alignment,
+                    *font_id,
+                )));
             }
             AmethystIcedPrimitive::None => {}
         }
     }
+
+    /// Whether the primitive is a leaf drawn by the triangle pipeline
+    fn is_triangles(&self) -> bool {
+        match self {
+            AmethystIcedPrimitive::Quad(..)
+            | AmethystIcedPrimitive::Mesh(..)
+            | AmethystIcedPrimitive::RoundedQuad { .. }
+            | AmethystIcedPrimitive::Gradient { .. }
+            | AmethystIcedPrimitive::Shadow { .. }
+            | AmethystIcedPrimitive::Backdrop { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Wraps the primitive in a Cached primitive keyed by its triangles, so
+    /// that it is only tessellated again once it looks different
+    pub(crate) fn cached(self) -> Self {
+        let mut hasher = iced_native::Hasher::default();
+        self.hash_triangles(&mut hasher);
+        AmethystIcedPrimitive::Cached {
+            key: hasher.finish(),
+            primitive: Box::new(self),
+        }
+    }
+
+    /// Hashes everything a Cached primitive keeps of the subtree.
+    ///
+    /// Images, texts and overlays are left out since they are drawn on every
+    /// frame anyway, and nested Cached primitives are hashed by their key.
+    fn hash_triangles<H: Hasher>(&self, state: &mut H) {
+        fn hash_floats<H: Hasher>(values: &[f32], state: &mut H) {
+            values.iter().for_each(|value| value.to_bits().hash(state));
+        }
+        fn hash_rectangle<H: Hasher>(bounds: &Rectangle, state: &mut H) {
+            hash_floats(&[bounds.x, bounds.y, bounds.width, bounds.height], state);
+        }
+        fn hash_color<H: Hasher>(color: &Color, state: &mut H) {
+            hash_floats(&[color.r, color.g, color.b, color.a], state);
+        }
+        fn hash_stops<H: Hasher>(stops: &[ColorStop], state: &mut H) {
+            stops.len().hash(state);
+            stops.iter().for_each(|stop| {
+                hash_floats(&[stop.offset], state);
+                hash_floats(&stop.color, state);
+            });
+        }
+
+        std::mem::discriminant(self).hash(state);
+        match self {
+            AmethystIcedPrimitive::Quad(bounds, color) => {
+                hash_rectangle(bounds, state);
+                color.iter().for_each(|color| hash_color(color, state));
+            }
+            AmethystIcedPrimitive::RoundedQuad {
+                bounds,
+                background,
+                border_radius,
+                border_width,
+                border_color,
+            } => {
+                hash_rectangle(bounds, state);
+                background.iter().for_each(|color| hash_color(color, state));
+                hash_floats(&[*border_radius, *border_width], state);
+                hash_color(border_color, state);
+            }
+            AmethystIcedPrimitive::Gradient {
+                bounds,
+                gradient,
+                border_radius,
+            } => {
+                hash_rectangle(bounds, state);
+                hash_floats(&[*border_radius], state);
+                match gradient {
+                    Gradient::Linear { start, end, stops } => {
+                        hash_floats(start, state);
+                        hash_floats(end, state);
+                        hash_stops(stops, state);
+                    }
+                    Gradient::Radial {
+                        center,
+                        radius,
+                        stops,
+                    } => {
+                        hash_floats(center, state);
+                        hash_floats(&[*radius], state);
+                        hash_stops(stops, state);
+                    }
+                }
+            }
+            AmethystIcedPrimitive::Shadow {
+                bounds,
+                border_radius,
+                shadow,
+            } => {
+                hash_rectangle(bounds, state);
+                hash_floats(&[*border_radius, shadow.blur_radius, shadow.spread], state);
+                hash_floats(&shadow.offset, state);
+                hash_floats(&shadow.color, state);
+            }
+            AmethystIcedPrimitive::Backdrop {
+                bounds,
+                border_radius,
+            } => {
+                hash_rectangle(bounds, state);
+                hash_floats(&[*border_radius], state);
+            }
+            AmethystIcedPrimitive::Mesh(vertices) => {
+                vertices.len().hash(state);
+                vertices.iter().for_each(|vertex| {
+                    hash_floats(AsRef::<[f32; 2]>::as_ref(&vertex.position), state);
+                    hash_floats(AsRef::<[f32; 4]>::as_ref(&vertex.color), state);
+                });
+            }
+            AmethystIcedPrimitive::Group(primitives) => {
+                primitives.len().hash(state);
+                primitives.iter().for_each(|p| p.hash_triangles(state));
+            }
+            AmethystIcedPrimitive::Opacity(alpha, primitive) => {
+                hash_floats(&[*alpha], state);
+                primitive.hash_triangles(state);
+            }
+            AmethystIcedPrimitive::Transform(transform, primitive) => {
+                hash_floats(&transform.to_cols_array(), state);
+                primitive.hash_triangles(state);
+            }
+            AmethystIcedPrimitive::Cached { key, .. } => key.hash(state),
+            AmethystIcedPrimitive::Image(..)
+            | AmethystIcedPrimitive::NinePatch(..)
+            | AmethystIcedPrimitive::Text { .. }
+            | AmethystIcedPrimitive::Overlay(_)
+            | AmethystIcedPrimitive::None => {}
+        }
+    }
+}
+
+/// Where the primitives of a layer are rendered
+struct RenderContext<'a, 'p> {
+    geometry: &'a mut Geometry,
+    cache: &'a mut GeometryCache,
+    queue_text: &'a mut dyn FnMut(Section<'_>),
+    /// The overlays found in the layer, rendered in the next one
+    overlays: &'a mut Vec<(RenderState, &'p AmethystIcedPrimitive)>,
+}
+
+impl AmethystIcedPrimitive {
+    /// Creates a background with an optional border and rounded corners.
+    ///
+    /// Corners are only rounded for plain colors and gradients, images are always drawn as rectangles.
+    pub(crate) fn panel(
+        bounds: Rectangle,
+        background: Option<&Background>,
+        border: &BorderStyle,
+        border_radius: u32,
+    ) -> AmethystIcedPrimitive {
+        let gradient = background.and_then(|background| background.gradient(bounds));
+        if let (Some(gradient), true) = (gradient, border_radius > 0) {
+            return AmethystIcedPrimitive::Group(vec![
+                AmethystIcedPrimitive::Gradient {
+                    bounds,
+                    gradient,
+                    border_radius: border_radius as f32,
+                },
+                AmethystIcedPrimitive::RoundedQuad {
+                    bounds,
+                    background: None,
+                    border_radius: border_radius as f32,
+                    border_width: border.width as f32,
+                    border_color: border.color.into(),
+                },
+            ]);
+        }
+
+        match background {
+            Some(Background::Color(color)) if border_radius > 0 => {
+                AmethystIcedPrimitive::RoundedQuad {
+                    bounds,
+                    background: Some((*color).into()),
+                    border_radius: border_radius as f32,
+                    border_width: border.width as f32,
+                    border_color: border.color.into(),
+                }
+            }
+            None if border_radius > 0 && border.width > 0 => AmethystIcedPrimitive::RoundedQuad {
+                bounds,
+                background: None,
+                border_radius: border_radius as f32,
+                border_width: border.width as f32,
+                border_color: border.color.into(),
+            },
+            _ => AmethystIcedPrimitive::Group(vec![
+                background
+                    .map(|background| background.primitive(bounds))
+                    .unwrap_or(AmethystIcedPrimitive::None),
+                AmethystIcedPrimitive::border(bounds, border),
+            ]),
+        }
+    }
+
+    /// Creates the shadow of a panel, if any
+    pub(crate) fn shadow(
+        bounds: Rectangle,
+        border_radius: u32,
+        shadow: Option<Shadow>,
+    ) -> AmethystIcedPrimitive {
+        match shadow {
+            Some(shadow) => AmethystIcedPrimitive::Shadow {
+                bounds,
+                border_radius: border_radius as f32,
+                shadow,
+            },
+            None => AmethystIcedPrimitive::None,
+        }
+    }
+
+    /// Creates the backdrop of a panel, if it has one
+    pub(crate) fn backdrop(bounds: Rectangle, border_radius: u32, blur: bool) -> AmethystIcedPrimitive {
+        if blur {
+            AmethystIcedPrimitive::Backdrop {
+                bounds,
+                border_radius: border_radius as f32,
+            }
+        } else {
+            AmethystIcedPrimitive::None
+        }
+    }
+
+    /// Moves the whole primitive tree by the given offset
+    pub(crate) fn translate(self, offset: Vector) -> AmethystIcedPrimitive {
+        AmethystIcedPrimitive::Transform(
+            Mat3::from_cols_array(&[1., 0., 0., 0., 1., 0., offset.x, offset.y, 1.]),
+            Box::new(self),
+        )
+    }
+
+    /// Scales the whole primitive tree around the given point
+    pub(crate) fn scale(self, center: Point, scale: f32) -> AmethystIcedPrimitive {
+        AmethystIcedPrimitive::Transform(
+            Mat3::from_cols_array(&[
+                scale,
+                0.,
+                0.,
+                0.,
+                scale,
+                0.,
+                center.x * (1. - scale),
+                center.y * (1. - scale),
+                1.,
+            ]),
+            Box::new(self),
+        )
+    }
+
+    /// Multiplies the opacity of the whole primitive tree
+    pub(crate) fn fade(self, alpha: f32) -> AmethystIcedPrimitive {
+        AmethystIcedPrimitive::Opacity(alpha, Box::new(self))
+    }
+
+    /// Creates the four quads outlining the inside of the given bounds
+    pub(crate) fn border(bounds: Rectangle, border: &BorderStyle) -> AmethystIcedPrimitive {
+        if border.width == 0 {
+            return AmethystIcedPrimitive::None;
+        }
+        let width = (border.width as f32).min(bounds.width / 2.).min(bounds.height / 2.);
+        let color: Color = border.color.into();
+        AmethystIcedPrimitive::Group(vec![
+            AmethystIcedPrimitive::Quad(Rectangle { height: width, ..bounds }, Some(color)),
+            AmethystIcedPrimitive::Quad(
+                Rectangle {
+                    y: bounds.y + bounds.height - width,
+                    height: width,
+                    ..bounds
+                },
+                Some(color),
+            ),
+            AmethystIcedPrimitive::Quad(
+                Rectangle {
+                    y: bounds.y + width,
+                    width,
+                    height: bounds.height - 2. * width,
+                    ..bounds
+                },
+                Some(color),
+            ),
+            AmethystIcedPrimitive::Quad(
+                Rectangle {
+                    x: bounds.x + bounds.width - width,
+                    y: bounds.y + width,
+                    width,
+                    height: bounds.height - 2. * width,
+                },
+                Some(color),
+            ),
+        ])
+    }
+}
+
+/// The opacity & transform applied to a primitive by its Opacity and Transform parents,
+/// along with the layer it is drawn in
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RenderState {
+    pub opacity: f32,
+    pub transform: Mat3,
+    pub layer: u32,
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        RenderState {
+            opacity: 1.,
+            transform: Mat3::identity(),
+            layer: 0,
+        }
+    }
+}
+
+impl RenderState {
+    pub(crate) fn with_opacity(self, opacity: f32) -> Self {
+        RenderState {
+            opacity: self.opacity * opacity,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_transform(self, transform: Mat3) -> Self {
+        RenderState {
+            transform: self.transform * transform,
+            ..self
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.opacity == 1. && self.transform == Mat3::identity()
+    }
+
+    pub(crate) fn transform_point(&self, x: f32, y: f32) -> [f32; 2] {
+        let m = self.transform.to_cols_array();
+        [m[0] * x + m[3] * y + m[6], m[1] * x + m[4] * y + m[7]]
+    }
+
+    /// The scale of the transform along the x & y axes
+    fn scale(&self) -> (f32, f32) {
+        let m = self.transform.to_cols_array();
+        (m[0].hypot(m[1]), m[3].hypot(m[4]))
+    }
+
+    /// Applies the state to triangles which were tessellated without it
+    pub(crate) fn apply_triangles(&self, vertices: &mut [TriangleVertex]) {
+        if self.is_identity() {
+            return;
+        }
+        for vertex in vertices {
+            let [x, y] = *AsRef::<[f32; 2]>::as_ref(&vertex.position);
+            let [r, g, b, a] = *AsRef::<[f32; 4]>::as_ref(&vertex.color);
+            vertex.position = self.transform_point(x, y).into();
+            vertex.color = [r, g, b, a * self.opacity].into();
+        }
+    }
+
+    /// Applies the state to shadows which were computed without it
+    pub(crate) fn apply_shadows(&self, vertices: &mut [ShadowVertex]) {
+        if self.is_identity() {
+            return;
+        }
+        for vertex in vertices {
+            let [x, y] = *AsRef::<[f32; 2]>::as_ref(&vertex.position);
+            let [r, g, b, a] = *AsRef::<[f32; 4]>::as_ref(&vertex.color);
+            vertex.position = self.transform_point(x, y).into();
+            vertex.color = [r, g, b, a * self.opacity].into();
+        }
+    }
+
+    /// Applies the state to a quad built without it
+    pub(crate) fn apply_quad(&self, mut quad: QuadInstance) -> QuadInstance {
+        self.apply_quads(std::slice::from_mut(&mut quad));
+        quad
+    }
+
+    /// Applies the state to quads built without it, composing their transforms
+    pub(crate) fn apply_quads(&self, quads: &mut [QuadInstance]) {
+        if self.is_identity() {
+            return;
+        }
+        let m = self.transform.to_cols_array();
+        let linear = |x: f32, y: f32| [m[0] * x + m[3] * y, m[1] * x + m[4] * y];
+        for quad in quads {
+            let [xx, xy, yx, yy] = *AsRef::<[f32; 4]>::as_ref(&quad.axes);
+            let [tx, ty] = *AsRef::<[f32; 2]>::as_ref(&quad.translation);
+            let [x_axis, y_axis] = [linear(xx, xy), linear(yx, yy)];
+            quad.axes = [x_axis[0], x_axis[1], y_axis[0], y_axis[1]].into();
+            quad.translation = self.transform_point(tx, ty).into();
+            let [r, g, b, a] = *AsRef::<[f32; 4]>::as_ref(&quad.color);
+            quad.color = [r, g, b, a * self.opacity].into();
+            let [r, g, b, a] = *AsRef::<[f32; 4]>::as_ref(&quad.border_color);
+            quad.border_color = [r, g, b, a * self.opacity].into();
+        }
+    }
+
+    /// The two triangles covering the blurred shadow of a box.
+    ///
+    /// The shadow is computed from the position relative to the box before the
+    /// transform, so rotated boxes keep a matching shadow.
+    pub(crate) fn shadow_vertices(
+        &self,
+        bounds: Rectangle,
+        border_radius: f32,
+        shadow: &Shadow,
+    ) -> Vec<ShadowVertex> {
+        let sigma = (shadow.blur_radius / 2.).max(0.5);
+        let half_size = [
+            (bounds.width / 2. + shadow.spread).max(0.),
+            (bounds.height / 2. + shadow.spread).max(0.),
+        ];
+        let corner = (border_radius + shadow.spread)
+            .max(0.)
+            .min(half_size[0])
+            .min(half_size[1]);
+        let center = [
+            bounds.x + bounds.width / 2. + shadow.offset[0],
+            bounds.y + bounds.height / 2. + shadow.offset[1],
+        ];
+        let [r, g, b, a] = shadow.color;
+        // The gaussian is negligible beyond three standard deviations
+        let reach = [half_size[0] + 3. * sigma, half_size[1] + 3. * sigma];
+        let vertex = |x: f32, y: f32| ShadowVertex {
+            position: self.transform_point(center[0] + x, center[1] + y).into(),
+            local: [x, y].into(),
+            half_size: half_size.into(),
+            color: [r, g, b, a * self.opacity].into(),
+            params: [sigma, corner].into(),
+        };
+        let (left, top, right, bottom) = (-reach[0], -reach[1], reach[0], reach[1]);
+        vec![
+            vertex(left, top),
+            vertex(right, top),
+            vertex(right, bottom),
+            vertex(left, top),
+            vertex(left, bottom),
+            vertex(right, bottom),
+        ]
+    }
+
+    /// The triangles covering a box with rounded corners, where the blurred scene is shown.
+    ///
+    /// Only the alpha of the vertices is used, for the antialiased edges and the opacity.
+    pub(crate) fn backdrop_vertices(&self, bounds: Rectangle, border_radius: f32) -> Vec<TriangleVertex> {
+        let mut vertices = vec![];
+        if border_radius > 0. {
+            tessellation::rounded_quad(
+                bounds,
+                Some(Color::WHITE),
+                border_radius,
+                0.,
+                Color::TRANSPARENT,
+                &mut vertices,
+            );
+        } else {
+            tessellation::quad(bounds, Color::WHITE, &mut vertices);
+        }
+        self.apply_triangles(&mut vertices);
+        vertices
+    }
+
+    /// Moves & scales a text section, ignoring any rotation of the transform.
+    ///
+    /// The layer is stored in the z of the section, so that glyphs can be sorted by layer.
+    pub(crate) fn apply_section<'a>(&self, section: Section<'a>) -> Section<'a> {
+        let section = Section {
+            z: self.layer as f32,
+            ..section
+        };
+        if self.is_identity() {
+            return section;
+        }
+        let (scale_x, scale_y) = self.scale();
+        let [x, y] = self.transform_point(section.screen_position.0, section.screen_position.1);
+        let [r, g, b, a] = section.color;
+        Section {
+            screen_position: (x, y),
+            bounds: (section.bounds.0 * scale_x, section.bounds.1 * scale_y),
+            scale: Scale {
+                x: section.scale.x * scale_x,
+                y: section.scale.y * scale_y,
+            },
+            color: [r, g, b, a * self.opacity],
+            ..section
+        }
+    }
+}
+
+/// Describes how a Text primitive is laid out by glyph_brush
+pub(crate) fn text_section(
+    content: &str,
+    bounds: Rectangle,
+    size: u16,
+    color: [f32; 4],
+    horizontal_alignment: HorizontalAlignment,
+    font_id: FontId,
+) -> Section<'_> {
+    Section {
+        font_id,
+        text: content,
+        color,
+        scale: Scale::uniform(size as f32),
+        bounds: (bounds.width, bounds.height),
+        screen_position: (bounds.x, bounds.y),
+        layout: Layout::default()
+            .h_align(into_h_align(horizontal_alignment))
+            // Todo: support proper Vertical alignment
+            .v_align(VerticalAlign::Top),
+        //.v_align(into_v_align(vertical_alignment)),
+        ..Default::default()
+    }
 }
 
 pub fn into_h_align(align: HorizontalAlignment) -> HorizontalAlign {
@@ -0,0 +1,116 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use iced_native::Rectangle;
+
+use crate::animation::{Animatable, Tween};
+
+struct AnimatedValue {
+    from: [f32; 4],
+    to: [f32; 4],
+    start: Duration,
+    tween: Tween,
+    used: bool,
+}
+
+impl AnimatedValue {
+    fn value(&self, now: Duration) -> [f32; 4] {
+        let progress = self
+            .tween
+            .progress(now.checked_sub(self.start).unwrap_or_default());
+        let mut value = [0.; 4];
+        for i in 0..4 {
+            value[i] = self.from[i] + (self.to[i] - self.from[i]) * progress;
+        }
+        value
+    }
+}
+
+/// The values animated by the widgets, keyed by an identifier of the widget.
+///
+/// Values which aren't animated during a frame are forgotten, so a widget
+/// which disappears starts over when it comes back.
+#[derive(Default)]
+pub struct IcedAnimations {
+    now: Duration,
+    values: HashMap<u64, AnimatedValue>,
+    timers: HashMap<u64, (Duration, bool)>,
+}
+
+impl IcedAnimations {
+    /// Builds an animation key out of anything hashable
+    pub fn key<H: Hash>(value: H) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Identifies a widget by its kind and position on screen.
+    ///
+    /// Its animations start over if the widget moves or is resized.
+    pub fn widget_key(kind: &str, bounds: Rectangle) -> u64 {
+        Self::key((
+            kind,
+            bounds.x.to_bits(),
+            bounds.y.to_bits(),
+            bounds.width.to_bits(),
+            bounds.height.to_bits(),
+        ))
+    }
+
+    /// Returns the current value of an animation heading to the target.
+    ///
+    /// The animation starts at `initial` the first time the key is seen, and restarts
+    /// from its current value whenever the target changes.
+    pub fn animate<T: Animatable>(&mut self, key: u64, initial: T, target: T, tween: Tween) -> T {
+        let now = self.now;
+        let target = target.to_components();
+        let value = self.values.entry(key).or_insert_with(|| AnimatedValue {
+            from: initial.to_components(),
+            to: target,
+            start: now,
+            tween,
+            used: true,
+        });
+        value.used = true;
+        if value.to != target {
+            value.from = value.value(now);
+            value.to = target;
+            value.start = now;
+            value.tween = tween;
+        }
+        T::from_components(value.value(now))
+    }
+
+    /// Returns for how long the condition has been true, e.g. how long a widget
+    /// has been hovered, or `None` if it is false.
+    pub fn active_for(&mut self, key: u64, active: bool) -> Option<Duration> {
+        if !active {
+            self.timers.remove(&key);
+            return None;
+        }
+        let now = self.now;
+        let timer = self.timers.entry(key).or_insert((now, true));
+        timer.1 = true;
+        Some(now.checked_sub(timer.0).unwrap_or_default())
+    }
+
+    /// The time used by the animations, which only goes forward between frames
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    pub(crate) fn set_time(&mut self, now: Duration) {
+        self.now = now;
+    }
+
+    /// Forgets the values & timers which weren't used since the last call
+    pub(crate) fn end_frame(&mut self) {
+        self.values.retain(|_, value| value.used);
+        self.values.values_mut().for_each(|value| value.used = false);
+        self.timers.retain(|_, (_, used)| *used);
+        self.timers.values_mut().for_each(|(_, used)| *used = false);
+    }
+}
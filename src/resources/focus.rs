@@ -0,0 +1,250 @@
+use iced_native::{Point, Rectangle};
+
+use std::cmp::Ordering;
+
+use crate::style::BorderStyle;
+
+/// How the focus moves between widgets when navigating with directions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusOrder {
+    /// Widgets are visited from left to right, then top to bottom,
+    /// as when reading a page. Up and left go backward, down and right go forward.
+    ReadingOrder,
+    /// Moves to the closest widget in the pressed direction
+    Spatial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Sent through an `EventChannel<FocusEvent>` when the focus changes or
+/// when a focus action is used, so that the game can react to it
+/// (e.g. closing a menu on cancel).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocusEvent {
+    Focused(Rectangle),
+    Activated(Rectangle),
+    Cancelled,
+}
+
+/// Keeps track of the focused widget, allowing a menu to be used without a mouse.
+///
+/// Buttons, checkboxes and radios register themselves as focusable when drawn.
+/// Activating the focused widget simulates a click at its center.
+pub struct FocusManager {
+    pub order: FocusOrder,
+    /// Style of the ring drawn around the focused widget
+    pub ring: BorderStyle,
+    /// Space between the focused widget and its ring
+    pub ring_offset: f32,
+    focusables: Vec<Rectangle>,
+    /// The overlay layer of every focusable widget
+    layers: Vec<usize>,
+    focused: Option<Rectangle>,
+    pending_clicks: Vec<Point>,
+}
+
+impl Default for FocusManager {
+    fn default() -> Self {
+        FocusManager {
+            order: FocusOrder::ReadingOrder,
+            ring: BorderStyle {
+                width: 2,
+                color: crate::style::colors::PRIMARY,
+            },
+            ring_offset: 2.,
+            focusables: vec![],
+            layers: vec![],
+            focused: None,
+            pending_clicks: vec![],
+        }
+    }
+}
+
+impl FocusManager {
+    pub fn focused(&self) -> Option<Rectangle> {
+        self.focused
+    }
+
+    /// Removes the focus, hiding the focus ring
+    pub fn clear(&mut self) {
+        self.focused = None;
+    }
+
+    /// Moves the focus in the given direction.
+    ///
+    /// When nothing is focused yet, the first widget gets the focus.
+    pub fn navigate(&mut self, direction: FocusDirection) -> Option<Rectangle> {
+        let next = match self.focused {
+            None => reading_order(&self.focusables).first().cloned(),
+            Some(focused) => match self.order {
+                FocusOrder::ReadingOrder => next_in_reading_order(&self.focusables, focused, direction),
+                FocusOrder::Spatial => next_spatially(&self.focusables, focused, direction),
+            },
+        };
+        if next.is_some() {
+            self.focused = next;
+        }
+        next
+    }
+
+    /// Schedules a click at the center of the focused widget
+    pub fn activate(&mut self) -> Option<Rectangle> {
+        let focused = self.focused?;
+        self.pending_clicks.push(Point::new(
+            focused.x + focused.width / 2.,
+            focused.y + focused.height / 2.,
+        ));
+        Some(focused)
+    }
+
+    pub(crate) fn take_pending_clicks(&mut self) -> Vec<Point> {
+        std::mem::replace(&mut self.pending_clicks, vec![])
+    }
+
+    /// Replaces the focusable widgets with those registered during the last draw.
+    ///
+    /// The focus moves to the closest widget, so that it survives layout changes.
+    pub(crate) fn set_focusables(&mut self, focusables: Vec<(Rectangle, usize)>) {
+        let (focusables, layers): (Vec<_>, Vec<_>) = focusables.into_iter().unzip();
+        self.focused = self.focused.and_then(|focused| {
+            let (x, y) = center(focused);
+            focusables.iter().cloned().min_by(|a, b| {
+                let (ax, ay) = center(*a);
+                let (bx, by) = center(*b);
+                let da = (ax - x).powi(2) + (ay - y).powi(2);
+                let db = (bx - x).powi(2) + (by - y).powi(2);
+                da.partial_cmp(&db).unwrap_or(Ordering::Equal)
+            })
+        });
+        self.focusables = focusables;
+        self.layers = layers;
+    }
+
+    /// The overlay layer of the focused widget, in which its ring is drawn
+    pub(crate) fn ring_layer(&self) -> usize {
+        self.focused
+            .and_then(|focused| self.focusables.iter().position(|f| *f == focused))
+            .map_or(0, |index| self.layers[index])
+    }
+
+    /// Bounds of the focus ring, if a widget is focused
+    pub(crate) fn ring_bounds(&self) -> Option<Rectangle> {
+        let offset = self.ring_offset + self.ring.width as f32;
+        self.focused.map(|focused| Rectangle {
+            x: focused.x - offset,
+            y: focused.y - offset,
+            width: focused.width + 2. * offset,
+            height: focused.height + 2. * offset,
+        })
+    }
+}
+
+fn center(bounds: Rectangle) -> (f32, f32) {
+    (bounds.x + bounds.width / 2., bounds.y + bounds.height / 2.)
+}
+
+/// Sorts the widgets by rows, widgets which overlap vertically being on the same row.
+///
+/// Rows are built first, from top to bottom, so that the order is total and
+/// doesn't depend on the order the widgets were registered in.
+fn reading_order(focusables: &[Rectangle]) -> Vec<Rectangle> {
+    let mut sorted = focusables.to_vec();
+    sorted.sort_by(|a, b| compare(a.y, b.y).then(compare(a.x, b.x)));
+
+    let mut rows: Vec<Vec<Rectangle>> = vec![];
+    let mut row_bottom = f32::NEG_INFINITY;
+    for bounds in sorted {
+        match rows.last_mut() {
+            Some(row) if bounds.y < row_bottom => row.push(bounds),
+            _ => rows.push(vec![bounds]),
+        }
+        row_bottom = row_bottom.max(bounds.y + bounds.height);
+    }
+
+    rows.into_iter()
+        .flat_map(|mut row| {
+            row.sort_by(|a, b| compare(a.x, b.x).then(compare(a.y, b.y)));
+            row
+        })
+        .collect()
+}
+
+fn compare(a: f32, b: f32) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+fn next_in_reading_order(
+    focusables: &[Rectangle],
+    focused: Rectangle,
+    direction: FocusDirection,
+) -> Option<Rectangle> {
+    let sorted = reading_order(focusables);
+    let index = sorted.iter().position(|bounds| *bounds == focused)?;
+    let next = match direction {
+        FocusDirection::Down | FocusDirection::Right => (index + 1) % sorted.len(),
+        FocusDirection::Up | FocusDirection::Left => (index + sorted.len() - 1) % sorted.len(),
+    };
+    sorted.get(next).cloned()
+}
+
+fn next_spatially(
+    focusables: &[Rectangle],
+    focused: Rectangle,
+    direction: FocusDirection,
+) -> Option<Rectangle> {
+    let (x, y) = center(focused);
+    focusables
+        .iter()
+        .filter(|bounds| **bounds != focused)
+        .filter_map(|bounds| {
+            let (bx, by) = center(*bounds);
+            let (dx, dy) = (bx - x, by - y);
+            // Distance along the direction, and away from it
+            let (along, across) = match direction {
+                FocusDirection::Up => (-dy, dx.abs()),
+                FocusDirection::Down => (dy, dx.abs()),
+                FocusDirection::Left => (-dx, dy.abs()),
+                FocusDirection::Right => (dx, dy.abs()),
+            };
+            if along <= 0. {
+                return None;
+            }
+            // Widgets straight ahead are preferred over closer diagonal ones
+            Some((along + 2. * across, *bounds))
+        })
+        .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+        .map(|(_, bounds)| bounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn reading_order_groups_rows_whatever_the_input_order() {
+        // b and c overlap a vertically but not each other
+        let a = rect(0., 0., 10., 30.);
+        let b = rect(20., 20., 10., 10.);
+        let c = rect(40., 0., 10., 10.);
+        let d = rect(0., 40., 10., 10.);
+        let expected = vec![a, b, c, d];
+        for input in &[[a, b, c, d], [d, c, b, a], [c, a, d, b], [b, d, a, c]] {
+            assert_eq!(reading_order(input), expected);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use iced_native::{Point, Rectangle};
+
+use std::collections::HashMap;
+
+/// The screen bounds of every `Tagged` widget, as of the last drawn frame.
+///
+/// Useful to point at UI elements from game code (e.g. tutorials), or to
+/// click on them in tests.
+#[derive(Debug, Clone, Default)]
+pub struct IcedLayoutSnapshot {
+    widgets: HashMap<String, Rectangle>,
+}
+
+impl IcedLayoutSnapshot {
+    pub fn get(&self, id: &str) -> Option<Rectangle> {
+        self.widgets.get(id).copied()
+    }
+
+    /// The center of the widget with the given id
+    pub fn center(&self, id: &str) -> Option<Point> {
+        self.get(id).map(|bounds| {
+            Point::new(bounds.x + bounds.width / 2., bounds.y + bounds.height / 2.)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Rectangle)> {
+        self.widgets.iter().map(|(id, bounds)| (id.as_str(), *bounds))
+    }
+
+    pub(crate) fn set(&mut self, widgets: HashMap<String, Rectangle>) {
+        self.widgets = widgets;
+    }
+}
@@ -1,3 +1,13 @@
+mod animations;
+mod focus;
 mod font_cache;
+mod layout_snapshot;
+mod mouse_cursor;
+mod touch;
 
-pub use font_cache::*;
\ No newline at end of file
+pub use animations::*;
+pub use focus::*;
+pub use font_cache::*;
+pub use layout_snapshot::*;
+pub use mouse_cursor::*;
+pub use touch::*;
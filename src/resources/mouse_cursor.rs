@@ -0,0 +1,36 @@
+use amethyst::winit;
+
+/// The cursor icon requested by the widgets during the last draw.
+///
+/// This resource is updated by the IcedDrawSystem,
+/// and applied to the window by the IcedCursorSystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseCursor {
+    Idle,
+    Pointer,
+    Text,
+    Grab,
+    Grabbing,
+    ResizingHorizontally,
+    ResizingVertically,
+}
+
+impl Default for MouseCursor {
+    fn default() -> Self {
+        MouseCursor::Idle
+    }
+}
+
+impl From<MouseCursor> for winit::MouseCursor {
+    fn from(cursor: MouseCursor) -> Self {
+        match cursor {
+            MouseCursor::Idle => winit::MouseCursor::Default,
+            MouseCursor::Pointer => winit::MouseCursor::Hand,
+            MouseCursor::Text => winit::MouseCursor::Text,
+            MouseCursor::Grab => winit::MouseCursor::Grab,
+            MouseCursor::Grabbing => winit::MouseCursor::Grabbing,
+            MouseCursor::ResizingHorizontally => winit::MouseCursor::EwResize,
+            MouseCursor::ResizingVertically => winit::MouseCursor::NsResize,
+        }
+    }
+}
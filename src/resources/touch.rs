@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Configures how touch events are translated into mouse events.
+///
+/// A tap is a left click. A touch moving further than `drag_threshold` pixels
+/// becomes a drag. Drags starting on widgets used by dragging them, like Sliders
+/// and the splitters of PaneGrids, press the left button where the touch started.
+/// Other drags scroll the content under the finger, unless `drag_scrolls` is disabled.
+#[derive(Debug, Clone)]
+pub struct TouchConfig {
+    /// Holding a touch still for this long triggers a right click.
+    /// `None` disables long presses.
+    pub long_press: Option<Duration>,
+    /// Distance in pixels a touch has to travel before being considered a drag
+    pub drag_threshold: f32,
+    /// When enabled, drags starting outside of draggable widgets are turned
+    /// into scroll events, for scrollable content. Otherwise they are left
+    /// button drags as well.
+    ///
+    /// Custom widgets are registered as draggable through
+    /// `IcedRenderer::register_draggable`.
+    pub drag_scrolls: bool,
+}
+
+impl Default for TouchConfig {
+    fn default() -> Self {
+        TouchConfig {
+            long_press: Some(Duration::from_millis(500)),
+            drag_threshold: 10.,
+            drag_scrolls: true,
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::backend::IcedRenderer;
+use crate::subscription::Subscription;
+use amethyst::ecs::SystemData;
 use iced_native::Hasher;
 
 pub type Element<'a, 'r, Message> = iced_native::Element<'a, Message, IcedRenderer<'r>>;
@@ -22,11 +24,39 @@ pub trait Sandbox: Send + Sync + 'static {
 
     fn view(&mut self) -> Element<Self::UIMessage>;
 
+    /// The sources of UIMessages the Sandbox listens to besides the UI itself,
+    /// such as timers or game events. Called every frame.
+    fn subscription(&self) -> Subscription<Self::UIMessage> {
+        Subscription::none()
+    }
+
     fn hash_layout(&mut self, state: &mut Hasher) {
         self.view().hash_layout(state)
     }
 }
 
+/// A Sandbox whose view reads ECS data directly, e.g. `ReadStorage<'a, Health>`.
+///
+/// The data is fetched by the draw system along with its own resources, like
+/// the SystemData of any System, so the UI is still built in parallel with the
+/// other systems. Use it through `IcedBundle::with_view_data`.
+///
+/// ```ignore
+/// impl<'a> SandboxData<'a> for PartyUIState {
+///     type ViewData = (ReadStorage<'a, Character>, ReadStorage<'a, Health>);
+///
+///     fn view_with_data(&mut self, (characters, healths): &Self::ViewData) -> Element<()> {
+///         ...
+///     }
+/// }
+/// ```
+pub trait SandboxData<'a>: Sandbox {
+    type ViewData: SystemData<'a>;
+
+    /// Replaces `view` when the Sandbox is drawn
+    fn view_with_data(&mut self, data: &Self::ViewData) -> Element<Self::UIMessage>;
+}
+
 #[derive(Default)]
 /// The SandboxContainer is the structure that will store the Sandbox in the
 /// ECS environement
@@ -36,6 +66,14 @@ impl<S: Sandbox> SandboxContainer<S> {
     pub fn new(sandbox: S) -> Self {
         SandboxContainer(sandbox)
     }
+
+    pub fn inner(&self) -> &S {
+        &self.0
+    }
+
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.0
+    }
 }
 
 impl<S: Sandbox> Sandbox for SandboxContainer<S> {
@@ -49,4 +87,8 @@ impl<S: Sandbox> Sandbox for SandboxContainer<S> {
     fn view(&mut self) -> Element<S::UIMessage> {
         self.0.view()
     }
+
+    fn subscription(&self) -> Subscription<S::UIMessage> {
+        self.0.subscription()
+    }
 }
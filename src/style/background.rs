@@ -0,0 +1,206 @@
+use iced_native::Rectangle;
+
+use crate::custom_widget::ImageHandle;
+use crate::primitive::AmethystIcedPrimitive;
+use crate::style::NinePatch;
+
+/// Describes how an area of a widget is filled
+#[derive(Clone)]
+pub enum Background {
+    Color([f32; 4]),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    Image(ImageHandle),
+    NinePatch(NinePatch),
+}
+
+/// A color at a given position of a gradient, from 0 (start) to 1 (end)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub offset: f32,
+    pub color: [f32; 4],
+}
+
+/// Blends colors along a line crossing the whole area.
+///
+/// Like in CSS, the first and last stops are on the corners of the area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearGradient {
+    /// Direction of the gradient in radians: 0 goes from left to right,
+    /// and `PI / 2` from top to bottom
+    pub angle: f32,
+    pub stops: Vec<ColorStop>,
+}
+
+impl LinearGradient {
+    pub fn new(angle: f32) -> Self {
+        LinearGradient {
+            angle,
+            stops: vec![],
+        }
+    }
+
+    /// A gradient from top to bottom between two colors
+    pub fn vertical(top: [f32; 4], bottom: [f32; 4]) -> Self {
+        LinearGradient::new(std::f32::consts::FRAC_PI_2)
+            .stop(0., top)
+            .stop(1., bottom)
+    }
+
+    pub fn stop(mut self, offset: f32, color: [f32; 4]) -> Self {
+        self.stops.push(ColorStop { offset, color });
+        self
+    }
+}
+
+/// Blends colors in circles around a point, e.g. for vignettes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RadialGradient {
+    /// Center of the circles, relative to the area: `[0.5, 0.5]` is its center
+    pub center: [f32; 2],
+    /// Radius of the last stop, relative to half the diagonal of the area:
+    /// 1 reaches the corners when the gradient is centered
+    pub radius: f32,
+    pub stops: Vec<ColorStop>,
+}
+
+impl RadialGradient {
+    pub fn new() -> Self {
+        RadialGradient {
+            center: [0.5, 0.5],
+            radius: 1.,
+            stops: vec![],
+        }
+    }
+
+    /// Darkens the borders of the area, keeping its center transparent
+    pub fn vignette(color: [f32; 4]) -> Self {
+        let [r, g, b, _] = color;
+        RadialGradient::new()
+            .stop(0.4, [r, g, b, 0.])
+            .stop(1., color)
+    }
+
+    pub fn center(mut self, x: f32, y: f32) -> Self {
+        self.center = [x, y];
+        self
+    }
+
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn stop(mut self, offset: f32, color: [f32; 4]) -> Self {
+        self.stops.push(ColorStop { offset, color });
+        self
+    }
+}
+
+impl Default for RadialGradient {
+    fn default() -> Self {
+        RadialGradient::new()
+    }
+}
+
+/// A gradient placed in window coordinates, ready to be tessellated
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    Linear {
+        start: [f32; 2],
+        end: [f32; 2],
+        stops: Vec<ColorStop>,
+    },
+    Radial {
+        center: [f32; 2],
+        radius: f32,
+        stops: Vec<ColorStop>,
+    },
+}
+
+impl Gradient {
+    fn linear(gradient: &LinearGradient, bounds: Rectangle) -> Self {
+        let (sin, cos) = gradient.angle.sin_cos();
+        let length = (bounds.width * cos).abs() + (bounds.height * sin).abs();
+        let center = [bounds.x + bounds.width / 2., bounds.y + bounds.height / 2.];
+        Gradient::Linear {
+            start: [center[0] - cos * length / 2., center[1] - sin * length / 2.],
+            end: [center[0] + cos * length / 2., center[1] + sin * length / 2.],
+            stops: sorted(&gradient.stops),
+        }
+    }
+
+    fn radial(gradient: &RadialGradient, bounds: Rectangle) -> Self {
+        let half_diagonal = (bounds.width * bounds.width + bounds.height * bounds.height).sqrt() / 2.;
+        Gradient::Radial {
+            center: [
+                bounds.x + bounds.width * gradient.center[0],
+                bounds.y + bounds.height * gradient.center[1],
+            ],
+            radius: gradient.radius * half_diagonal,
+            stops: sorted(&gradient.stops),
+        }
+    }
+}
+
+fn sorted(stops: &[ColorStop]) -> Vec<ColorStop> {
+    let mut stops = stops.to_vec();
+    stops.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap_or(std::cmp::Ordering::Equal));
+    stops
+}
+
+impl Background {
+    /// Places the gradient of the background in the given area, if it is one
+    pub(crate) fn gradient(&self, bounds: Rectangle) -> Option<Gradient> {
+        match self {
+            Background::LinearGradient(gradient) => Some(Gradient::linear(gradient, bounds)),
+            Background::RadialGradient(gradient) => Some(Gradient::radial(gradient, bounds)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn primitive(&self, bounds: Rectangle) -> AmethystIcedPrimitive {
+        match self {
+            Background::Color(color) => AmethystIcedPrimitive::Quad(bounds, Some((*color).into())),
+            Background::LinearGradient(_) | Background::RadialGradient(_) => {
+                AmethystIcedPrimitive::Gradient {
+                    bounds,
+                    gradient: self.gradient(bounds).unwrap(),
+                    border_radius: 0.,
+                }
+            }
+            Background::Image(handle) => AmethystIcedPrimitive::Image(bounds, handle.clone()),
+            Background::NinePatch(patch) => AmethystIcedPrimitive::NinePatch(bounds, patch.clone()),
+        }
+    }
+}
+
+impl From<[f32; 4]> for Background {
+    fn from(color: [f32; 4]) -> Self {
+        Background::Color(color)
+    }
+}
+
+impl From<LinearGradient> for Background {
+    fn from(gradient: LinearGradient) -> Self {
+        Background::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Background {
+    fn from(gradient: RadialGradient) -> Self {
+        Background::RadialGradient(gradient)
+    }
+}
+
+impl From<ImageHandle> for Background {
+    fn from(handle: ImageHandle) -> Self {
+        Background::Image(handle)
+    }
+}
+
+impl From<NinePatch> for Background {
+    fn from(patch: NinePatch) -> Self {
+        Background::NinePatch(patch)
+    }
+}
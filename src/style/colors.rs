@@ -6,4 +6,6 @@ pub const PRIMARY_SHADED: [f32;4] = [0.098, 0.462, 0.823, 1.];
 pub const PRIMARY_DARKER: [f32;4] = [0.050, 0.278, 0.631, 1.];
 pub const VERY_LIGHT_GRAY: [f32;4] = [0.98, 0.98, 0.98, 1.];
 pub const LIGHT_GRAY: [f32;4] = [0.878, 0.878, 0.878, 1.];
-pub const GRAY: [f32;4] = [0.619, 0.619, 0.619, 1.];
\ No newline at end of file
+pub const GRAY: [f32;4] = [0.619, 0.619, 0.619, 1.];
+pub const DARK_GRAY: [f32;4] = [0.129, 0.129, 0.129, 1.];
+pub const WHITE: [f32;4] = [1., 1., 1., 1.];
@@ -1,4 +1,10 @@
+mod background;
 mod border;
 pub mod colors;
+mod nine_patch;
+mod shadow;
 
-pub use border::*;
\ No newline at end of file
+pub use background::*;
+pub use border::*;
+pub use nine_patch::*;
+pub use shadow::*;
@@ -0,0 +1,94 @@
+use iced_native::Rectangle;
+
+use crate::custom_widget::ImageHandle;
+
+/// An image split in nine regions using pixel insets.
+///
+/// Corners keep their size, edges are stretched along one axis and the center
+/// is stretched along both, which allows frames and bars of any size to be
+/// drawn from a single small texture.
+#[derive(Clone, Hash)]
+pub struct NinePatch {
+    pub handle: ImageHandle,
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl NinePatch {
+    pub fn new<T: Into<ImageHandle>>(handle: T, left: u32, right: u32, top: u32, bottom: u32) -> Self {
+        NinePatch {
+            handle: handle.into(),
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    /// Creates a NinePatch with the same inset on every side
+    pub fn uniform<T: Into<ImageHandle>>(handle: T, inset: u32) -> Self {
+        NinePatch::new(handle, inset, inset, inset, inset)
+    }
+
+    /// Returns the nine (bounds, uv) pairs making up the patch, row by row.
+    pub(crate) fn regions(&self, bounds: Rectangle) -> Vec<(Rectangle, Rectangle)> {
+        let (width, height) = self.handle.dimensions();
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+
+        // Insets are shrunk proportionally when the target is too small to fit them
+        let shrink_x = (bounds.width / (self.left + self.right).max(1) as f32).min(1.);
+        let shrink_y = (bounds.height / (self.top + self.bottom).max(1) as f32).min(1.);
+        let (left, right) = (self.left as f32 * shrink_x, self.right as f32 * shrink_x);
+        let (top, bottom) = (self.top as f32 * shrink_y, self.bottom as f32 * shrink_y);
+
+        let xs = [
+            bounds.x,
+            bounds.x + left,
+            bounds.x + bounds.width - right,
+            bounds.x + bounds.width,
+        ];
+        let ys = [
+            bounds.y,
+            bounds.y + top,
+            bounds.y + bounds.height - bottom,
+            bounds.y + bounds.height,
+        ];
+        let us = [
+            0.,
+            self.left as f32 / width,
+            1. - self.right as f32 / width,
+            1.,
+        ];
+        let vs = [
+            0.,
+            self.top as f32 / height,
+            1. - self.bottom as f32 / height,
+            1.,
+        ];
+
+        let mut regions = Vec::with_capacity(9);
+        for row in 0..3 {
+            for col in 0..3 {
+                let region = Rectangle {
+                    x: xs[col],
+                    y: ys[row],
+                    width: xs[col + 1] - xs[col],
+                    height: ys[row + 1] - ys[row],
+                };
+                if region.width <= 0. || region.height <= 0. {
+                    continue;
+                }
+                let uv = Rectangle {
+                    x: us[col],
+                    y: vs[row],
+                    width: us[col + 1] - us[col],
+                    height: vs[row + 1] - vs[row],
+                };
+                regions.push((region, uv));
+            }
+        }
+        regions
+    }
+}
@@ -0,0 +1,33 @@
+/// A soft shadow cast by a box, or a glow around it when it isn't offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// Moves the shadow relative to the box, e.g. `[0., 4.]` for a light coming from above
+    pub offset: [f32; 2],
+    /// Distance over which the shadow fades out, like CSS's blur radius
+    pub blur_radius: f32,
+    /// Grows the shadow on every side before it is blurred
+    pub spread: f32,
+    pub color: [f32; 4],
+}
+
+impl Shadow {
+    /// A shadow below a floating box, e.g. a window or a menu
+    pub fn drop(color: [f32; 4]) -> Self {
+        Shadow {
+            offset: [0., 6.],
+            blur_radius: 16.,
+            spread: 0.,
+            color,
+        }
+    }
+
+    /// A halo of the given color all around the box
+    pub fn glow(color: [f32; 4], radius: f32) -> Self {
+        Shadow {
+            offset: [0., 0.],
+            blur_radius: radius,
+            spread: radius / 4.,
+            color,
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use amethyst::ecs::World;
+use amethyst::shrev::{Event, EventChannel, ReaderId};
+
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// A source of UIMessages which is active as long as the Sandbox declares it.
+///
+/// Subscriptions are compared every frame with the ones of the previous frame:
+/// new ones are started, missing ones are stopped and the others keep running.
+/// Two subscriptions are the same when they are of the same kind, with the same
+/// parameters and the same id. The id is given by the caller, since functions
+/// of the same signature can't be told apart: subscriptions which should run
+/// side by side must have different ids.
+pub struct Subscription<M> {
+    recipes: Vec<Box<dyn Recipe<M>>>,
+}
+
+impl<M: Send + Sync + 'static> Subscription<M> {
+    pub fn none() -> Self {
+        Subscription { recipes: vec![] }
+    }
+
+    pub fn batch<I: IntoIterator<Item = Subscription<M>>>(subscriptions: I) -> Self {
+        Subscription {
+            recipes: subscriptions
+                .into_iter()
+                .flat_map(|subscription| subscription.recipes)
+                .collect(),
+        }
+    }
+
+    /// Produces a message at the given interval, using Amethyst's real time
+    /// so that the UI keeps running while the game is paused.
+    pub fn every<I, F>(id: I, interval: Duration, f: F) -> Self
+    where
+        I: Hash,
+        F: Fn(Instant) -> M + Send + Sync + 'static,
+    {
+        Subscription {
+            recipes: vec![Box::new(Every {
+                id: hash_id(id),
+                interval,
+                elapsed: Duration::from_secs(0),
+                f,
+            })],
+        }
+    }
+
+    /// Turns the events of an `EventChannel<E>` of the World into messages.
+    ///
+    /// Only the events written after the subscription started are read.
+    pub fn events<I, E, F>(id: I, f: F) -> Self
+    where
+        I: Hash,
+        E: Event,
+        F: Fn(&E) -> Option<M> + Send + Sync + 'static,
+    {
+        Subscription {
+            recipes: vec![Box::new(Events {
+                id: hash_id(id),
+                f,
+                reader: None,
+            })],
+        }
+    }
+}
+
+fn hash_id<I: Hash>(id: I) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+trait Recipe<M>: Send + Sync {
+    fn id(&self) -> u64;
+
+    /// Called once, when the subscription starts
+    fn start(&mut self, world: &World);
+
+    /// Called every frame while the subscription is active
+    fn poll(&mut self, world: &World, delta: Duration, messages: &mut Vec<M>);
+}
+
+struct Every<F> {
+    id: u64,
+    interval: Duration,
+    elapsed: Duration,
+    f: F,
+}
+
+impl<M, F> Recipe<M> for Every<F>
+where
+    F: Fn(Instant) -> M + Send + Sync + 'static,
+{
+    fn id(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        TypeId::of::<Self>().hash(&mut hasher);
+        self.id.hash(&mut hasher);
+        self.interval.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn start(&mut self, _world: &World) {}
+
+    fn poll(&mut self, _world: &World, delta: Duration, messages: &mut Vec<M>) {
+        if self.interval == Duration::from_secs(0) {
+            messages.push((self.f)(Instant::now()));
+            return;
+        }
+        self.elapsed += delta;
+        while self.elapsed >= self.interval {
+            self.elapsed -= self.interval;
+            messages.push((self.f)(Instant::now()));
+        }
+    }
+}
+
+struct Events<E: Event, F> {
+    id: u64,
+    f: F,
+    reader: Option<ReaderId<E>>,
+}
+
+impl<M, E, F> Recipe<M> for Events<E, F>
+where
+    E: Event,
+    F: Fn(&E) -> Option<M> + Send + Sync + 'static,
+{
+    fn id(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        TypeId::of::<Self>().hash(&mut hasher);
+        self.id.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn start(&mut self, world: &World) {
+        match world.try_fetch_mut::<EventChannel<E>>() {
+            Some(mut channel) => self.reader = Some(channel.register_reader()),
+            None => log::warn!(
+                "No EventChannel<{}> was found in the World, the subscription won't produce any message.",
+                std::any::type_name::<E>()
+            ),
+        }
+    }
+
+    fn poll(&mut self, world: &World, _delta: Duration, messages: &mut Vec<M>) {
+        if let (Some(reader), Some(channel)) = (
+            self.reader.as_mut(),
+            world.try_fetch::<EventChannel<E>>(),
+        ) {
+            messages.extend(channel.read(reader).filter_map(&self.f));
+        }
+    }
+}
+
+/// The subscriptions currently running, kept by the IcedInteropSystem
+pub(crate) struct Subscriptions<M> {
+    running: Vec<(u64, Box<dyn Recipe<M>>)>,
+}
+
+impl<M> Default for Subscriptions<M> {
+    fn default() -> Self {
+        Subscriptions { running: vec![] }
+    }
+}
+
+impl<M> Subscriptions<M> {
+    /// Starts the new subscriptions and stops the ones which aren't declared anymore
+    pub(crate) fn update(&mut self, subscription: Subscription<M>, world: &World) {
+        let mut running = std::mem::replace(&mut self.running, vec![]);
+        for recipe in subscription.recipes {
+            let id = recipe.id();
+            if self.running.iter().any(|(running_id, _)| *running_id == id) {
+                // Declared twice in the same frame
+                continue;
+            }
+            match running.iter().position(|(running_id, _)| *running_id == id) {
+                Some(index) => self.running.push(running.swap_remove(index)),
+                None => {
+                    let mut recipe = recipe;
+                    recipe.start(world);
+                    self.running.push((id, recipe));
+                }
+            }
+        }
+    }
+
+    /// Collects the messages produced by the subscriptions since the last frame
+    pub(crate) fn poll(&mut self, world: &World, delta: Duration) -> Vec<M> {
+        let mut messages = vec![];
+        for (_, recipe) in self.running.iter_mut() {
+            recipe.poll(world, delta, &mut messages);
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Message {
+        First,
+        Second,
+    }
+
+    fn tick(_instant: Instant) -> u32 {
+        1
+    }
+
+    fn polled<M>(subscriptions: &mut Subscriptions<M>, subscription: Subscription<M>) -> Vec<M> {
+        let world = World::new();
+        subscriptions.update(subscription, &world);
+        subscriptions.poll(&world, Duration::from_secs(1))
+    }
+
+    #[test]
+    fn same_functions_with_different_ids_run_side_by_side() {
+        let mut subscriptions = Subscriptions::default();
+        let every = |id| Subscription::every(id, Duration::from_secs(1), tick as fn(_) -> _);
+        assert_eq!(
+            polled(
+                &mut subscriptions,
+                Subscription::batch(vec![every("a"), every("b")])
+            )
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn same_ids_are_only_run_once() {
+        let mut subscriptions = Subscriptions::default();
+        let every = |id| Subscription::every(id, Duration::from_secs(1), tick as fn(_) -> _);
+        assert_eq!(
+            polled(
+                &mut subscriptions,
+                Subscription::batch(vec![every("a"), every("a")])
+            )
+            .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn subscriptions_keep_running_across_frames() {
+        let mut subscriptions = Subscriptions::default();
+        let every =
+            |f: fn(Instant) -> Message| Subscription::every("timer", Duration::from_secs(2), f);
+        // Half of the interval elapses on each frame
+        assert_eq!(
+            polled(&mut subscriptions, every(|_| Message::First)),
+            vec![]
+        );
+        // Another function with the same id is the same subscription, which
+        // isn't restarted
+        assert_eq!(
+            polled(&mut subscriptions, every(|_| Message::Second)),
+            vec![Message::First]
+        );
+    }
+}
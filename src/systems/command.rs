@@ -0,0 +1,105 @@
+use amethyst::core::ArcThreadPool;
+use amethyst::ecs::{ReadExpect, System, Write};
+use amethyst::shrev::EventChannel;
+use iced_native::futures::{
+    future::BoxFuture,
+    task::{waker_ref, ArcWake, Context},
+    FutureExt,
+};
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::application::{Application, ApplicationSandbox};
+use crate::sandbox::SandboxContainer;
+
+/// Spawns the commands of an Application on Amethyst's thread pool, and sends
+/// the resulting messages back through the `EventChannel` of UIMessages.
+///
+/// A future is only polled when it is woken, so waiting ones never hold a
+/// worker of the pool.
+pub(crate) struct IcedCommandSystem<A: Application> {
+    sender: Sender<A::UIMessage>,
+    receiver: Receiver<A::UIMessage>,
+}
+
+impl<A: Application> Default for IcedCommandSystem<A> {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        IcedCommandSystem { sender, receiver }
+    }
+}
+
+impl<'a, A: Application> System<'a> for IcedCommandSystem<A> {
+    type SystemData = (
+        Option<Write<'a, SandboxContainer<ApplicationSandbox<A>>>>,
+        ReadExpect<'a, ArcThreadPool>,
+        Write<'a, EventChannel<A::UIMessage>>,
+    );
+
+    fn run(&mut self, (sandbox, pool, mut ui_messages): Self::SystemData) {
+        // Messages of the commands which completed since the last frame
+        self.receiver
+            .try_iter()
+            .for_each(|message| ui_messages.single_write(message));
+
+        let sandbox = match sandbox {
+            Some(sandbox) => sandbox,
+            None => return,
+        };
+        sandbox
+            .inner()
+            .take_commands()
+            .into_iter()
+            .flat_map(|command| command.futures())
+            .for_each(|future| {
+                let sender = self.sender.clone();
+                // The receiver only goes away with the system, when the game stops
+                let future = future.map(move |message| {
+                    let _ = sender.send(message);
+                });
+                Task::spawn(future.boxed(), pool.clone());
+            });
+    }
+}
+
+/// A future polled on the thread pool each time it is woken
+struct Task {
+    /// Taken out once the future has completed
+    future: Mutex<Option<BoxFuture<'static, ()>>>,
+    pool: ArcThreadPool,
+}
+
+impl Task {
+    fn spawn(future: BoxFuture<'static, ()>, pool: ArcThreadPool) {
+        let task = Arc::new(Task {
+            future: Mutex::new(Some(future)),
+            pool,
+        });
+        ArcWake::wake(task);
+    }
+
+    /// Polls the future once. The lock is held meanwhile, so a wake coming
+    /// from another thread polls it again right after instead of being lost.
+    fn poll(self: &Arc<Self>) {
+        let mut slot = match self.future.lock() {
+            Ok(slot) => slot,
+            // The future panicked while being polled
+            Err(_) => return,
+        };
+        if let Some(mut future) = slot.take() {
+            let waker = waker_ref(self);
+            let mut context = Context::from_waker(&*waker);
+            if future.poll_unpin(&mut context).is_pending() {
+                *slot = Some(future);
+            }
+        }
+    }
+}
+
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let task = arc_self.clone();
+        arc_self.pool.spawn(move || task.poll());
+    }
+}
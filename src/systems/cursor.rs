@@ -0,0 +1,26 @@
+use amethyst::ecs::{Read, ReadExpect, System};
+use amethyst::window::Window;
+
+use crate::resources::MouseCursor;
+
+/// Applies the cursor icon requested by the widgets to the window
+#[derive(Default)]
+pub(crate) struct IcedCursorSystem {
+    current: Option<MouseCursor>,
+}
+
+impl<'a> System<'a> for IcedCursorSystem {
+    type SystemData = (Read<'a, MouseCursor>, Option<ReadExpect<'a, Window>>);
+
+    fn run(&mut self, (cursor, window): Self::SystemData) {
+        let window = match window {
+            Some(window) => window,
+            None => return,
+        };
+        // Only talks to the window when the requested cursor changes
+        if self.current != Some(*cursor) {
+            window.set_cursor((*cursor).into());
+            self.current = Some(*cursor);
+        }
+    }
+}
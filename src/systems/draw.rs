@@ -1,53 +1,114 @@
 use amethyst::assets::AssetStorage;
+use amethyst::core::Time;
 use amethyst::ecs::{Read, ReadExpect, System, SystemData, World, Write, WriteExpect};
 use amethyst::renderer::SpriteSheet;
 use amethyst::shrev::{EventChannel, ReaderId};
 use amethyst::window::ScreenDimensions;
 use amethyst::winit::{
-    ElementState, Event as WinitEvent, MouseButton, WindowEvent as WinitWindowEvent,
+    ElementState, Event as WinitEvent, KeyboardInput, ModifiersState, MouseButton, VirtualKeyCode,
+    WindowEvent as WinitWindowEvent,
 };
-use iced_native::{Cache, Size, UserInterface};
+use iced_native::{Cache, Point, Rectangle, Size, UserInterface};
 
-use crate::backend::IcedRenderer;
-use crate::primitive::IcedPrimitives;
-use crate::sandbox::{Sandbox, SandboxContainer};
-use crate::resources::FontCache;
+use crate::backend::{IcedRenderer, OverlayState};
+use crate::primitive::{AmethystIcedPrimitive, IcedPrimitives};
+use crate::sandbox::{Element, Sandbox, SandboxContainer, SandboxData};
+use crate::resources::{
+    FocusManager, FontCache, IcedAnimations, IcedLayoutSnapshot, MouseCursor, TouchConfig,
+};
 
+use crate::systems::TouchState;
+use crate::widget::PaneGridStyle;
 use crate::IcedGlyphBrush;
 
-use std::hash::Hasher;
+use std::time::Instant;
+
+/// How the draw system builds the view of a Sandbox, and the ECS data it needs for it
+pub(crate) trait ViewSource<'a, S: Sandbox>: Send + Sync + 'static {
+    type Data: SystemData<'a>;
+
+    fn view<'s>(sandbox: &'s mut S, data: &Self::Data) -> Element<'s, 's, S::UIMessage>;
+}
+
+/// Builds the view with `Sandbox::view`
+pub(crate) struct PlainView;
+
+impl<'a, S: Sandbox> ViewSource<'a, S> for PlainView {
+    type Data = ();
+
+    fn view<'s>(sandbox: &'s mut S, _data: &()) -> Element<'s, 's, S::UIMessage> {
+        sandbox.view()
+    }
+}
+
+/// Builds the view with `SandboxData::view_with_data`
+pub(crate) struct DataView;
+
+impl<'a, S: SandboxData<'a>> ViewSource<'a, S> for DataView {
+    type Data = S::ViewData;
+
+    fn view<'s>(sandbox: &'s mut S, data: &S::ViewData) -> Element<'s, 's, S::UIMessage> {
+        sandbox.view_with_data(data)
+    }
+}
+
+/// Fetches the data of the view from the World, then builds it
+pub(crate) fn fetch_view<'s, 'w, S, V>(
+    sandbox: &'s mut S,
+    world: &'w World,
+) -> Element<'s, 's, S::UIMessage>
+where
+    S: Sandbox,
+    V: for<'c> ViewSource<'c, S>,
+{
+    let data = <<V as ViewSource<'w, S>>::Data as SystemData<'w>>::fetch(world);
+    <V as ViewSource<'w, S>>::view(sandbox, &data)
+}
 
-pub(crate) struct IcedDrawSystem<S: Sandbox> {
-    _sandbox: std::marker::PhantomData<S>,
+pub(crate) struct IcedDrawSystem<S: Sandbox, V = PlainView> {
+    _sandbox: std::marker::PhantomData<(S, V)>,
     winit_reader_id: Option<ReaderId<WinitEvent>>,
     cache: Option<Cache>,
+    touch: TouchState,
+    overlays: OverlayState,
 }
 
-impl<S: Sandbox> Default for IcedDrawSystem<S> {
+impl<S: Sandbox, V> Default for IcedDrawSystem<S, V> {
     fn default() -> Self {
         IcedDrawSystem {
             _sandbox: std::marker::PhantomData,
             winit_reader_id: None,
             cache: Some(Cache::default()),
+            touch: TouchState::default(),
+            overlays: OverlayState::default(),
         }
     }
 }
 
-impl<'a, S: Sandbox> System<'a> for IcedDrawSystem<S> {
-    type SystemData = (
-        Read<'a, EventChannel<WinitEvent>>,
-        Write<'a, EventChannel<<S as Sandbox>::UIMessage>>,
-        Option<Write<'a, SandboxContainer<S>>>,
-        Read<'a, AssetStorage<SpriteSheet>>,
-        WriteExpect<'a, IcedGlyphBrush>,
-        Read<'a, FontCache>,
-        ReadExpect<'a, ScreenDimensions>,
-        Write<'a, IcedPrimitives>,
-    );
-
-    fn run(
-        &mut self,
-        (
+type DrawSystemData<'a, S> = (
+    Read<'a, EventChannel<WinitEvent>>,
+    Write<'a, EventChannel<<S as Sandbox>::UIMessage>>,
+    Option<Write<'a, SandboxContainer<S>>>,
+    Read<'a, AssetStorage<SpriteSheet>>,
+    WriteExpect<'a, IcedGlyphBrush>,
+    Read<'a, FontCache>,
+    ReadExpect<'a, ScreenDimensions>,
+    Write<'a, IcedPrimitives>,
+    Write<'a, MouseCursor>,
+    Write<'a, FocusManager>,
+    Read<'a, TouchConfig>,
+    Write<'a, IcedLayoutSnapshot>,
+    Read<'a, Time>,
+    Write<'a, IcedAnimations>,
+    Read<'a, PaneGridStyle>,
+);
+
+/// Builds, updates and draws the UI of the Sandbox
+impl<'a, S: Sandbox, V: ViewSource<'a, S>> System<'a> for IcedDrawSystem<S, V> {
+    type SystemData = (DrawSystemData<'a, S>, V::Data);
+
+    fn run(&mut self, (data, view_data): Self::SystemData) {
+        let (
             winit_events,
             mut ui_messages,
             sandbox,
@@ -56,86 +117,151 @@ impl<'a, S: Sandbox> System<'a> for IcedDrawSystem<S> {
             font_cache,
             screen_dimensions,
             mut iced_primitives,
-        ): Self::SystemData,
-    ) {
+            mut mouse_cursor,
+            mut focus,
+            touch_config,
+            mut layout_snapshot,
+            time,
+            mut animations,
+            pane_grid_style,
+        ) = data;
         if sandbox.is_none() {
             log::warn!("No sandbox was found in resources, Iced UI will not be drawn.");
             return;
         }
         let mut sandbox = sandbox.unwrap();
         {
-            let mut renderer = IcedRenderer::new(sprite_sheet, glyph_brush, font_cache);
+            animations.set_time(time.absolute_real_time());
+            let bounds: Size = [screen_dimensions.width(), screen_dimensions.height()].into();
+            let mut renderer = IcedRenderer::new(sprite_sheet, glyph_brush, font_cache)
+                .with_animations(animations)
+                .with_viewport(bounds)
+                .with_overlays(std::mem::take(&mut self.overlays))
+                .with_pane_grid_style(*pane_grid_style);
 
             let reader = self
                 .winit_reader_id
                 .as_mut()
                 .expect("Failed to get ReaderID: IcedUpdateSystem has not been setup.");
-            let bounds: Size = [screen_dimensions.width(), screen_dimensions.height()].into();
             let cache = self.cache.take().unwrap();
-            let mut user_interface =
-                UserInterface::build(sandbox.view(), bounds, cache, &mut renderer);
-            winit_events
+            let view = V::view(sandbox.inner_mut(), &view_data);
+            let mut user_interface = UserInterface::build(view, bounds, cache, &mut renderer);
+            let focus_clicks = focus.take_pending_clicks();
+            let now = Instant::now();
+            let touch_state = &mut self.touch;
+            let long_press = touch_state.poll(now, &touch_config);
+            let overlay_events = renderer.overlay_events();
+            let events = winit_events
                 .read(reader)
-                .filter_map(|winit_event| match winit_event {
+                .flat_map(|winit_event| match winit_event {
                     // TODO: Propper handling of window events, using iced_winit::conversion
                     // Possible when Amethyst upgrades to winit 0.22
                     WinitEvent::WindowEvent {
                         event: WinitWindowEvent::Resized(size),
                         ..
-                    } => Some(iced_native::Event::Window(
+                    } => vec![iced_native::Event::Window(
                         iced_native::window::Event::Resized {
                             width: size.width as u32,
                             height: size.height as u32,
                         },
-                    )),
+                    )],
                     WinitEvent::WindowEvent {
-                        event:
-                            WinitWindowEvent::MouseInput {
-                                button: MouseButton::Left,
-                                state: ElementState::Pressed,
-                                ..
+                        event: WinitWindowEvent::MouseInput { button, state, .. },
+                        ..
+                    } => match (mouse_button(*button), state) {
+                        (Some(button), ElementState::Pressed) => vec![iced_native::Event::Mouse(
+                            iced_native::input::mouse::Event::Input {
+                                state: iced_native::input::ButtonState::Pressed,
+                                button,
+                            },
+                        )],
+                        (Some(button), ElementState::Released) => vec![iced_native::Event::Mouse(
+                            iced_native::input::mouse::Event::Input {
+                                state: iced_native::input::ButtonState::Released,
+                                button,
                             },
+                        )],
+                        (None, _) => vec![],
+                    },
+                    WinitEvent::WindowEvent {
+                        event: WinitWindowEvent::CursorMoved { position, .. },
                         ..
-                    } => Some(iced_native::Event::Mouse(
-                        iced_native::input::mouse::Event::Input {
-                            state: iced_native::input::ButtonState::Pressed,
-                            button: iced_native::input::mouse::Button::Left,
+                    } => vec![iced_native::Event::Mouse(
+                        iced_native::input::mouse::Event::CursorMoved {
+                            x: position.x as f32 * screen_dimensions.hidpi_factor() as f32,
+                            y: position.y as f32 * screen_dimensions.hidpi_factor() as f32,
                         },
-                    )),
+                    )],
                     WinitEvent::WindowEvent {
                         event:
-                            WinitWindowEvent::MouseInput {
-                                button: MouseButton::Left,
-                                state: ElementState::Released,
+                            WinitWindowEvent::KeyboardInput {
+                                input:
+                                    KeyboardInput {
+                                        state,
+                                        virtual_keycode: Some(key),
+                                        modifiers,
+                                        ..
+                                    },
                                 ..
                             },
                         ..
-                    } => Some(iced_native::Event::Mouse(
-                        iced_native::input::mouse::Event::Input {
-                            state: iced_native::input::ButtonState::Released,
-                            button: iced_native::input::mouse::Button::Left,
-                        },
-                    )),
+                    } => match key_code(*key) {
+                        Some(key_code) => vec![iced_native::Event::Keyboard(
+                            iced_native::input::keyboard::Event::Input {
+                                state: button_state(*state),
+                                key_code,
+                                modifiers: modifiers_state(*modifiers),
+                            },
+                        )],
+                        None => vec![],
+                    },
                     WinitEvent::WindowEvent {
-                        event: WinitWindowEvent::CursorMoved { position, .. },
+                        event: WinitWindowEvent::ReceivedCharacter(c),
                         ..
-                    } => Some(iced_native::Event::Mouse(
-                        iced_native::input::mouse::Event::CursorMoved {
-                            x: position.x as f32 * screen_dimensions.hidpi_factor() as f32,
-                            y: position.y as f32 * screen_dimensions.hidpi_factor() as f32,
-                        },
-                    )),
-                    _ => None,
+                    } => vec![iced_native::Event::Keyboard(
+                        iced_native::input::keyboard::Event::CharacterReceived(*c),
+                    )],
+                    WinitEvent::WindowEvent {
+                        event: WinitWindowEvent::Touch(_),
+                        ..
+                    } => touch_state.handle_event(
+                        winit_event,
+                        screen_dimensions.hidpi_factor() as f32,
+                        now,
+                        &touch_config,
+                    ),
+                    _ => vec![],
                 })
+                .chain(long_press)
+                .chain(focus_clicks.into_iter().flat_map(click_events))
+                .collect::<Vec<_>>();
+            // Each event is captured right before being sent, see `capture_event`
+            overlay_events
+                .into_iter()
+                .chain(
+                    events
+                        .into_iter()
+                        .map(|iced_event| renderer.capture_event(iced_event)),
+                )
                 .flat_map(|iced_event| user_interface.update(vec![iced_event], None, &renderer))
                 .for_each(|ui_msg| ui_messages.single_write(ui_msg));
 
-            iced_primitives.0 = Some(user_interface.draw(&mut renderer));
+            let primitives = user_interface.draw(&mut renderer);
+            *mouse_cursor = renderer.mouse_cursor();
+
+            layout_snapshot.set(renderer.take_ids());
+            self.overlays = renderer.take_overlays();
+            focus.set_focusables(renderer.take_focusables());
+            self.touch.set_draggables(renderer.take_draggables());
+            iced_primitives.0 = Some(match focus.ring_bounds() {
+                Some(ring) => {
+                    AmethystIcedPrimitive::Group(vec![primitives, focus_ring(ring, &focus)])
+                }
+                None => primitives,
+            });
             self.cache = Some(user_interface.into_cache());
+            renderer.end_animation_frame();
         }
-        let mut hasher = iced_native::Hasher::default();
-        sandbox.hash_layout(&mut hasher);
-        iced_primitives.1 = hasher.finish();
     }
 
     fn setup(&mut self, world: &mut World) {
@@ -144,3 +270,359 @@ impl<'a, S: Sandbox> System<'a> for IcedDrawSystem<S> {
         self.winit_reader_id = Some(winit_event_channel.register_reader());
     }
 }
+
+/// Draws the focus ring above the overlay holding the focused widget, and under
+/// the overlays drawn above it.
+///
+/// The ring is nested in as many overlays as the widget: being drawn last, it goes
+/// after everything else in the layer of the widget.
+fn focus_ring(bounds: Rectangle, focus: &FocusManager) -> AmethystIcedPrimitive {
+    (0..focus.ring_layer()).fold(
+        AmethystIcedPrimitive::border(bounds, &focus.ring),
+        |ring, _| AmethystIcedPrimitive::Overlay(Box::new(ring)),
+    )
+}
+
+/// Converts the mouse buttons used by the widgets
+fn mouse_button(button: MouseButton) -> Option<iced_native::input::mouse::Button> {
+    match button {
+        MouseButton::Left => Some(iced_native::input::mouse::Button::Left),
+        MouseButton::Right => Some(iced_native::input::mouse::Button::Right),
+        MouseButton::Middle => Some(iced_native::input::mouse::Button::Middle),
+        MouseButton::Other(_) => None,
+    }
+}
+
+/// Converts the state of a key
+fn button_state(state: ElementState) -> iced_native::input::ButtonState {
+    match state {
+        ElementState::Pressed => iced_native::input::ButtonState::Pressed,
+        ElementState::Released => iced_native::input::ButtonState::Released,
+    }
+}
+
+fn modifiers_state(modifiers: ModifiersState) -> iced_native::input::keyboard::ModifiersState {
+    iced_native::input::keyboard::ModifiersState {
+        shift: modifiers.shift,
+        control: modifiers.ctrl,
+        alt: modifiers.alt,
+        logo: modifiers.logo,
+    }
+}
+
+/// Converts the keys used by the widgets, i.e. the keys found on most keyboards
+fn key_code(key: VirtualKeyCode) -> Option<iced_native::input::keyboard::KeyCode> {
+    use iced_native::input::keyboard::KeyCode;
+    Some(match key {
+        VirtualKeyCode::Back => KeyCode::Backspace,
+        VirtualKeyCode::Return => KeyCode::Enter,
+        VirtualKeyCode::Key1 => KeyCode::Key1,
+        VirtualKeyCode::Key2 => KeyCode::Key2,
+        VirtualKeyCode::Key3 => KeyCode::Key3,
+        VirtualKeyCode::Key4 => KeyCode::Key4,
+        VirtualKeyCode::Key5 => KeyCode::Key5,
+        VirtualKeyCode::Key6 => KeyCode::Key6,
+        VirtualKeyCode::Key7 => KeyCode::Key7,
+        VirtualKeyCode::Key8 => KeyCode::Key8,
+        VirtualKeyCode::Key9 => KeyCode::Key9,
+        VirtualKeyCode::Key0 => KeyCode::Key0,
+        VirtualKeyCode::A => KeyCode::A,
+        VirtualKeyCode::B => KeyCode::B,
+        VirtualKeyCode::C => KeyCode::C,
+        VirtualKeyCode::D => KeyCode::D,
+        VirtualKeyCode::E => KeyCode::E,
+        VirtualKeyCode::F => KeyCode::F,
+        VirtualKeyCode::G => KeyCode::G,
+        VirtualKeyCode::H => KeyCode::H,
+        VirtualKeyCode::I => KeyCode::I,
+        VirtualKeyCode::J => KeyCode::J,
+        VirtualKeyCode::K => KeyCode::K,
+        VirtualKeyCode::L => KeyCode::L,
+        VirtualKeyCode::M => KeyCode::M,
+        VirtualKeyCode::N => KeyCode::N,
+        VirtualKeyCode::O => KeyCode::O,
+        VirtualKeyCode::P => KeyCode::P,
+        VirtualKeyCode::Q => KeyCode::Q,
+        VirtualKeyCode::R => KeyCode::R,
+        VirtualKeyCode::S => KeyCode::S,
+        VirtualKeyCode::T => KeyCode::T,
+        VirtualKeyCode::U => KeyCode::U,
+        VirtualKeyCode::V => KeyCode::V,
+        VirtualKeyCode::W => KeyCode::W,
+        VirtualKeyCode::X => KeyCode::X,
+        VirtualKeyCode::Y => KeyCode::Y,
+        VirtualKeyCode::Z => KeyCode::Z,
+        VirtualKeyCode::Escape => KeyCode::Escape,
+        VirtualKeyCode::F1 => KeyCode::F1,
+        VirtualKeyCode::F2 => KeyCode::F2,
+        VirtualKeyCode::F3 => KeyCode::F3,
+        VirtualKeyCode::F4 => KeyCode::F4,
+        VirtualKeyCode::F5 => KeyCode::F5,
+        VirtualKeyCode::F6 => KeyCode::F6,
+        VirtualKeyCode::F7 => KeyCode::F7,
+        VirtualKeyCode::F8 => KeyCode::F8,
+        VirtualKeyCode::F9 => KeyCode::F9,
+        VirtualKeyCode::F10 => KeyCode::F10,
+        VirtualKeyCode::F11 => KeyCode::F11,
+        VirtualKeyCode::F12 => KeyCode::F12,
+        VirtualKeyCode::Insert => KeyCode::Insert,
+        VirtualKeyCode::Home => KeyCode::Home,
+        VirtualKeyCode::Delete => KeyCode::Delete,
+        VirtualKeyCode::End => KeyCode::End,
+        VirtualKeyCode::PageDown => KeyCode::PageDown,
+        VirtualKeyCode::PageUp => KeyCode::PageUp,
+        VirtualKeyCode::Left => KeyCode::Left,
+        VirtualKeyCode::Up => KeyCode::Up,
+        VirtualKeyCode::Right => KeyCode::Right,
+        VirtualKeyCode::Down => KeyCode::Down,
+        VirtualKeyCode::Space => KeyCode::Space,
+        VirtualKeyCode::Tab => KeyCode::Tab,
+        VirtualKeyCode::Numpad0 => KeyCode::Numpad0,
+        VirtualKeyCode::Numpad1 => KeyCode::Numpad1,
+        VirtualKeyCode::Numpad2 => KeyCode::Numpad2,
+        VirtualKeyCode::Numpad3 => KeyCode::Numpad3,
+        VirtualKeyCode::Numpad4 => KeyCode::Numpad4,
+        VirtualKeyCode::Numpad5 => KeyCode::Numpad5,
+        VirtualKeyCode::Numpad6 => KeyCode::Numpad6,
+        VirtualKeyCode::Numpad7 => KeyCode::Numpad7,
+        VirtualKeyCode::Numpad8 => KeyCode::Numpad8,
+        VirtualKeyCode::Numpad9 => KeyCode::Numpad9,
+        VirtualKeyCode::NumpadEnter => KeyCode::NumpadEnter,
+        VirtualKeyCode::Add => KeyCode::Add,
+        VirtualKeyCode::Subtract => KeyCode::Subtract,
+        VirtualKeyCode::Multiply => KeyCode::Multiply,
+        VirtualKeyCode::Divide => KeyCode::Divide,
+        VirtualKeyCode::Decimal => KeyCode::Decimal,
+        VirtualKeyCode::Apostrophe => KeyCode::Apostrophe,
+        VirtualKeyCode::Backslash => KeyCode::Backslash,
+        VirtualKeyCode::Comma => KeyCode::Comma,
+        VirtualKeyCode::Equals => KeyCode::Equals,
+        VirtualKeyCode::Grave => KeyCode::Grave,
+        VirtualKeyCode::LBracket => KeyCode::LBracket,
+        VirtualKeyCode::Minus => KeyCode::Minus,
+        VirtualKeyCode::Period => KeyCode::Period,
+        VirtualKeyCode::RBracket => KeyCode::RBracket,
+        VirtualKeyCode::Semicolon => KeyCode::Semicolon,
+        VirtualKeyCode::Slash => KeyCode::Slash,
+        VirtualKeyCode::LAlt => KeyCode::LAlt,
+        VirtualKeyCode::LControl => KeyCode::LControl,
+        VirtualKeyCode::LShift => KeyCode::LShift,
+        VirtualKeyCode::LWin => KeyCode::LWin,
+        VirtualKeyCode::RAlt => KeyCode::RAlt,
+        VirtualKeyCode::RControl => KeyCode::RControl,
+        VirtualKeyCode::RShift => KeyCode::RShift,
+        VirtualKeyCode::RWin => KeyCode::RWin,
+        _ => return None,
+    })
+}
+
+/// The events simulating a left click at the given position
+fn click_events(position: Point) -> Vec<iced_native::Event> {
+    vec![
+        iced_native::Event::Mouse(iced_native::input::mouse::Event::CursorMoved {
+            x: position.x,
+            y: position.y,
+        }),
+        iced_native::Event::Mouse(iced_native::input::mouse::Event::Input {
+            state: iced_native::input::ButtonState::Pressed,
+            button: iced_native::input::mouse::Button::Left,
+        }),
+        iced_native::Event::Mouse(iced_native::input::mouse::Event::Input {
+            state: iced_native::input::ButtonState::Released,
+            button: iced_native::input::mouse::Button::Left,
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::{RunNow, WorldExt};
+    use amethyst::winit::{DeviceId, WindowId};
+    use glyph_brush::GlyphBrushBuilder;
+    use iced_native::input::{keyboard, ButtonState};
+    use iced_native::{layout, Clipboard, Event, Layout, Length, Renderer, Widget};
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        Typed(char),
+        Key(keyboard::KeyCode, ButtonState, bool),
+    }
+
+    /// Sends the characters and the keys it receives, along with whether shift is held
+    struct KeyListener;
+
+    impl<'r> Widget<Message, IcedRenderer<'r>> for KeyListener {
+        fn width(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn height(&self) -> Length {
+            Length::Shrink
+        }
+
+        fn layout(&self, _renderer: &IcedRenderer<'r>, _limits: &layout::Limits) -> layout::Node {
+            layout::Node::new(Size::ZERO)
+        }
+
+        fn draw(
+            &self,
+            _renderer: &mut IcedRenderer<'r>,
+            _defaults: &<IcedRenderer<'r> as Renderer>::Defaults,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+        ) -> <IcedRenderer<'r> as Renderer>::Output {
+            AmethystIcedPrimitive::None
+        }
+
+        fn hash_layout(&self, _state: &mut iced_native::Hasher) {}
+
+        fn on_event(
+            &mut self,
+            event: Event,
+            _layout: Layout<'_>,
+            _cursor_position: Point,
+            messages: &mut Vec<Message>,
+            _renderer: &IcedRenderer<'r>,
+            _clipboard: Option<&dyn Clipboard>,
+        ) {
+            match event {
+                Event::Keyboard(keyboard::Event::CharacterReceived(c)) => {
+                    messages.push(Message::Typed(c))
+                }
+                Event::Keyboard(keyboard::Event::Input {
+                    state,
+                    key_code,
+                    modifiers,
+                }) => messages.push(Message::Key(key_code, state, modifiers.shift)),
+                _ => {}
+            }
+        }
+    }
+
+    struct Listener;
+
+    impl Sandbox for Listener {
+        type UIMessage = Message;
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<Message> {
+            Element::new(KeyListener)
+        }
+    }
+
+    fn window_event(event: WinitWindowEvent) -> WinitEvent {
+        WinitEvent::WindowEvent {
+            // Safe as long as the ids aren't given to winit
+            window_id: unsafe { WindowId::dummy() },
+            event,
+        }
+    }
+
+    fn key(key: VirtualKeyCode, state: ElementState, shift: bool) -> WinitEvent {
+        window_event(WinitWindowEvent::KeyboardInput {
+            device_id: unsafe { DeviceId::dummy() },
+            input: KeyboardInput {
+                scancode: 0,
+                state,
+                virtual_keycode: Some(key),
+                modifiers: ModifiersState {
+                    shift,
+                    ..ModifiersState::default()
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn winit_keys_and_characters_reach_the_widgets() {
+        let mut world = World::new();
+        world.insert(AssetStorage::<SpriteSheet>::default());
+        world.insert(ScreenDimensions::new(800, 600, 1.));
+        world.insert(SandboxContainer::new(Listener));
+        let square_ttf: &[u8] = include_bytes!("../../font/square.ttf");
+        world.insert::<IcedGlyphBrush>(GlyphBrushBuilder::using_font_bytes(square_ttf).build());
+        let mut system = IcedDrawSystem::<Listener>::default();
+        RunNow::setup(&mut system, &mut world);
+        let mut reader = world
+            .write_resource::<EventChannel<Message>>()
+            .register_reader();
+
+        world
+            .write_resource::<EventChannel<WinitEvent>>()
+            .iter_write(vec![
+                key(VirtualKeyCode::Escape, ElementState::Pressed, false),
+                key(VirtualKeyCode::A, ElementState::Pressed, true),
+                window_event(WinitWindowEvent::ReceivedCharacter('A')),
+                key(VirtualKeyCode::Back, ElementState::Released, false),
+                // Keys the widgets don't know about are left out
+                key(VirtualKeyCode::Sleep, ElementState::Pressed, false),
+            ]);
+        system.run_now(&world);
+
+        let messages = world
+            .read_resource::<EventChannel<Message>>()
+            .read(&mut reader)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                Message::Key(keyboard::KeyCode::Escape, ButtonState::Pressed, false),
+                Message::Key(keyboard::KeyCode::A, ButtonState::Pressed, true),
+                Message::Typed('A'),
+                Message::Key(keyboard::KeyCode::Backspace, ButtonState::Released, false),
+            ]
+        );
+    }
+
+    #[derive(Default)]
+    struct Dialog {
+        confirm: iced_native::button::State,
+    }
+
+    impl Sandbox for Dialog {
+        type UIMessage = Message;
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<Message> {
+            let confirm = crate::widget::Button::new(&mut self.confirm, crate::Text::new("Ok"))
+                .on_press(Message::Typed('y'));
+            crate::Modal::new(crate::Text::new("Game"), confirm)
+                .visible(true)
+                .into()
+        }
+    }
+
+    #[test]
+    fn the_focus_ring_is_drawn_in_the_overlay_of_the_focused_widget() {
+        let mut world = World::new();
+        world.insert(AssetStorage::<SpriteSheet>::default());
+        world.insert(ScreenDimensions::new(800, 600, 1.));
+        world.insert(SandboxContainer::new(Dialog::default()));
+        let square_ttf: &[u8] = include_bytes!("../../font/square.ttf");
+        world.insert::<IcedGlyphBrush>(GlyphBrushBuilder::using_font_bytes(square_ttf).build());
+        let mut system = IcedDrawSystem::<Dialog>::default();
+        RunNow::setup(&mut system, &mut world);
+
+        system.run_now(&world);
+        let focused = world
+            .write_resource::<FocusManager>()
+            .navigate(crate::FocusDirection::Down);
+        assert!(focused.is_some());
+        system.run_now(&world);
+
+        let primitives = world.write_resource::<IcedPrimitives>().0.take();
+        match primitives {
+            Some(AmethystIcedPrimitive::Group(primitives)) => match primitives.last() {
+                Some(AmethystIcedPrimitive::Overlay(ring)) => {
+                    if let AmethystIcedPrimitive::Overlay(_) = **ring {
+                        panic!("The ring is drawn above the overlay of the dialog")
+                    }
+                }
+                _ => panic!("The ring isn't drawn in the overlay of the dialog"),
+            },
+            _ => panic!("No focus ring was drawn"),
+        }
+    }
+}
@@ -12,7 +12,9 @@ use amethyst::renderer::{
 };
 use glyph_brush::{BrushAction, BrushError};
 
-use crate::vertex::TextVertex;
+use std::ops::Range;
+
+use crate::vertex::QuadInstance;
 use crate::IcedGlyphBrush;
 
 pub struct IcedDrawGlyphSystem<B: Backend> {
@@ -30,8 +32,16 @@ impl<B: Backend> Default for IcedDrawGlyphSystem<B> {
 #[derive(Default)]
 pub struct GlyphAtlas(pub Option<Handle<Texture>>);
 
+/// The glyphs to draw, sorted by layer
 #[derive(Default)]
-pub struct TextVertexContainer(pub Vec<TextVertex>);
+pub struct GlyphInstances {
+    pub instances: Vec<QuadInstance>,
+    /// The range of instances of every layer, see `AmethystIcedPrimitive::Overlay`
+    pub layers: Vec<Range<u32>>,
+    /// Changes whenever the glyphs are laid out again, so that the pass only
+    /// uploads them when they changed
+    pub generation: u64,
+}
 
 impl<'a, B: Backend> System<'a> for IcedDrawGlyphSystem<B> {
     type SystemData = (
@@ -40,7 +50,7 @@ impl<'a, B: Backend> System<'a> for IcedDrawGlyphSystem<B> {
         WriteExpect<'a, Factory<B>>,
         Option<Read<'a, QueueId>>,
         Write<'a, GlyphAtlas>,
-        Write<'a, TextVertexContainer>,
+        Write<'a, GlyphInstances>,
     );
 
     fn run(
@@ -51,7 +61,7 @@ impl<'a, B: Backend> System<'a> for IcedDrawGlyphSystem<B> {
             mut factory,
             queue,
             mut glyph_atlas,
-            mut text_vertex_container,
+            mut glyph_instances,
         ): Self::SystemData,
     ) {
         if queue.is_none() {
@@ -64,104 +74,90 @@ impl<'a, B: Backend> System<'a> for IcedDrawGlyphSystem<B> {
             asset_textures.insert(create_glyph_texture(&mut *factory, queue, w, h))
         });
 
-        let glyph_tex = asset_textures
-            .get(glyph_atlas)
-            .and_then(B::unwrap_texture)
-            .unwrap();
-        let action = iced_glyph_brush.process_queued(
-            |rect, data| unsafe {
-                factory
-                    .upload_image(
-                        glyph_tex.image().clone(),
-                        rect.width(),
-                        rect.height(),
-                        hal::image::SubresourceLayers {
-                            aspects: hal::format::Aspects::COLOR,
-                            level: 0,
-                            layers: 0..1,
-                        },
-                        hal::image::Offset {
-                            x: rect.min.x as _,
-                            y: rect.min.y as _,
-                            z: 0,
-                        },
-                        hal::image::Extent {
-                            width: rect.width(),
-                            height: rect.height(),
-                            depth: 1,
-                        },
-                        data,
-                        ImageState {
-                            queue,
-                            stage: hal::pso::PipelineStage::FRAGMENT_SHADER,
-                            access: hal::image::Access::SHADER_READ,
-                            layout: hal::image::Layout::General,
-                        },
-                        ImageState {
-                            queue,
-                            stage: hal::pso::PipelineStage::FRAGMENT_SHADER,
-                            access: hal::image::Access::SHADER_READ,
-                            layout: hal::image::Layout::General,
-                        },
+        // The queue is kept when the texture is too small, so the texts are
+        // processed again once it is resized
+        loop {
+            let glyph_tex = asset_textures
+                .get(glyph_atlas)
+                .and_then(B::unwrap_texture)
+                .unwrap();
+            let action = iced_glyph_brush.process_queued(
+                |rect, data| unsafe {
+                    factory
+                        .upload_image(
+                            glyph_tex.image().clone(),
+                            rect.width(),
+                            rect.height(),
+                            hal::image::SubresourceLayers {
+                                aspects: hal::format::Aspects::COLOR,
+                                level: 0,
+                                layers: 0..1,
+                            },
+                            hal::image::Offset {
+                                x: rect.min.x as _,
+                                y: rect.min.y as _,
+                                z: 0,
+                            },
+                            hal::image::Extent {
+                                width: rect.width(),
+                                height: rect.height(),
+                                depth: 1,
+                            },
+                            data,
+                            ImageState {
+                                queue,
+                                stage: hal::pso::PipelineStage::FRAGMENT_SHADER,
+                                access: hal::image::Access::SHADER_READ,
+                                layout: hal::image::Layout::General,
+                            },
+                            ImageState {
+                                queue,
+                                stage: hal::pso::PipelineStage::FRAGMENT_SHADER,
+                                access: hal::image::Access::SHADER_READ,
+                                layout: hal::image::Layout::General,
+                            },
+                        )
+                        .unwrap();
+                },
+                |glyph| {
+                    // TODO: dont display glyph if out of screen bounds
+                    (
+                        glyph.z.to_bits(),
+                        QuadInstance::glyph(glyph.pixel_coords, glyph.tex_coords, glyph.color),
                     )
-                    .unwrap();
-            },
-            |glyph| {
-                // TODO: dont display glyph if out of screen bounds
-
-                let uvs = glyph.tex_coords;
-                //let pos = glyph.pixel_coords;
-                let pos = glyph.pixel_coords;
-                let color: [f32; 4] = glyph.color;
-
-                (
-                    glyph.z.to_bits(),
-                    vec![
-                        TextVertex {
-                            position: [pos.min.x as f32, pos.min.y as f32].into(),
-                            uv: [uvs.min.x, uvs.min.y].into(),
-                            color: color.into(),
-                        },
-                        TextVertex {
-                            position: [pos.max.x as f32, pos.min.y as f32].into(),
-                            uv: [uvs.max.x, uvs.min.y].into(),
-                            color: color.into(),
-                        },
-                        TextVertex {
-                            position: [pos.max.x as f32, pos.max.y as f32].into(),
-                            uv: [uvs.max.x, uvs.max.y].into(),
-                            color: color.into(),
-                        },
-                        TextVertex {
-                            position: [pos.min.x as f32, pos.min.y as f32].into(),
-                            uv: [uvs.min.x, uvs.min.y].into(),
-                            color: color.into(),
-                        },
-                        TextVertex {
-                            position: [pos.min.x as f32, pos.max.y as f32].into(),
-                            uv: [uvs.min.x, uvs.max.y].into(),
-                            color: color.into(),
-                        },
-                        TextVertex {
-                            position: [pos.max.x as f32, pos.max.y as f32].into(),
-                            uv: [uvs.max.x, uvs.max.y].into(),
-                            color: color.into(),
-                        },
-                    ],
-                )
-            },
-        );
-        match action {
-            Ok(BrushAction::Draw(vertices)) => {
-                text_vertex_container.0 = vertices
-                    .into_iter()
-                    .flat_map(|(_id, verts)| verts.into_iter())
-                    .collect();
-            }
-            Err(BrushError::TextureTooSmall { suggested }) => {
-                println!("brusherror. Suggest {:?}", suggested);
+                },
+            );
+            match action {
+                Ok(BrushAction::Draw(mut glyphs)) => {
+                    // Glyphs are tagged with the z of their section, which holds their layer
+                    glyphs.sort_by_key(|(z, _)| f32::from_bits(*z) as usize);
+                    let container = &mut *glyph_instances;
+                    container.instances.clear();
+                    container.layers.clear();
+                    for (z, instance) in glyphs {
+                        let layer = f32::from_bits(z) as usize;
+                        while container.layers.len() <= layer {
+                            let start = container.instances.len() as u32;
+                            container.layers.push(start..start);
+                        }
+                        container.instances.push(instance);
+                        container.layers[layer].end = container.instances.len() as u32;
+                    }
+                    container.generation = container.generation.wrapping_add(1);
+                    return;
+                }
+                Ok(BrushAction::ReDraw) => return,
+                Err(BrushError::TextureTooSmall { suggested: (w, h) }) => {
+                    log::warn!(
+                        "The glyph texture is too small, resizing it to {}x{}.",
+                        w,
+                        h
+                    );
+                    iced_glyph_brush.resize_texture(w, h);
+                    *glyph_atlas =
+                        asset_textures.insert(create_glyph_texture(&mut *factory, queue, w, h));
+                }
             }
-            _ => {}
         }
     }
 }
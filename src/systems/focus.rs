@@ -0,0 +1,82 @@
+use amethyst::ecs::{Read, System, Write};
+use amethyst::input::{BindingTypes, InputHandler};
+use amethyst::shrev::EventChannel;
+
+use crate::resources::{FocusDirection, FocusEvent, FocusManager};
+
+/// The input actions driving the focus.
+///
+/// Gamepads can drive the focus as well by binding their
+/// d-pad & face buttons to these actions.
+#[derive(Debug, Clone)]
+pub struct FocusActions<T: BindingTypes> {
+    pub up: T::Action,
+    pub down: T::Action,
+    pub left: T::Action,
+    pub right: T::Action,
+    pub confirm: T::Action,
+    pub cancel: T::Action,
+}
+
+/// Moves the focus & activates the focused widget from the InputHandler's actions.
+///
+/// Actions are edge-triggered: holding a direction only moves the focus once.
+pub struct IcedFocusSystem<T: BindingTypes> {
+    actions: FocusActions<T>,
+    previously_down: [bool; 6],
+}
+
+impl<T: BindingTypes> IcedFocusSystem<T> {
+    pub fn new(actions: FocusActions<T>) -> Self {
+        IcedFocusSystem {
+            actions,
+            previously_down: [false; 6],
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for IcedFocusSystem<T> {
+    type SystemData = (
+        Read<'a, InputHandler<T>>,
+        Write<'a, FocusManager>,
+        Write<'a, EventChannel<FocusEvent>>,
+    );
+
+    fn run(&mut self, (input, mut focus, mut focus_events): Self::SystemData) {
+        let actions = [
+            &self.actions.up,
+            &self.actions.down,
+            &self.actions.left,
+            &self.actions.right,
+            &self.actions.confirm,
+            &self.actions.cancel,
+        ];
+        let mut pressed = [false; 6];
+        for (i, action) in actions.iter().enumerate() {
+            let down = input.action_is_down(action).unwrap_or(false);
+            pressed[i] = down && !self.previously_down[i];
+            self.previously_down[i] = down;
+        }
+
+        let directions = [
+            FocusDirection::Up,
+            FocusDirection::Down,
+            FocusDirection::Left,
+            FocusDirection::Right,
+        ];
+        for (direction, _) in directions.iter().zip(pressed.iter()).filter(|(_, p)| **p) {
+            if let Some(bounds) = focus.navigate(*direction) {
+                focus_events.single_write(FocusEvent::Focused(bounds));
+            }
+        }
+        if pressed[4] {
+            if let Some(bounds) = focus.activate() {
+                focus_events.single_write(FocusEvent::Activated(bounds));
+            }
+        }
+        if pressed[5] {
+            focus.clear();
+            focus_events.single_write(FocusEvent::Cancelled);
+        }
+    }
+}
@@ -1,12 +1,23 @@
 use crate::sandbox::{Sandbox, SandboxContainer};
-use amethyst::ecs::{Read, System, SystemData, World, Write};
+use crate::subscription::Subscriptions;
+use amethyst::core::Time;
+use amethyst::ecs::{Read, RunNow, SystemData, World, Write};
 use amethyst::shrev::{EventChannel, ReaderId};
 
+type InteropSystemData<'a, S> = (
+    Write<'a, EventChannel<<S as Sandbox>::UIMessage>>,
+    Write<'a, EventChannel<<S as Sandbox>::GameMessage>>,
+);
+
 /// The system in charge of interop between the Iced Sandbox, and the World.
 /// Reads UIMessages and sends back GameMessages to other systems.
+///
+/// It also drives the subscriptions of the Sandbox, which is why it runs
+/// on the main thread: subscriptions can read any EventChannel of the World.
 pub(crate) struct IcedInteropSystem<S: Sandbox> {
     sandbox: std::marker::PhantomData<S>,
     ui_event_reader: Option<ReaderId<S::UIMessage>>,
+    subscriptions: Subscriptions<S::UIMessage>,
 }
 
 impl<S: Sandbox> Default for IcedInteropSystem<S> {
@@ -14,22 +25,25 @@ impl<S: Sandbox> Default for IcedInteropSystem<S> {
         IcedInteropSystem {
             sandbox: std::marker::PhantomData,
             ui_event_reader: None,
+            subscriptions: Subscriptions::default(),
         }
     }
 }
 
-impl<'a, S: Sandbox> System<'a> for IcedInteropSystem<S> {
-    type SystemData = (
-        Read<'a, EventChannel<S::UIMessage>>,
-        Write<'a, EventChannel<S::GameMessage>>,
-        Option<Write<'a, SandboxContainer<S>>>,
-    );
-
-    fn run(&mut self, (ui_messages, mut game_messages, sandbox): Self::SystemData) {
+impl<'a, S: Sandbox> RunNow<'a> for IcedInteropSystem<S> {
+    fn run_now(&mut self, world: &'a World) {
+        let sandbox = Option::<Write<'_, SandboxContainer<S>>>::fetch(world);
         if sandbox.is_none() {
             return;
         }
         let mut sandbox = sandbox.unwrap();
+
+        self.subscriptions.update(sandbox.subscription(), world);
+        let delta = Read::<'_, Time>::fetch(world).delta_real_time();
+        let subscription_messages = self.subscriptions.poll(world, delta);
+
+        let (mut ui_messages, mut game_messages) = InteropSystemData::<S>::fetch(world);
+        ui_messages.iter_write(subscription_messages);
         let reader = self
             .ui_event_reader
             .as_mut()
@@ -41,7 +55,8 @@ impl<'a, S: Sandbox> System<'a> for IcedInteropSystem<S> {
     }
 
     fn setup(&mut self, res: &mut World) {
-        Self::SystemData::setup(res);
+        InteropSystemData::<S>::setup(res);
+        Read::<'_, Time>::setup(res);
         let mut ui_event_channel = Write::<'_, EventChannel<S::UIMessage>>::fetch(res);
         self.ui_event_reader = Some(ui_event_channel.register_reader());
     }
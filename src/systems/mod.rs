@@ -1,9 +1,19 @@
+pub(crate) mod command;
+pub(crate) mod cursor;
 pub(crate) mod draw;
 pub(crate) mod draw_glyphs;
+pub(crate) mod focus;
 pub(crate) mod interop;
 pub(crate) mod load_fonts;
+pub(crate) mod prefab;
+pub(crate) mod touch;
 
+pub(crate) use command::*;
+pub(crate) use cursor::*;
 pub(crate) use draw::*;
 pub(crate) use draw_glyphs::*;
+pub use focus::*;
 pub(crate) use interop::*;
 pub(crate) use load_fonts::*;
+pub use prefab::*;
+pub(crate) use touch::*;
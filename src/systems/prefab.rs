@@ -0,0 +1,35 @@
+use amethyst::assets::AssetStorage;
+use amethyst::ecs::{Read, System, Write};
+
+use crate::prefab::{PrefabSandbox, UiPrefab};
+use crate::sandbox::SandboxContainer;
+
+/// Keeps a PrefabSandbox in sync with its UiPrefab asset, so that the UI is
+/// displayed once loaded and updated whenever the file is hot-reloaded.
+pub struct IcedPrefabSystem<G> {
+    _game_message: std::marker::PhantomData<G>,
+}
+
+impl<G> Default for IcedPrefabSystem<G> {
+    fn default() -> Self {
+        IcedPrefabSystem {
+            _game_message: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, G: Send + Sync + 'static> System<'a> for IcedPrefabSystem<G> {
+    type SystemData = (
+        Read<'a, AssetStorage<UiPrefab>>,
+        Option<Write<'a, SandboxContainer<PrefabSandbox<G>>>>,
+    );
+
+    fn run(&mut self, (prefabs, sandbox): Self::SystemData) {
+        if let Some(mut sandbox) = sandbox {
+            let sandbox = sandbox.inner_mut();
+            if let Some(prefab) = prefabs.get(sandbox.handle()) {
+                sandbox.reload(prefab);
+            }
+        }
+    }
+}
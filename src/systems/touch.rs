@@ -0,0 +1,404 @@
+use amethyst::winit::{Event as WinitEvent, Touch, TouchPhase, WindowEvent as WinitWindowEvent};
+use iced_native::input::{mouse, ButtonState};
+use iced_native::{Event, Point, Rectangle};
+
+use std::time::Instant;
+
+use crate::resources::TouchConfig;
+
+/// Translates the touches of a single finger into mouse events.
+///
+/// Other fingers are ignored while a touch is in progress.
+#[derive(Default)]
+pub(crate) struct TouchState {
+    current: Option<CurrentTouch>,
+    /// The widgets registered as draggable during the last frame
+    draggables: Vec<Rectangle>,
+}
+
+struct CurrentTouch {
+    id: u64,
+    start: Point,
+    position: Point,
+    started_at: Instant,
+    state: TouchGesture,
+}
+
+#[derive(PartialEq)]
+enum TouchGesture {
+    /// The touch hasn't moved further than the drag threshold yet
+    Pending,
+    /// The touch is dragging, with the left button pressed
+    Dragging,
+    /// The touch is scrolling
+    Scrolling,
+    /// A long press was already emitted as a right click
+    LongPressed,
+}
+
+impl TouchState {
+    /// Handles the touch events of winit, whose locations are scaled by the
+    /// hidpi factor. Other events are ignored.
+    pub(crate) fn handle_event(
+        &mut self,
+        event: &WinitEvent,
+        hidpi_factor: f32,
+        now: Instant,
+        config: &TouchConfig,
+    ) -> Vec<Event> {
+        match event {
+            WinitEvent::WindowEvent {
+                event: WinitWindowEvent::Touch(touch),
+                ..
+            } => {
+                let position = Point::new(
+                    touch.location.x as f32 * hidpi_factor,
+                    touch.location.y as f32 * hidpi_factor,
+                );
+                self.handle(touch, position, now, config)
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Replaces the draggable widgets with those registered during the last draw
+    pub(crate) fn set_draggables(&mut self, draggables: Vec<Rectangle>) {
+        self.draggables = draggables;
+    }
+
+    /// Handles a winit touch, whose location has already been scaled to physical pixels
+    pub(crate) fn handle(
+        &mut self,
+        touch: &Touch,
+        position: Point,
+        now: Instant,
+        config: &TouchConfig,
+    ) -> Vec<Event> {
+        match touch.phase {
+            TouchPhase::Started => {
+                if self.current.is_some() {
+                    return vec![];
+                }
+                self.current = Some(CurrentTouch {
+                    id: touch.id,
+                    start: position,
+                    position,
+                    started_at: now,
+                    state: TouchGesture::Pending,
+                });
+                vec![cursor_moved(position)]
+            }
+            TouchPhase::Moved => {
+                let current = match self.current.as_mut() {
+                    Some(current) if current.id == touch.id => current,
+                    _ => return vec![],
+                };
+                let previous = current.position;
+                current.position = position;
+                match current.state {
+                    TouchGesture::Pending => {
+                        let distance = ((position.x - current.start.x).powi(2)
+                            + (position.y - current.start.y).powi(2))
+                        .sqrt();
+                        if distance < config.drag_threshold {
+                            return vec![];
+                        }
+                        let on_draggable = self
+                            .draggables
+                            .iter()
+                            .any(|bounds| bounds.contains(current.start));
+                        if config.drag_scrolls && !on_draggable {
+                            current.state = TouchGesture::Scrolling;
+                            vec![scrolled(current.start, position)]
+                        } else {
+                            // Presses where the touch started, so that the drag
+                            // starts on the widget which was touched
+                            current.state = TouchGesture::Dragging;
+                            vec![
+                                cursor_moved(current.start),
+                                button(mouse::Button::Left, ButtonState::Pressed),
+                                cursor_moved(position),
+                            ]
+                        }
+                    }
+                    TouchGesture::Dragging => vec![cursor_moved(position)],
+                    TouchGesture::Scrolling => vec![scrolled(previous, position)],
+                    TouchGesture::LongPressed => vec![],
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let current = match self.current.take() {
+                    Some(current) if current.id == touch.id => current,
+                    other => {
+                        self.current = other;
+                        return vec![];
+                    }
+                };
+                match current.state {
+                    TouchGesture::Pending if touch.phase == TouchPhase::Ended => vec![
+                        cursor_moved(current.start),
+                        button(mouse::Button::Left, ButtonState::Pressed),
+                        button(mouse::Button::Left, ButtonState::Released),
+                    ],
+                    TouchGesture::Dragging => {
+                        vec![button(mouse::Button::Left, ButtonState::Released)]
+                    }
+                    _ => vec![],
+                }
+            }
+        }
+    }
+
+    /// Emits a right click when the current touch has been held long enough
+    pub(crate) fn poll(&mut self, now: Instant, config: &TouchConfig) -> Vec<Event> {
+        let long_press = match config.long_press {
+            Some(long_press) => long_press,
+            None => return vec![],
+        };
+        match self.current.as_mut() {
+            Some(current)
+                if current.state == TouchGesture::Pending
+                    && now.duration_since(current.started_at) >= long_press =>
+            {
+                current.state = TouchGesture::LongPressed;
+                vec![
+                    cursor_moved(current.start),
+                    button(mouse::Button::Right, ButtonState::Pressed),
+                    button(mouse::Button::Right, ButtonState::Released),
+                ]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+fn cursor_moved(position: Point) -> Event {
+    Event::Mouse(mouse::Event::CursorMoved {
+        x: position.x,
+        y: position.y,
+    })
+}
+
+fn button(button: mouse::Button, state: ButtonState) -> Event {
+    Event::Mouse(mouse::Event::Input { state, button })
+}
+
+fn scrolled(from: Point, to: Point) -> Event {
+    // Content follows the finger, like on mobile platforms
+    Event::Mouse(mouse::Event::WheelScrolled {
+        delta: mouse::ScrollDelta::Pixels {
+            x: to.x - from.x,
+            y: to.y - from.y,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::winit::dpi::LogicalPosition;
+    use amethyst::winit::{DeviceId, WindowId};
+
+    use std::time::Duration;
+
+    fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> WinitEvent {
+        WinitEvent::WindowEvent {
+            // Safe as long as the ids aren't given to winit
+            window_id: unsafe { WindowId::dummy() },
+            event: WinitWindowEvent::Touch(Touch {
+                device_id: unsafe { DeviceId::dummy() },
+                phase,
+                location: LogicalPosition::new(x, y),
+                id,
+            }),
+        }
+    }
+
+    fn press(button: mouse::Button) -> Event {
+        Event::Mouse(mouse::Event::Input {
+            state: ButtonState::Pressed,
+            button,
+        })
+    }
+
+    fn release(button: mouse::Button) -> Event {
+        Event::Mouse(mouse::Event::Input {
+            state: ButtonState::Released,
+            button,
+        })
+    }
+
+    fn moved(x: f32, y: f32) -> Event {
+        cursor_moved(Point::new(x, y))
+    }
+
+    struct Fixture {
+        state: TouchState,
+        config: TouchConfig,
+        start: Instant,
+    }
+
+    impl Fixture {
+        fn new(config: TouchConfig) -> Self {
+            Fixture {
+                state: TouchState::default(),
+                config,
+                start: Instant::now(),
+            }
+        }
+
+        fn send(&mut self, event: WinitEvent) -> Vec<Event> {
+            self.send_at(event, Duration::from_millis(0))
+        }
+
+        fn send_at(&mut self, event: WinitEvent, elapsed: Duration) -> Vec<Event> {
+            self.state
+                .handle_event(&event, 1., self.start + elapsed, &self.config)
+        }
+
+        fn poll_at(&mut self, elapsed: Duration) -> Vec<Event> {
+            self.state.poll(self.start + elapsed, &self.config)
+        }
+    }
+
+    #[test]
+    fn tap_is_a_left_click() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Started, 10., 20.)),
+            vec![moved(10., 20.)]
+        );
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Ended, 10., 20.)),
+            vec![
+                moved(10., 20.),
+                press(mouse::Button::Left),
+                release(mouse::Button::Left)
+            ]
+        );
+    }
+
+    #[test]
+    fn hidpi_factor_scales_touches() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        let events = touches.state.handle_event(
+            &touch(0, TouchPhase::Started, 10., 20.),
+            2.,
+            touches.start,
+            &touches.config,
+        );
+        assert_eq!(events, vec![moved(20., 40.)]);
+    }
+
+    #[test]
+    fn small_movements_are_still_a_tap() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(touches.send(touch(0, TouchPhase::Moved, 15., 13.)), vec![]);
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Ended, 15., 13.)),
+            vec![
+                moved(10., 10.),
+                press(mouse::Button::Left),
+                release(mouse::Button::Left)
+            ]
+        );
+    }
+
+    #[test]
+    fn drags_scroll_outside_of_draggables() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Moved, 10., 30.)),
+            vec![scrolled(Point::new(10., 10.), Point::new(10., 30.))]
+        );
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Moved, 10., 35.)),
+            vec![scrolled(Point::new(10., 30.), Point::new(10., 35.))]
+        );
+        assert_eq!(touches.send(touch(0, TouchPhase::Ended, 10., 35.)), vec![]);
+    }
+
+    #[test]
+    fn drags_press_on_draggables() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        touches.state.set_draggables(vec![Rectangle {
+            x: 0.,
+            y: 0.,
+            width: 100.,
+            height: 20.,
+        }]);
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Moved, 30., 10.)),
+            vec![moved(10., 10.), press(mouse::Button::Left), moved(30., 10.)]
+        );
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Moved, 40., 10.)),
+            vec![moved(40., 10.)]
+        );
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Ended, 40., 10.)),
+            vec![release(mouse::Button::Left)]
+        );
+    }
+
+    #[test]
+    fn drags_press_everywhere_without_drag_scrolls() {
+        let mut touches = Fixture::new(TouchConfig {
+            drag_scrolls: false,
+            ..TouchConfig::default()
+        });
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Moved, 10., 30.)),
+            vec![moved(10., 10.), press(mouse::Button::Left), moved(10., 30.)]
+        );
+    }
+
+    #[test]
+    fn long_press_is_a_right_click() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(touches.poll_at(Duration::from_millis(100)), vec![]);
+        assert_eq!(
+            touches.poll_at(Duration::from_millis(500)),
+            vec![
+                moved(10., 10.),
+                press(mouse::Button::Right),
+                release(mouse::Button::Right)
+            ]
+        );
+        // Only once, and without a left click when released
+        assert_eq!(touches.poll_at(Duration::from_millis(1000)), vec![]);
+        assert_eq!(
+            touches.send_at(
+                touch(0, TouchPhase::Ended, 10., 10.),
+                Duration::from_millis(1000)
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn second_finger_is_ignored() {
+        let mut touches = Fixture::new(TouchConfig::default());
+        touches.send(touch(0, TouchPhase::Started, 10., 10.));
+        assert_eq!(
+            touches.send(touch(1, TouchPhase::Started, 50., 50.)),
+            vec![]
+        );
+        assert_eq!(touches.send(touch(1, TouchPhase::Moved, 90., 90.)), vec![]);
+        assert_eq!(touches.send(touch(1, TouchPhase::Ended, 90., 90.)), vec![]);
+        // The first finger is still tracked
+        assert_eq!(
+            touches.send(touch(0, TouchPhase::Ended, 10., 10.)),
+            vec![
+                moved(10., 10.),
+                press(mouse::Button::Left),
+                release(mouse::Button::Left)
+            ]
+        );
+    }
+}
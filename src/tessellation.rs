@@ -0,0 +1,716 @@
+//! CPU-side helpers turning shapes into triangle lists for the TrianglePipeline
+
+use glsl_layout::vec4;
+use iced_native::{Color, Rectangle};
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Path, PathEvent};
+use lyon::tessellation::{
+    BuffersBuilder, FillAttributes, FillOptions, FillTessellator, FillVertexConstructor,
+    StrokeAttributes, StrokeOptions, StrokeTessellator, StrokeVertexConstructor, VertexBuffers,
+};
+
+use crate::style::{ColorStop, Gradient};
+use crate::vertex::TriangleVertex;
+
+/// Number of segments used to approximate each rounded corner
+const CORNER_SEGMENTS: usize = 8;
+
+/// Width of the band over which antialiased edges fade out, centered on the edges.
+///
+/// A single pixel keeps the edges which are aligned with the pixel grid sharp.
+pub(crate) const FEATHER: f32 = 1.;
+
+/// Maximum distance between curves and the segments approximating them
+const TOLERANCE: f32 = 0.1;
+
+/// Tessellates a rectangle in two triangles
+pub(crate) fn quad(bounds: Rectangle, color: Color, vertices: &mut Vec<TriangleVertex>) {
+    let color: vec4 = [color.r, color.g, color.b, color.a].into();
+    vertices.extend_from_slice(&[
+        TriangleVertex {
+            position: [bounds.x, bounds.y].into(),
+            color,
+        },
+        TriangleVertex {
+            position: [bounds.x + bounds.width, bounds.y].into(),
+            color,
+        },
+        TriangleVertex {
+            position: [bounds.x + bounds.width, bounds.y + bounds.height].into(),
+            color,
+        },
+        TriangleVertex {
+            position: [bounds.x, bounds.y].into(),
+            color,
+        },
+        TriangleVertex {
+            position: [bounds.x, bounds.y + bounds.height].into(),
+            color,
+        },
+        TriangleVertex {
+            position: [bounds.x + bounds.width, bounds.y + bounds.height].into(),
+            color,
+        },
+    ]);
+}
+
+/// Returns the clockwise contour of a rectangle with rounded corners.
+///
+/// Every corner is made of the same amount of points, even when the radius
+/// is zero, so that two contours of the same shape can be stitched together.
+pub(crate) fn rounded_rect_contour(bounds: Rectangle, radius: f32) -> Vec<[f32; 2]> {
+    let radius = radius
+        .max(0.)
+        .min(bounds.width / 2.)
+        .min(bounds.height / 2.);
+    let corners = [
+        // (center of the corner arc, starting angle)
+        (
+            [bounds.x + bounds.width - radius, bounds.y + radius],
+            -std::f32::consts::FRAC_PI_2,
+        ),
+        (
+            [bounds.x + bounds.width - radius, bounds.y + bounds.height - radius],
+            0.,
+        ),
+        (
+            [bounds.x + radius, bounds.y + bounds.height - radius],
+            std::f32::consts::FRAC_PI_2,
+        ),
+        ([bounds.x + radius, bounds.y + radius], std::f32::consts::PI),
+    ];
+
+    let mut contour = Vec::with_capacity(4 * (CORNER_SEGMENTS + 1));
+    for (center, start) in corners.iter() {
+        for i in 0..=CORNER_SEGMENTS {
+            let angle = start + std::f32::consts::FRAC_PI_2 * i as f32 / CORNER_SEGMENTS as f32;
+            contour.push([
+                center[0] + radius * angle.cos(),
+                center[1] + radius * angle.sin(),
+            ]);
+        }
+    }
+    contour
+}
+
+/// Fills a convex contour with a single color, using a triangle fan
+pub(crate) fn fill_convex(contour: &[[f32; 2]], color: vec4, vertices: &mut Vec<TriangleVertex>) {
+    if contour.len() < 3 {
+        return;
+    }
+    let origin = contour[0];
+    contour[1..].windows(2).for_each(|edge| {
+        vertices.extend_from_slice(&[
+            TriangleVertex {
+                position: origin.into(),
+                color,
+            },
+            TriangleVertex {
+                position: edge[0].into(),
+                color,
+            },
+            TriangleVertex {
+                position: edge[1].into(),
+                color,
+            },
+        ]);
+    });
+}
+
+/// Fills the area between two contours having the same amount of points
+pub(crate) fn fill_ring(
+    outer: &[[f32; 2]],
+    inner: &[[f32; 2]],
+    color: vec4,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    fill_strip(outer, inner, color, color, true, vertices);
+}
+
+/// Fills the area between two lines having the same amount of points, blending
+/// from the color of the first line to the color of the second one
+fn fill_strip(
+    a: &[[f32; 2]],
+    b: &[[f32; 2]],
+    color_a: vec4,
+    color_b: vec4,
+    closed: bool,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    debug_assert_eq!(a.len(), b.len());
+    let len = a.len().min(b.len());
+    let segments = if closed { len } else { len.saturating_sub(1) };
+    for i in 0..segments {
+        let next = (i + 1) % len;
+        vertices.extend_from_slice(&[
+            TriangleVertex {
+                position: a[i].into(),
+                color: color_a,
+            },
+            TriangleVertex {
+                position: a[next].into(),
+                color: color_a,
+            },
+            TriangleVertex {
+                position: b[i].into(),
+                color: color_b,
+            },
+            TriangleVertex {
+                position: b[i].into(),
+                color: color_b,
+            },
+            TriangleVertex {
+                position: a[next].into(),
+                color: color_a,
+            },
+            TriangleVertex {
+                position: b[next].into(),
+                color: color_b,
+            },
+        ]);
+    }
+}
+
+/// Draws the band fading out the edge of a shape, from `inside` (opaque) to `outside`
+fn fill_fringe(
+    inside: &[[f32; 2]],
+    outside: &[[f32; 2]],
+    color: vec4,
+    closed: bool,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let [r, g, b, _] = *AsRef::<[f32; 4]>::as_ref(&color);
+    fill_strip(inside, outside, color, [r, g, b, 0.].into(), closed, vertices);
+}
+
+/// Moves every point of a line by the given distance, to the left of the direction
+/// of travel on screen, i.e. outwards for clockwise contours.
+///
+/// Corners are moved along the bisector of their edges, so that the offset edges
+/// stay parallel to the original ones. Repeated points are moved like their neighbours.
+fn offset_line(points: &[[f32; 2]], closed: bool, distance: f32) -> Vec<[f32; 2]> {
+    let len = points.len();
+    let distinct = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() + (a[1] - b[1]).abs() > 1e-4;
+    let normal = |from: [f32; 2], to: [f32; 2]| {
+        let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+        let length = (dx * dx + dy * dy).sqrt();
+        [dy / length, -dx / length]
+    };
+    // Finds the closest point distinct from points[i], looking backward or forward
+    let neighbour = |i: usize, step: isize| {
+        let mut j = i as isize;
+        for _ in 1..len {
+            j += step;
+            if !closed && (j < 0 || j >= len as isize) {
+                return None;
+            }
+            let point = points[j.rem_euclid(len as isize) as usize];
+            if distinct(point, points[i]) {
+                return Some(point);
+            }
+        }
+        None
+    };
+
+    (0..len)
+        .map(|i| {
+            let point = points[i];
+            let direction = match (neighbour(i, -1), neighbour(i, 1)) {
+                (Some(previous), Some(next)) => {
+                    let (a, b) = (normal(previous, point), normal(point, next));
+                    let sum = [a[0] + b[0], a[1] + b[1]];
+                    let length = (sum[0] * sum[0] + sum[1] * sum[1]).sqrt();
+                    if length < 1e-4 {
+                        a
+                    } else {
+                        let bisector = [sum[0] / length, sum[1] / length];
+                        // Lengthens the offset in corners, up to a limit for sharp ones
+                        let scale = 1. / (bisector[0] * a[0] + bisector[1] * a[1]).max(0.25);
+                        [bisector[0] * scale, bisector[1] * scale]
+                    }
+                }
+                (Some(previous), None) => normal(previous, point),
+                (None, Some(next)) => normal(point, next),
+                (None, None) => [0., 0.],
+            };
+            [point[0] + direction[0] * distance, point[1] + direction[1] * distance]
+        })
+        .collect()
+}
+
+/// Fills a convex clockwise contour, fading its edges out over FEATHER pixels
+pub(crate) fn fill_convex_antialiased(
+    contour: &[[f32; 2]],
+    color: vec4,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let inside = offset_line(contour, true, -FEATHER / 2.);
+    fill_convex(&inside, color, vertices);
+    fill_fringe(&inside, &offset_line(contour, true, FEATHER / 2.), color, true, vertices);
+}
+
+/// Tessellates a rectangle with rounded corners and an optional border.
+///
+/// The curved edges are antialiased, see `FEATHER`.
+pub(crate) fn rounded_quad(
+    bounds: Rectangle,
+    background: Option<Color>,
+    border_radius: f32,
+    border_width: f32,
+    border_color: Color,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let outer = rounded_rect_contour(bounds, border_radius);
+    let half = FEATHER / 2.;
+    if border_width <= 0. {
+        if let Some(background) = background {
+            let color: vec4 = [background.r, background.g, background.b, background.a].into();
+            fill_convex_antialiased(&outer, color, vertices);
+        }
+        return;
+    }
+
+    let inner = rounded_rect_contour(
+        shrink(bounds, border_width),
+        (border_radius - border_width).max(0.),
+    );
+    // The background goes under the fringe of the border, so that they blend without seams
+    let inner_edge = offset_line(&inner, true, half);
+    if let Some(background) = background {
+        let color: vec4 = [background.r, background.g, background.b, background.a].into();
+        fill_convex(&inner_edge, color, vertices);
+    }
+    let color: vec4 = [border_color.r, border_color.g, border_color.b, border_color.a].into();
+    let outer_edge = offset_line(&outer, true, -half);
+    fill_ring(&outer_edge, &inner_edge, color, vertices);
+    fill_fringe(&outer_edge, &offset_line(&outer, true, half), color, true, vertices);
+    fill_fringe(&inner_edge, &offset_line(&inner, true, -half), color, true, vertices);
+}
+
+/// Number of wedges approximating the circles of radial gradients
+const RADIAL_SEGMENTS: usize = 64;
+
+/// The color of the gradient at the given offset, the stops being sorted
+fn gradient_color(stops: &[ColorStop], offset: f32) -> vec4 {
+    let color = match stops.iter().position(|stop| stop.offset > offset) {
+        None => stops[stops.len() - 1].color,
+        Some(0) => stops[0].color,
+        Some(next) => {
+            let (a, b) = (stops[next - 1], stops[next]);
+            let t = (offset - a.offset) / (b.offset - a.offset);
+            let mut color = [0.; 4];
+            for i in 0..4 {
+                color[i] = a.color[i] + (b.color[i] - a.color[i]) * t;
+            }
+            color
+        }
+    };
+    color.into()
+}
+
+/// Where a point lies on a gradient going from `start` (offset 0) to `end` (offset 1)
+fn linear_offset(start: [f32; 2], end: [f32; 2], point: [f32; 2]) -> f32 {
+    let direction = [end[0] - start[0], end[1] - start[1]];
+    let length = direction[0] * direction[0] + direction[1] * direction[1];
+    if length <= 0. {
+        return 0.;
+    }
+    ((point[0] - start[0]) * direction[0] + (point[1] - start[1]) * direction[1]) / length
+}
+
+/// Keeps the part of a convex polygon where `dot(point, normal) >= distance`
+fn clip(polygon: &[[f32; 2]], normal: [f32; 2], distance: f32) -> Vec<[f32; 2]> {
+    let side = |point: [f32; 2]| point[0] * normal[0] + point[1] * normal[1] - distance;
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (side_a, side_b) = (side(a), side(b));
+        if side_a >= 0. {
+            clipped.push(a);
+        }
+        if (side_a >= 0.) != (side_b >= 0.) {
+            let t = side_a / (side_a - side_b);
+            clipped.push([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]);
+        }
+    }
+    clipped
+}
+
+/// Fills a convex polygon with a linear gradient.
+///
+/// The polygon is cut between every pair of stops, so that blending the colors
+/// of the vertices gives the exact gradient.
+fn fill_linear_gradient(
+    polygon: &[[f32; 2]],
+    start: [f32; 2],
+    end: [f32; 2],
+    stops: &[ColorStop],
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let direction = [end[0] - start[0], end[1] - start[1]];
+    let length = direction[0] * direction[0] + direction[1] * direction[1];
+    if length <= 0. {
+        fill_convex(polygon, gradient_color(stops, 0.), vertices);
+        return;
+    }
+    // Distance of the line where the gradient reaches the offset, along its direction
+    let offset_plane = |offset: f32| {
+        start[0] * direction[0] + start[1] * direction[1] + offset * length
+    };
+
+    let bounds = std::iter::once(std::f32::NEG_INFINITY)
+        .chain(stops.iter().map(|stop| stop.offset))
+        .chain(std::iter::once(std::f32::INFINITY))
+        .collect::<Vec<_>>();
+    for band in bounds.windows(2) {
+        let mut part = polygon.to_vec();
+        if band[0].is_finite() {
+            part = clip(&part, direction, offset_plane(band[0]));
+        }
+        if band[1].is_finite() {
+            part = clip(
+                &part,
+                [-direction[0], -direction[1]],
+                -offset_plane(band[1]),
+            );
+        }
+        if part.len() < 3 {
+            continue;
+        }
+        let origin = part[0];
+        part[1..].windows(2).for_each(|edge| {
+            for point in [origin, edge[0], edge[1]].iter() {
+                vertices.push(TriangleVertex {
+                    position: (*point).into(),
+                    color: gradient_color(stops, linear_offset(start, end, *point)),
+                });
+            }
+        });
+    }
+}
+
+/// Fills a convex polygon with a radial gradient.
+///
+/// Around the center, the polygon is cut in thin wedges, in which the gradient
+/// is linear along the middle of the wedge.
+fn fill_radial_gradient(
+    polygon: &[[f32; 2]],
+    center: [f32; 2],
+    radius: f32,
+    stops: &[ColorStop],
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    if radius <= 0. {
+        fill_convex(polygon, gradient_color(stops, 1.), vertices);
+        return;
+    }
+    let angle = |i: usize| 2. * std::f32::consts::PI * i as f32 / RADIAL_SEGMENTS as f32;
+    // Normal of the ray going from the center at the given angle, pointing counterclockwise
+    let normal = |angle: f32| [-angle.sin(), angle.cos()];
+    let dot = |a: [f32; 2], b: [f32; 2]| a[0] * b[0] + a[1] * b[1];
+    for i in 0..RADIAL_SEGMENTS {
+        let (from, to) = (normal(angle(i)), normal(angle(i + 1)));
+        let wedge = clip(polygon, from, dot(center, from));
+        let wedge = clip(&wedge, [-to[0], -to[1]], -dot(center, to));
+        if wedge.len() < 3 {
+            continue;
+        }
+        let middle = (angle(i) + angle(i + 1)) / 2.;
+        let end = [
+            center[0] + middle.cos() * radius,
+            center[1] + middle.sin() * radius,
+        ];
+        fill_linear_gradient(&wedge, center, end, stops, vertices);
+    }
+}
+
+/// Fills a convex clockwise contour with a gradient, optionally fading its edges out
+pub(crate) fn fill_gradient(
+    contour: &[[f32; 2]],
+    gradient: &Gradient,
+    antialiased: bool,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let stops = match gradient {
+        Gradient::Linear { stops, .. } | Gradient::Radial { stops, .. } => stops,
+    };
+    if stops.is_empty() {
+        return;
+    }
+
+    let inside = if antialiased {
+        offset_line(contour, true, -FEATHER / 2.)
+    } else {
+        contour.to_vec()
+    };
+    match gradient {
+        Gradient::Linear { start, end, stops } => {
+            fill_linear_gradient(&inside, *start, *end, stops, vertices)
+        }
+        Gradient::Radial {
+            center,
+            radius,
+            stops,
+        } => fill_radial_gradient(&inside, *center, *radius, stops, vertices),
+    }
+    if !antialiased {
+        return;
+    }
+
+    let color_at = |point: [f32; 2]| match gradient {
+        Gradient::Linear { start, end, stops } => {
+            gradient_color(stops, linear_offset(*start, *end, point))
+        }
+        Gradient::Radial {
+            center,
+            radius,
+            stops,
+        } => {
+            let (dx, dy) = (point[0] - center[0], point[1] - center[1]);
+            gradient_color(stops, (dx * dx + dy * dy).sqrt() / radius.max(std::f32::EPSILON))
+        }
+    };
+    let faded = |point: [f32; 2]| {
+        let [r, g, b, _] = *AsRef::<[f32; 4]>::as_ref(&color_at(point));
+        [r, g, b, 0.].into()
+    };
+    let outside = offset_line(contour, true, FEATHER / 2.);
+    for i in 0..inside.len() {
+        let next = (i + 1) % inside.len();
+        vertices.extend_from_slice(&[
+            TriangleVertex {
+                position: inside[i].into(),
+                color: color_at(inside[i]),
+            },
+            TriangleVertex {
+                position: inside[next].into(),
+                color: color_at(inside[next]),
+            },
+            TriangleVertex {
+                position: outside[i].into(),
+                color: faded(outside[i]),
+            },
+            TriangleVertex {
+                position: outside[i].into(),
+                color: faded(outside[i]),
+            },
+            TriangleVertex {
+                position: inside[next].into(),
+                color: color_at(inside[next]),
+            },
+            TriangleVertex {
+                position: outside[next].into(),
+                color: faded(outside[next]),
+            },
+        ]);
+    }
+}
+
+/// Tessellates a rectangle filled with a gradient.
+///
+/// Only rounded corners are antialiased, so that plain rectangles stay sharp.
+pub(crate) fn gradient_quad(
+    bounds: Rectangle,
+    gradient: &Gradient,
+    border_radius: f32,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    if border_radius > 0. {
+        fill_gradient(
+            &rounded_rect_contour(bounds, border_radius),
+            gradient,
+            true,
+            vertices,
+        );
+    } else {
+        let contour = [
+            [bounds.x, bounds.y],
+            [bounds.x + bounds.width, bounds.y],
+            [bounds.x + bounds.width, bounds.y + bounds.height],
+            [bounds.x, bounds.y + bounds.height],
+        ];
+        fill_gradient(&contour, gradient, false, vertices);
+    }
+}
+
+/// Shrinks the rectangle by the given amount on every side
+pub(crate) fn shrink(bounds: Rectangle, amount: f32) -> Rectangle {
+    let amount = amount.min(bounds.width / 2.).min(bounds.height / 2.);
+    Rectangle {
+        x: bounds.x + amount,
+        y: bounds.y + amount,
+        width: bounds.width - 2. * amount,
+        height: bounds.height - 2. * amount,
+    }
+}
+
+/// Builds triangle vertices of a single color out of the positions given by lyon
+struct SolidVertex(vec4);
+
+impl FillVertexConstructor<TriangleVertex> for SolidVertex {
+    fn new_vertex(
+        &mut self,
+        position: lyon::math::Point,
+        _attributes: FillAttributes<'_>,
+    ) -> TriangleVertex {
+        TriangleVertex {
+            position: [position.x, position.y].into(),
+            color: self.0,
+        }
+    }
+}
+
+impl StrokeVertexConstructor<TriangleVertex> for SolidVertex {
+    fn new_vertex(
+        &mut self,
+        position: lyon::math::Point,
+        _attributes: StrokeAttributes<'_, '_>,
+    ) -> TriangleVertex {
+        TriangleVertex {
+            position: [position.x, position.y].into(),
+            color: self.0,
+        }
+    }
+}
+
+/// Turns the indexed output of lyon into the triangle list drawn by the TrianglePipeline
+fn unindex(buffers: VertexBuffers<TriangleVertex, u32>, vertices: &mut Vec<TriangleVertex>) {
+    vertices.extend(
+        buffers
+            .indices
+            .iter()
+            .map(|index| buffers.vertices[*index as usize]),
+    );
+}
+
+/// Flattens the sub-paths of a path into lines, telling whether each one is closed
+fn flatten(path: &Path) -> Vec<(Vec<[f32; 2]>, bool)> {
+    let mut lines = vec![];
+    let mut line = vec![];
+    for event in path.iter().flattened(TOLERANCE) {
+        match event {
+            PathEvent::Begin { at } => line = vec![[at.x, at.y]],
+            PathEvent::Line { to, .. } => line.push([to.x, to.y]),
+            PathEvent::End { close, .. } => {
+                lines.push((std::mem::replace(&mut line, vec![]), close));
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// Twice the signed area of a closed line, positive when it turns clockwise on screen
+fn signed_area(points: &[[f32; 2]]) -> f32 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum()
+}
+
+/// Fills the inside of a path, following the non-zero rule.
+///
+/// When antialiased, the fill is wrapped in a band fading out over FEATHER pixels.
+pub(crate) fn fill_path(
+    path: &Path,
+    color: [f32; 4],
+    antialiased: bool,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let mut buffers = VertexBuffers::new();
+    let result = FillTessellator::new().tessellate_path(
+        path,
+        &FillOptions::default()
+            .with_fill_rule(lyon::tessellation::FillRule::NonZero)
+            .with_tolerance(TOLERANCE),
+        &mut BuffersBuilder::new(&mut buffers, SolidVertex(color.into())),
+    );
+    match result {
+        Ok(_) => unindex(buffers, vertices),
+        Err(error) => {
+            log::warn!("Could not fill a path: {:?}", error);
+            return;
+        }
+    }
+    if !antialiased {
+        return;
+    }
+
+    let contours = flatten(path);
+    // With the non-zero rule, holes turn the other way: the orientation of the largest
+    // contour tells which side of every edge is outside
+    let outwards = contours
+        .iter()
+        .map(|(contour, _)| signed_area(contour))
+        .fold(0., |largest: f32, area| {
+            if area.abs() > largest.abs() {
+                area
+            } else {
+                largest
+            }
+        })
+        .signum();
+    let half = FEATHER / 2. * outwards;
+    for (contour, _) in contours.iter().filter(|(contour, _)| contour.len() > 2) {
+        fill_fringe(
+            &offset_line(contour, true, -half),
+            &offset_line(contour, true, half),
+            color.into(),
+            true,
+            vertices,
+        );
+    }
+}
+
+/// Draws the outline of a path with the given line width.
+///
+/// When antialiased, both sides of the outline fade out over FEATHER pixels. The
+/// fringe follows the path itself, so it only approximates round joins and caps.
+pub(crate) fn stroke_path(
+    path: &Path,
+    color: [f32; 4],
+    options: &StrokeOptions,
+    antialiased: bool,
+    vertices: &mut Vec<TriangleVertex>,
+) {
+    let width = options.line_width;
+    let mut options = options.with_tolerance(TOLERANCE);
+    let mut color = color;
+    if antialiased {
+        options = options.with_line_width((width - FEATHER).max(0.));
+        // Lines thinner than the fringe are drawn lighter rather than thinner
+        color[3] *= (width / FEATHER).min(1.);
+    }
+
+    if options.line_width > 0. {
+        let mut buffers = VertexBuffers::new();
+        let result = StrokeTessellator::new().tessellate_path(
+            path,
+            &options,
+            &mut BuffersBuilder::new(&mut buffers, SolidVertex(color.into())),
+        );
+        match result {
+            Ok(_) => unindex(buffers, vertices),
+            Err(error) => log::warn!("Could not stroke a path: {:?}", error),
+        }
+    }
+    if !antialiased {
+        return;
+    }
+
+    let inside = options.line_width / 2.;
+    let outside = width.max(FEATHER) / 2. + FEATHER / 2.;
+    for (line, closed) in flatten(path).iter().filter(|(line, _)| line.len() > 1) {
+        for side in [-1., 1.].iter() {
+            fill_fringe(
+                &offset_line(line, *closed, inside * side),
+                &offset_line(line, *closed, outside * side),
+                color.into(),
+                *closed,
+                vertices,
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
-pub(crate) mod image;
-pub(crate) mod text;
+pub(crate) mod quad;
+pub(crate) mod shadow;
 pub(crate) mod triangle;
 
-pub(crate) use image::ImageVertex;
-pub(crate) use text::TextVertex;
+pub(crate) use quad::{QuadCorner, QuadInstance};
+pub(crate) use shadow::ShadowVertex;
 pub(crate) use triangle::TriangleVertex;
@@ -0,0 +1,131 @@
+use amethyst::renderer::rendy::{
+    hal::format::Format,
+    mesh::{AsVertex, VertexFormat},
+};
+use glsl_layout::{vec2, vec4, AsStd140};
+use glyph_brush::rusttype::Rect;
+use iced_native::{Color, Rectangle};
+
+use crate::tessellation::FEATHER;
+
+/// A corner of the unit quad, which every QuadInstance stretches over its rectangle
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, AsStd140)]
+#[repr(C, align(4))]
+pub struct QuadCorner {
+    pub position: vec2,
+}
+
+impl AsVertex for QuadCorner {
+    fn vertex() -> VertexFormat {
+        VertexFormat::new(((Format::Rg32Sfloat, "in_corner"),))
+    }
+}
+
+/// The corners of the unit quad, drawn as a triangle strip
+pub(crate) const UNIT_QUAD: [[f32; 2]; 4] = [[0., 0.], [1., 0.], [0., 1.], [1., 1.]];
+
+/// A rectangle drawn by stretching the unit quad over it: a solid quad with
+/// optionally rounded corners and a border, an image or a glyph.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, AsStd140)]
+#[repr(C, align(4))]
+pub struct QuadInstance {
+    /// The x, y, width & height of the rectangle, before the transform
+    pub rect: vec4,
+    /// The x, y, width & height of the region of the texture drawn over the rectangle
+    pub uv: vec4,
+    /// The background of solid quads, or the tint of textured ones
+    pub color: vec4,
+    pub border_color: vec4,
+    /// The border radius, the border width, the width of the antialiased
+    /// edges and the kind of quad
+    pub params: vec4,
+    /// The x & y axes of the transform
+    pub axes: vec4,
+    pub translation: vec2,
+}
+
+impl AsVertex for QuadInstance {
+    fn vertex() -> VertexFormat {
+        VertexFormat::new((
+            (Format::Rgba32Sfloat, "in_rect"),
+            (Format::Rgba32Sfloat, "in_uv"),
+            (Format::Rgba32Sfloat, "in_color"),
+            (Format::Rgba32Sfloat, "in_border_color"),
+            (Format::Rgba32Sfloat, "in_params"),
+            (Format::Rgba32Sfloat, "in_axes"),
+            (Format::Rg32Sfloat, "in_translation"),
+        ))
+    }
+}
+
+impl QuadInstance {
+    /// Filled with its color, see shaders/src/quad.frag
+    pub const SOLID: f32 = 0.;
+    /// The texture multiplied by the color, see shaders/src/textured.frag
+    pub const IMAGE: f32 = 1.;
+    /// The color, covering the texture where its alpha is, see shaders/src/textured.frag
+    pub const GLYPH: f32 = 2.;
+
+    fn new(bounds: Rectangle, color: [f32; 4], kind: f32) -> Self {
+        QuadInstance {
+            rect: [bounds.x, bounds.y, bounds.width, bounds.height].into(),
+            uv: [0., 0., 1., 1.].into(),
+            color: color.into(),
+            border_color: [0., 0., 0., 0.].into(),
+            params: [0., 0., 0., kind].into(),
+            axes: [1., 0., 0., 1.].into(),
+            translation: [0., 0.].into(),
+        }
+    }
+
+    /// A rectangle filled with a single color, with sharp edges
+    pub fn solid(bounds: Rectangle, color: Color) -> Self {
+        QuadInstance::new(bounds, components(color), QuadInstance::SOLID)
+    }
+
+    /// A rectangle with rounded corners and a border, both optional, with antialiased edges
+    pub fn rounded(
+        bounds: Rectangle,
+        background: Option<Color>,
+        border_radius: f32,
+        border_width: f32,
+        border_color: Color,
+    ) -> Self {
+        let background = background.unwrap_or(Color::TRANSPARENT);
+        let radius = border_radius
+            .max(0.)
+            .min(bounds.width / 2.)
+            .min(bounds.height / 2.);
+        QuadInstance {
+            border_color: components(border_color).into(),
+            params: [radius, border_width.max(0.), FEATHER, QuadInstance::SOLID].into(),
+            ..QuadInstance::new(bounds, components(background), QuadInstance::SOLID)
+        }
+    }
+
+    /// The uv region of a texture drawn over the bounds
+    pub fn image(bounds: Rectangle, uv: Rectangle) -> Self {
+        QuadInstance {
+            uv: [uv.x, uv.y, uv.width, uv.height].into(),
+            ..QuadInstance::new(bounds, [1., 1., 1., 1.], QuadInstance::IMAGE)
+        }
+    }
+
+    /// A glyph laid out by glyph_brush, whose coverage is in the glyph atlas
+    pub fn glyph(pixels: Rect<i32>, uv: Rect<f32>, color: [f32; 4]) -> Self {
+        let bounds = Rectangle {
+            x: pixels.min.x as f32,
+            y: pixels.min.y as f32,
+            width: pixels.width() as f32,
+            height: pixels.height() as f32,
+        };
+        QuadInstance {
+            uv: [uv.min.x, uv.min.y, uv.width(), uv.height()].into(),
+            ..QuadInstance::new(bounds, color, QuadInstance::GLYPH)
+        }
+    }
+}
+
+fn components(color: Color) -> [f32; 4] {
+    [color.r, color.g, color.b, color.a]
+}
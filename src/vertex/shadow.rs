@@ -6,18 +6,24 @@ use glsl_layout::{vec2, vec4, AsStd140};
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, AsStd140)]
 #[repr(C, align(4))]
-pub struct TextVertex {
+pub struct ShadowVertex {
     pub position: vec2,
-    pub uv: vec2,
+    /// Position relative to the center of the shadowed box, before any transform
+    pub local: vec2,
+    pub half_size: vec2,
     pub color: vec4,
+    /// The standard deviation of the blur, and the corner radius of the box
+    pub params: vec2,
 }
 
-impl AsVertex for TextVertex {
+impl AsVertex for ShadowVertex {
     fn vertex() -> VertexFormat {
         VertexFormat::new((
             (Format::Rg32Sfloat, "in_pos"),
-            (Format::Rg32Sfloat, "in_uv"),
+            (Format::Rg32Sfloat, "in_local"),
+            (Format::Rg32Sfloat, "in_half_size"),
             (Format::Rgba32Sfloat, "in_color"),
+            (Format::Rg32Sfloat, "in_params"),
         ))
     }
 }
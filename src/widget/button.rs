@@ -1,9 +1,11 @@
 use iced_native::button::Renderer;
 use iced_native::{Element, Layout, Point, Rectangle};
 
+use crate::animation::Tween;
 use crate::backend::IcedRenderer;
 use crate::primitive::AmethystIcedPrimitive;
-use crate::{BorderStyle, style::colors};
+use crate::resources::{IcedAnimations, MouseCursor};
+use crate::{Background, BorderStyle, LinearGradient, Shadow, style::colors};
 
 impl<'a> Renderer for IcedRenderer<'a> {
     const DEFAULT_PADDING: u16 = 5;
@@ -20,16 +22,17 @@ impl<'a> Renderer for IcedRenderer<'a> {
         content: &Element<'_, Message, Self>,
         content_layout: Layout<'_>,
     ) -> Self::Output {
-        // TODO: Make these default colors customizable
-        // + add color to style
-        // + handle hovering
-        let background = match style {
+        let (background, defaults) = match style {
             ButtonStyle::Builtin {
                 background_color,
                 hovered_color, 
                 pressed_color,
                 disabled_color,
-                ..
+                text_color,
+                border_radius,
+                border,
+                transition,
+                shadow,
             } => {
                 let color = if is_disabled {
                     *disabled_color
@@ -40,10 +43,69 @@ impl<'a> Renderer for IcedRenderer<'a> {
                 } else {
                     *background_color
                 };
-                AmethystIcedPrimitive::Quad(bounds, Some(color.into()))
+                let color = match transition {
+                    Some(tween) => self.animate(
+                        IcedAnimations::widget_key("button", bounds),
+                        color,
+                        color,
+                        *tween,
+                    ),
+                    None => color,
+                };
+                (
+                    AmethystIcedPrimitive::Group(vec![
+                        AmethystIcedPrimitive::shadow(bounds, *border_radius, *shadow),
+                        AmethystIcedPrimitive::panel(
+                            bounds,
+                            Some(&Background::Color(color)),
+                            border,
+                            *border_radius,
+                        ),
+                    ]),
+                    defaults.with_text_color((*text_color).into()),
+                )
+            }
+            ButtonStyle::Custom {
+                background,
+                hovered_background,
+                pressed_background,
+                disabled_background,
+                text_color,
+                border_radius,
+                border,
+                shadow,
+            } => {
+                let state_background = if is_disabled {
+                    disabled_background
+                } else if is_pressed {
+                    pressed_background
+                } else if bounds.contains(cursor_position) {
+                    hovered_background
+                } else {
+                    &None
+                };
+                (
+                    AmethystIcedPrimitive::Group(vec![
+                        AmethystIcedPrimitive::shadow(bounds, *border_radius, *shadow),
+                        AmethystIcedPrimitive::panel(
+                            bounds,
+                            Some(state_background.as_ref().unwrap_or(background)),
+                            border,
+                            *border_radius,
+                        ),
+                    ]),
+                    defaults.with_text_color((*text_color).into()),
+                )
             }
         };
-        let children = content.draw(self, defaults, content_layout, cursor_position);
+        if !is_disabled {
+            self.register_focusable(bounds);
+            if bounds.contains(cursor_position) {
+                self.request_cursor(MouseCursor::Pointer);
+            }
+        }
+
+        let children = content.draw(self, &defaults, content_layout, cursor_position);
 
         AmethystIcedPrimitive::Group(vec![background, children])
     }
@@ -56,10 +118,28 @@ pub enum ButtonStyle {
         hovered_color: [f32;4],
         pressed_color: [f32;4],
         disabled_color: [f32;4],
+        /// Color inherited by the Texts of the button
+        text_color: [f32;4],
         border_radius: u32,
         border: BorderStyle,
+        /// Animates the background color between states, when set
+        transition: Option<Tween>,
+        shadow: Option<Shadow>,
     },
-} 
+    /// Fills the button with any Background, e.g. a gradient. The states without
+    /// a background of their own use the default one.
+    Custom {
+        background: Background,
+        hovered_background: Option<Background>,
+        pressed_background: Option<Background>,
+        disabled_background: Option<Background>,
+        /// Color inherited by the Texts of the button
+        text_color: [f32;4],
+        border_radius: u32,
+        border: BorderStyle,
+        shadow: Option<Shadow>,
+    },
+}
 
 impl ButtonStyle {
     pub fn danger() -> Self {
@@ -68,25 +148,59 @@ impl ButtonStyle {
             hovered_color: colors::DANGER_SHADED, 
             disabled_color: colors::DANGER_SHADED, 
             pressed_color: colors::DANGER_DARKER, 
+            text_color: colors::WHITE,
             border_radius: 0,
             border: BorderStyle {
                 width: 1,
                 color: [0.,0.,0.,1.],
             },
+            transition: Some(Tween::default()),
+            shadow: None,
         }
     }
     
+    /// A rounded button with a vertical gradient and a small shadow, lighter when hovered
+    pub fn gradient(top: [f32;4], bottom: [f32;4]) -> Self {
+        let lighten = |color: [f32;4]| [
+            (color[0] + 0.1).min(1.),
+            (color[1] + 0.1).min(1.),
+            (color[2] + 0.1).min(1.),
+            color[3],
+        ];
+        ButtonStyle::Custom {
+            background: LinearGradient::vertical(top, bottom).into(),
+            hovered_background: Some(LinearGradient::vertical(lighten(top), lighten(bottom)).into()),
+            pressed_background: Some(LinearGradient::vertical(bottom, top).into()),
+            disabled_background: Some(colors::LIGHT_GRAY.into()),
+            text_color: colors::WHITE,
+            border_radius: 6,
+            border: BorderStyle {
+                width: 1,
+                color: [0.,0.,0.,1.],
+            },
+            shadow: Some(Shadow {
+                offset: [0., 2.],
+                blur_radius: 6.,
+                spread: 0.,
+                color: [0., 0., 0., 0.4],
+            }),
+        }
+    }
+
     pub fn primary() -> Self {
         ButtonStyle::Builtin {
             background_color: colors::PRIMARY, 
             hovered_color: colors::PRIMARY_SHADED, 
             disabled_color: colors::PRIMARY_SHADED, 
             pressed_color: colors::PRIMARY_DARKER, 
+            text_color: colors::WHITE,
             border_radius: 0,
             border: BorderStyle {
                 width: 1,
                 color: [0.,0.,0.,1.],
             },
+            transition: Some(Tween::default()),
+            shadow: None,
         }
     }
 }
@@ -98,11 +212,14 @@ impl Default for ButtonStyle {
             hovered_color: colors::LIGHT_GRAY, 
             disabled_color: colors::LIGHT_GRAY, 
             pressed_color: colors::GRAY, 
+            text_color: colors::DARK_GRAY,
             border_radius: 0,
             border: BorderStyle {
                 width: 1,
                 color: [0.,0.,0.,1.],
             },
+            transition: Some(Tween::default()),
+            shadow: None,
         }
     }
 }
\ No newline at end of file
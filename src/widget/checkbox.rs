@@ -1,40 +1,197 @@
 use iced_native::checkbox::Renderer;
 use iced_native::Rectangle;
 
+use crate::animation::StateTransition;
 use crate::backend::IcedRenderer;
 use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::{IcedAnimations, MouseCursor};
 
 impl<'a> Renderer for IcedRenderer<'a> {
     const DEFAULT_SIZE: u16 = 20;
     const DEFAULT_SPACING: u16 = 20;
 
-    type Style = ();
+    type Style = CheckboxStyle;
 
     fn draw(
         &mut self,
         bounds: Rectangle,
         is_checked: bool,
-        _is_mouse_over: bool,
+        is_mouse_over: bool,
         label: Self::Output,
-        _style: &Self::Style,
+        style: &Self::Style,
     ) -> Self::Output {
-        // TODO: Style background color & radio color, outline
-        println!("drawing radio");
-        let background = AmethystIcedPrimitive::Quad(bounds, Some([1., 1., 1., 1.].into()));
-        let selected = if is_checked {
-            let default_size = Self::DEFAULT_SIZE as f32;
-            AmethystIcedPrimitive::Quad(
-                Rectangle {
-                    x: bounds.x + default_size / 4.,
-                    y: bounds.y + default_size / 4.,
-                    width: bounds.width - default_size / 2.,
-                    height: bounds.height - default_size / 2.,
-                },
-                Some([0., 1., 0., 1.].into()),
-            )
-        } else {
-            AmethystIcedPrimitive::None
-        };
-        AmethystIcedPrimitive::Group(vec![background, selected, label])
+        // TODO: outline
+        self.register_focusable(bounds);
+        if is_mouse_over {
+            self.request_cursor(MouseCursor::Pointer);
+        }
+        let background = AmethystIcedPrimitive::Quad(bounds, Some(style.background.into()));
+        let check = check_mark(
+            self,
+            "checkbox_mark",
+            bounds,
+            Self::DEFAULT_SIZE as f32,
+            is_checked,
+            style.check_color,
+            style.transition.as_ref(),
+        );
+        let check_box = self.animate_state(
+            IcedAnimations::widget_key("checkbox", bounds),
+            bounds,
+            style.transition.as_ref(),
+            is_mouse_over,
+            false,
+            AmethystIcedPrimitive::Group(vec![background, check]),
+        );
+        AmethystIcedPrimitive::Group(vec![check_box, label])
+    }
+}
+
+/// Draws the mark of a checked Checkbox or selected Radio, growing from its
+/// center when it appears if the style has a transition
+pub(crate) fn check_mark(
+    renderer: &IcedRenderer<'_>,
+    kind: &str,
+    bounds: Rectangle,
+    default_size: f32,
+    is_checked: bool,
+    color: [f32; 4],
+    transition: Option<&StateTransition>,
+) -> AmethystIcedPrimitive {
+    let target = if is_checked { 1. } else { 0. };
+    let scale = match transition {
+        Some(transition) => renderer.animate(
+            IcedAnimations::widget_key(kind, bounds),
+            target,
+            target,
+            transition.tween,
+        ),
+        None => target,
+    };
+    if scale <= 0. {
+        return AmethystIcedPrimitive::None;
+    }
+    let width = (bounds.width - default_size / 2.) * scale;
+    let height = (bounds.height - default_size / 2.) * scale;
+    AmethystIcedPrimitive::Quad(
+        Rectangle {
+            x: bounds.x + (bounds.width - width) / 2.,
+            y: bounds.y + (bounds.height - height) / 2.,
+            width,
+            height,
+        },
+        Some(color.into()),
+    )
+}
+
+/// The style of a Checkbox
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckboxStyle {
+    pub background: [f32; 4],
+    pub check_color: [f32; 4],
+    /// Eases the box into its hovered look and grows the check mark, when set
+    pub transition: Option<StateTransition>,
+}
+
+impl Default for CheckboxStyle {
+    fn default() -> Self {
+        CheckboxStyle {
+            background: [1., 1., 1., 1.],
+            check_color: [0., 1., 0., 1.],
+            transition: Some(StateTransition::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use crate::widget::Checkbox;
+    use crate::{Element, Sandbox};
+    use iced_native::Point;
+
+    use std::time::Duration;
+
+    struct Toggle(bool);
+
+    impl Sandbox for Toggle {
+        type UIMessage = bool;
+        type GameMessage = ();
+
+        fn update(&mut self, checked: &bool) -> Vec<()> {
+            self.0 = *checked;
+            vec![]
+        }
+
+        fn view(&mut self) -> Element<bool> {
+            Checkbox::new(self.0, "Toggle", |checked| checked).into()
+        }
+    }
+
+    /// The scales of the transforms found in the primitive tree
+    fn scales(primitive: &AmethystIcedPrimitive, found: &mut Vec<f32>) {
+        match primitive {
+            AmethystIcedPrimitive::Group(primitives) => primitives
+                .iter()
+                .for_each(|primitive| scales(primitive, found)),
+            AmethystIcedPrimitive::Opacity(_, primitive) => scales(primitive, found),
+            AmethystIcedPrimitive::Transform(transform, primitive) => {
+                found.push(transform.to_cols_array()[0]);
+                scales(primitive, found);
+            }
+            _ => {}
+        }
+    }
+
+    fn drawn_scales<S: Sandbox>(harness: &mut IcedTestHarness<S>) -> Vec<f32> {
+        let mut found = vec![];
+        scales(&harness.draw(), &mut found);
+        found
+    }
+
+    #[test]
+    fn hovered_checkboxes_ease_into_their_hovered_look() {
+        let mut harness = IcedTestHarness::new(Toggle(false));
+        let check_box = harness.layout().child(&[0]).center();
+        assert!(drawn_scales(&mut harness).is_empty());
+
+        harness.move_cursor(check_box);
+        // The animation starts from the idle look
+        assert!(drawn_scales(&mut harness).is_empty());
+        harness.advance_time(Duration::from_secs(1));
+        let hovered = StateTransition::default().hovered.scale;
+        assert!(drawn_scales(&mut harness).contains(&hovered));
+
+        harness.move_cursor(Point::new(700., 500.));
+        assert!(drawn_scales(&mut harness).contains(&hovered));
+        harness.advance_time(Duration::from_secs(1));
+        assert!(drawn_scales(&mut harness).is_empty());
+    }
+
+    #[test]
+    fn checkboxes_without_transition_are_never_transformed() {
+        struct Plain;
+
+        impl Sandbox for Plain {
+            type UIMessage = bool;
+            type GameMessage = ();
+
+            fn view(&mut self) -> Element<bool> {
+                Checkbox::new(true, "Plain", |checked| checked)
+                    .style(CheckboxStyle {
+                        transition: None,
+                        ..CheckboxStyle::default()
+                    })
+                    .into()
+            }
+        }
+
+        let mut harness = IcedTestHarness::new(Plain);
+        let check_box = harness.layout().child(&[0]).center();
+        harness.move_cursor(check_box);
+        harness.draw();
+        harness.advance_time(Duration::from_secs(1));
+        assert!(drawn_scales(&mut harness).is_empty());
     }
 }
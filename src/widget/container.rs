@@ -1,19 +1,326 @@
-use crate::backend::IcedRenderer;
+use crate::animation::StateTransition;
+use crate::backend::{Defaults, IcedRenderer, TextDefaults};
+use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::IcedAnimations;
+use crate::style::{Background, BorderStyle, Shadow};
 use iced_native::widget::container::Renderer;
-use iced_native::{Element, Point, Rectangle};
+use iced_native::{
+    layout, Align, Clipboard, Element, Event, Font, Hasher, Layout, Length, Point, Rectangle,
+    Widget,
+};
+use std::hash::Hash;
+
+/// A widget holding a single child, which it can align and decorate.
+///
+/// Wraps `iced_native::Container` so the texts inside use the font & size of
+/// the style when they are laid out, and not only when they are drawn.
+pub struct Container<'a, 'r, Message> {
+    container: iced_native::Container<'a, Message, IcedRenderer<'r>>,
+    font: Option<Font>,
+    text_size: Option<u16>,
+}
+
+impl<'a, 'r, Message> Container<'a, 'r, Message> {
+    pub fn new<E>(content: E) -> Self
+    where
+        E: Into<Element<'a, Message, IcedRenderer<'r>>>,
+    {
+        Container {
+            container: iced_native::Container::new(content),
+            font: None,
+            text_size: None,
+        }
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.container = self.container.width(width);
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.container = self.container.height(height);
+        self
+    }
+
+    pub fn max_width(mut self, max_width: u32) -> Self {
+        self.container = self.container.max_width(max_width);
+        self
+    }
+
+    pub fn max_height(mut self, max_height: u32) -> Self {
+        self.container = self.container.max_height(max_height);
+        self
+    }
+
+    pub fn padding(mut self, units: u16) -> Self {
+        self.container = self.container.padding(units);
+        self
+    }
+
+    pub fn align_x(mut self, alignment: Align) -> Self {
+        self.container = self.container.align_x(alignment);
+        self
+    }
+
+    pub fn align_y(mut self, alignment: Align) -> Self {
+        self.container = self.container.align_y(alignment);
+        self
+    }
+
+    pub fn center_x(mut self) -> Self {
+        self.container = self.container.center_x();
+        self
+    }
+
+    pub fn center_y(mut self) -> Self {
+        self.container = self.container.center_y();
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<ContainerStyle>) -> Self {
+        let style = style.into();
+        self.font = style.font;
+        self.text_size = style.text_size;
+        self.container = self.container.style(style);
+        self
+    }
+}
+
+impl<'a, 'r, Message> Widget<Message, IcedRenderer<'r>> for Container<'a, 'r, Message> {
+    fn width(&self) -> Length {
+        Widget::width(&self.container)
+    }
+
+    fn height(&self) -> Length {
+        Widget::height(&self.container)
+    }
+
+    fn layout(&self, renderer: &IcedRenderer<'r>, limits: &layout::Limits) -> layout::Node {
+        renderer.layout_with_text(self.font, self.text_size, || {
+            self.container.layout(renderer, limits)
+        })
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut IcedRenderer<'r>,
+        defaults: &Defaults,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> AmethystIcedPrimitive {
+        self.container
+            .draw(renderer, defaults, layout, cursor_position)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        self.container.hash_layout(state);
+        hash_text(self.font, self.text_size, state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        messages: &mut Vec<Message>,
+        renderer: &IcedRenderer<'r>,
+        clipboard: Option<&dyn Clipboard>,
+    ) {
+        self.container.on_event(
+            event,
+            layout,
+            cursor_position,
+            messages,
+            renderer,
+            clipboard,
+        )
+    }
+}
+
+impl<'a, 'r, Message: 'a> From<Container<'a, 'r, Message>>
+    for Element<'a, Message, IcedRenderer<'r>>
+{
+    fn from(container: Container<'a, 'r, Message>) -> Element<'a, Message, IcedRenderer<'r>> {
+        Element::new(container)
+    }
+}
 
 impl<'a> Renderer for IcedRenderer<'a> {
-    type Style = ();
+    type Style = ContainerStyle;
 
     fn draw<Message>(
         &mut self,
         defaults: &<Self as iced_native::renderer::Renderer>::Defaults,
-        _: Rectangle,
+        bounds: Rectangle,
         cursor_pos: Point,
-        _: &<Self as iced_native::widget::container::Renderer>::Style,
+        style: &<Self as iced_native::widget::container::Renderer>::Style,
         elem: &Element<'_, Message, Self>,
         layout: iced_native::layout::Layout<'_>,
     ) -> Self::Output {
-        elem.draw(self, &defaults, layout, cursor_pos)
+        let defaults = Defaults {
+            text: TextDefaults {
+                color: style
+                    .text_color
+                    .map(Into::into)
+                    .unwrap_or(defaults.text.color),
+                ..defaults.text.with(style.font, style.text_size)
+            },
+        };
+
+        let content = elem.draw(self, &defaults, layout, cursor_pos);
+
+        let primitive = if style.background.is_none()
+            && style.border.width == 0
+            && style.shadow.is_none()
+            && !style.backdrop_blur
+        {
+            content
+        } else {
+            panel(bounds, style, content)
+        };
+        self.animate_state(
+            IcedAnimations::widget_key("container", bounds),
+            bounds,
+            style.transition.as_ref(),
+            bounds.contains(cursor_pos),
+            false,
+            primitive,
+        )
+    }
+}
+
+/// Hashes the text properties a Container hands down to its content
+pub(crate) fn hash_text(font: Option<Font>, size: Option<u16>, state: &mut Hasher) {
+    if let Some(Font::External { name, .. }) = font {
+        name.hash(state);
+    }
+    size.hash(state);
+}
+
+/// Draws the content over the shadow, backdrop & background of the style
+fn panel(
+    bounds: Rectangle,
+    style: &ContainerStyle,
+    content: AmethystIcedPrimitive,
+) -> AmethystIcedPrimitive {
+    let shadow = AmethystIcedPrimitive::shadow(bounds, style.border_radius, style.shadow);
+    let backdrop =
+        AmethystIcedPrimitive::backdrop(bounds, style.border_radius, style.backdrop_blur);
+    let background = AmethystIcedPrimitive::panel(
+        bounds,
+        style.background.as_ref(),
+        &style.border,
+        style.border_radius,
+    );
+
+    AmethystIcedPrimitive::Group(vec![shadow, backdrop, background, content])
+}
+
+/// The style of a Container.
+///
+/// The text color, font & size are inherited by every Text of the Container
+/// which doesn't define its own.
+#[derive(Clone, Default)]
+pub struct ContainerStyle {
+    pub background: Option<Background>,
+    pub border: BorderStyle,
+    pub border_radius: u32,
+    pub text_color: Option<[f32; 4]>,
+    pub font: Option<Font>,
+    pub text_size: Option<u16>,
+    /// Drawn below the background, e.g. to lift a window above the rest of the UI
+    pub shadow: Option<Shadow>,
+    /// Blurs the scene behind the container, like frosted glass. A translucent
+    /// background tints it.
+    ///
+    /// Requires `IcedUI::with_backdrop_blur`, it is ignored otherwise.
+    pub backdrop_blur: bool,
+    /// Eases the container into its hovered look, e.g. to lift a card under the cursor
+    pub transition: Option<StateTransition>,
+}
+
+impl ContainerStyle {
+    /// A dark, rounded panel with light text, casting a shadow
+    pub fn panel() -> Self {
+        ContainerStyle {
+            background: Some([0.12, 0.12, 0.14, 0.95].into()),
+            border: BorderStyle {
+                width: 1,
+                color: [0.3, 0.3, 0.35, 1.],
+            },
+            border_radius: 6,
+            text_color: Some([0.95, 0.95, 0.95, 1.]),
+            shadow: Some(Shadow::drop([0., 0., 0., 0.5])),
+            ..ContainerStyle::default()
+        }
+    }
+
+    /// A light, rounded box with dark text
+    pub fn card() -> Self {
+        ContainerStyle {
+            background: Some(crate::style::colors::VERY_LIGHT_GRAY.into()),
+            border: BorderStyle {
+                width: 1,
+                color: crate::style::colors::GRAY,
+            },
+            border_radius: 4,
+            text_color: Some([0.1, 0.1, 0.1, 1.]),
+            ..ContainerStyle::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::IcedTestHarness;
+    use crate::widget::Column;
+    use crate::{Sandbox, Text};
+
+    struct Labels;
+
+    impl Sandbox for Labels {
+        type UIMessage = ();
+        type GameMessage = ();
+
+        fn view(&mut self) -> Element<(), IcedRenderer> {
+            let labels = Column::new()
+                .push(Text::new("ab"))
+                .push(Text::new("ab").size(20));
+            Container::new(labels)
+                .style(ContainerStyle {
+                    text_size: Some(30),
+                    ..ContainerStyle::default()
+                })
+                .into()
+        }
+    }
+
+    fn text_sizes(primitive: &AmethystIcedPrimitive, sizes: &mut Vec<u16>) {
+        match primitive {
+            AmethystIcedPrimitive::Text { size, .. } => sizes.push(*size),
+            AmethystIcedPrimitive::Group(primitives) => {
+                primitives.iter().for_each(|p| text_sizes(p, sizes))
+            }
+            AmethystIcedPrimitive::Cached { primitive, .. }
+            | AmethystIcedPrimitive::Opacity(_, primitive)
+            | AmethystIcedPrimitive::Transform(_, primitive)
+            | AmethystIcedPrimitive::Overlay(primitive) => text_sizes(primitive, sizes),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn texts_are_laid_out_and_drawn_with_the_inherited_size() {
+        let mut harness = IcedTestHarness::new(Labels);
+        let layout = harness.layout();
+        let inherited = layout.child(&[0, 0]).bounds;
+        assert_eq!((inherited.width, inherited.height), (30., 30.));
+        let explicit = layout.child(&[0, 1]).bounds;
+        assert_eq!((explicit.width, explicit.height), (20., 20.));
+
+        let mut sizes = vec![];
+        text_sizes(&harness.draw(), &mut sizes);
+        assert_eq!(sizes, vec![30, 20]);
     }
 }
@@ -1,7 +1,7 @@
 mod button;
 mod checkbox;
 mod column;
-mod container;
+pub(crate) mod container;
 mod pane;
 mod progress_bar;
 mod radio;
@@ -13,9 +13,14 @@ mod text;
 use crate::backend::IcedRenderer;
 
 pub use button::ButtonStyle;
+pub use checkbox::CheckboxStyle;
+pub use container::{Container, ContainerStyle};
+pub use pane::{PaneGridStyle, TitleBar};
+pub use progress_bar::{FillDirection, ProgressBarStyle, Segments};
+pub use radio::RadioStyle;
+pub use slider::SliderStyle;
 pub type Button<'a, 'r, Message> = iced_native::Button<'a, Message, IcedRenderer<'r>>;
 pub type Checkbox<'a, Message> = iced_native::Checkbox<Message, IcedRenderer<'a>>;
-pub type Container<'a, 'r, Message> = iced_native::Container<'a, Message, IcedRenderer<'r>>;
 pub type Column<'a, 'r, Message> = iced_native::Column<'a, Message, IcedRenderer<'r>>;
 pub type PaneGrid<'a, 'r, Message> = iced_native::PaneGrid<'a, Message, IcedRenderer<'r>>;
 pub type ProgressBar<'a> = iced_native::ProgressBar<IcedRenderer<'a>>;
@@ -1,11 +1,59 @@
 use iced_native::pane_grid::Renderer;
 use iced_native::{
     pane_grid::{Axis, Pane},
-    Element, Layout, Point,
+    Element, Layout, Length, Point, Rectangle, Vector,
 };
 
+use std::cmp::Ordering;
+
 use crate::backend::IcedRenderer;
 use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::MouseCursor;
+use crate::style::colors;
+use crate::widget::{Column, Container, ContainerStyle, Row};
+use crate::{Align, Text};
+
+/// The look of the PaneGrids, shared by all of them.
+///
+/// Insert it in the World to restyle the PaneGrids of the UI:
+///
+/// ```ignore
+/// world.insert(PaneGridStyle {
+///     splitter_color: [0.5, 0.5, 0.5, 1.],
+///     ..PaneGridStyle::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaneGridStyle {
+    pub splitter_width: f32,
+    /// The width of a splitter under the cursor, which is also how close the
+    /// cursor must be to grab thin splitters
+    pub splitter_hovered_width: f32,
+    pub splitter_color: [f32; 4],
+    pub splitter_hovered_color: [f32; 4],
+    /// Panes further apart than this are not considered neighbours
+    pub max_split_gap: f32,
+    /// Drawn over the pane the dragged pane would be dropped on
+    pub drop_target_color: [f32; 4],
+    /// Drawn where the dragged pane was
+    pub drag_placeholder_color: [f32; 4],
+    pub dragged_pane_opacity: f32,
+}
+
+impl Default for PaneGridStyle {
+    fn default() -> Self {
+        PaneGridStyle {
+            splitter_width: 2.,
+            splitter_hovered_width: 4.,
+            splitter_color: [0.3, 0.3, 0.35, 1.],
+            splitter_hovered_color: colors::PRIMARY,
+            max_split_gap: 32.,
+            drop_target_color: [0.1176, 0.5333, 0.8980, 0.25],
+            drag_placeholder_color: [1., 1., 1., 0.05],
+            dragged_pane_opacity: 0.6,
+        }
+    }
+}
 
 impl<'a> Renderer for IcedRenderer<'a> {
     fn draw<Message>(
@@ -13,10 +61,11 @@ impl<'a> Renderer for IcedRenderer<'a> {
         defaults: &Self::Defaults,
         content: &[(Pane, Element<'_, Message, Self>)],
         dragging: Option<Pane>,
-        _resizing: Option<Axis>,
+        resizing: Option<Axis>,
         layout: Layout<'_>,
         cursor_position: Point,
     ) -> Self::Output {
+        let style = *self.pane_grid_style();
         let pane_cursor_position = if dragging.is_some() {
             // TODO: Remove once cursor availability is encoded in the type
             // system
@@ -25,15 +74,255 @@ impl<'a> Renderer for IcedRenderer<'a> {
             cursor_position
         };
 
-        AmethystIcedPrimitive::Group(
-            content
+        let mut primitives = Vec::with_capacity(content.len() + 2);
+        let mut dragged = None;
+        let mut pane_bounds = Vec::with_capacity(content.len());
+
+        for ((id, pane), layout) in content.iter().zip(layout.children()) {
+            let bounds = layout.bounds();
+            pane_bounds.push(bounds);
+
+            if Some(*id) == dragging {
+                let primitive = pane.draw(self, defaults, layout, pane_cursor_position);
+                dragged = Some((bounds, primitive));
+                primitives.push(AmethystIcedPrimitive::Quad(
+                    bounds,
+                    Some(style.drag_placeholder_color.into()),
+                ));
+            } else {
+                primitives.push(pane.draw(self, defaults, layout, pane_cursor_position));
+                if dragging.is_some() && bounds.contains(cursor_position) {
+                    primitives.push(AmethystIcedPrimitive::Quad(
+                        bounds,
+                        Some(style.drop_target_color.into()),
+                    ));
+                }
+            }
+        }
+
+        let splitters = splitters(&pane_bounds, style.max_split_gap);
+        for (axis, bounds) in &splitters {
+            self.register_draggable(splitter_grab_area(*axis, *bounds, &style));
+        }
+        if dragging.is_none() {
+            primitives.push(draw_splitters(
+                &splitters,
+                resizing,
+                cursor_position,
+                &style,
+            ));
+        }
+
+        let resize_axis = resizing.or_else(|| {
+            splitters
                 .iter()
-                .zip(layout.children())
-                .enumerate()
-                .map(|(_i, ((_id, pane), layout))| {
-                    pane.draw(self, defaults, layout, pane_cursor_position)
-                })
-                .collect(),
-        )
+                .find(|(axis, bounds)| is_splitter_hovered(*axis, *bounds, cursor_position, &style))
+                .map(|(axis, _)| *axis)
+        });
+        match (dragging, resize_axis) {
+            (Some(_), _) => self.request_cursor(MouseCursor::Grabbing),
+            (None, Some(Axis::Vertical)) => self.request_cursor(MouseCursor::ResizingHorizontally),
+            (None, Some(Axis::Horizontal)) => self.request_cursor(MouseCursor::ResizingVertically),
+            (None, None) => {}
+        }
+
+        // The dragged pane is drawn last, centered on the cursor and faded out
+        if let Some((bounds, primitive)) = dragged {
+            let offset = Vector::new(
+                cursor_position.x - (bounds.x + bounds.width / 2.),
+                cursor_position.y - (bounds.y + bounds.height / 2.),
+            );
+            primitives.push(primitive.translate(offset).fade(style.dragged_pane_opacity));
+        }
+
+        AmethystIcedPrimitive::Group(primitives)
+    }
+}
+
+/// Finds the gaps separating neighbouring panes.
+///
+/// The panes are sorted along each axis, so that only the panes starting right
+/// after the end of a pane are compared with it.
+pub(crate) fn splitters(panes: &[Rectangle], max_gap: f32) -> Vec<(Axis, Rectangle)> {
+    let mut splitters = Vec::new();
+    let mut by_x: Vec<&Rectangle> = panes.iter().collect();
+    by_x.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal));
+    let mut by_y: Vec<&Rectangle> = panes.iter().collect();
+    by_y.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal));
+
+    for a in panes {
+        // The panes on the right of a
+        let right = a.x + a.width;
+        let first = by_x.partition_point(|b| b.x < right);
+        for b in by_x[first..].iter().take_while(|b| b.x <= right + max_gap) {
+            let top = a.y.max(b.y);
+            let bottom = (a.y + a.height).min(b.y + b.height);
+            if bottom > top {
+                splitters.push((
+                    Axis::Vertical,
+                    Rectangle {
+                        x: right,
+                        y: top,
+                        width: b.x - right,
+                        height: bottom - top,
+                    },
+                ));
+            }
+        }
+
+        // The panes below a
+        let bottom = a.y + a.height;
+        let first = by_y.partition_point(|b| b.y < bottom);
+        for b in by_y[first..].iter().take_while(|b| b.y <= bottom + max_gap) {
+            let left = a.x.max(b.x);
+            let right = (a.x + a.width).min(b.x + b.width);
+            if right > left {
+                splitters.push((
+                    Axis::Horizontal,
+                    Rectangle {
+                        x: left,
+                        y: bottom,
+                        width: right - left,
+                        height: b.y - bottom,
+                    },
+                ));
+            }
+        }
+    }
+    splitters
+}
+
+/// Returns whether the cursor is over the splitter, with a bit of leeway
+/// for splitters thinner than a few pixels
+pub(crate) fn is_splitter_hovered(
+    axis: Axis,
+    bounds: Rectangle,
+    cursor_position: Point,
+    style: &PaneGridStyle,
+) -> bool {
+    splitter_grab_area(axis, bounds, style).contains(cursor_position)
+}
+
+/// The area where a splitter can be grabbed
+fn splitter_grab_area(axis: Axis, bounds: Rectangle, style: &PaneGridStyle) -> Rectangle {
+    let leeway = (style.splitter_hovered_width - bounds.width.min(bounds.height)).max(0.);
+    match axis {
+        Axis::Vertical => Rectangle {
+            x: bounds.x - leeway / 2.,
+            width: bounds.width + leeway,
+            ..bounds
+        },
+        Axis::Horizontal => Rectangle {
+            y: bounds.y - leeway / 2.,
+            height: bounds.height + leeway,
+            ..bounds
+        },
+    }
+}
+
+fn draw_splitters(
+    splitters: &[(Axis, Rectangle)],
+    resizing: Option<Axis>,
+    cursor_position: Point,
+    style: &PaneGridStyle,
+) -> AmethystIcedPrimitive {
+    AmethystIcedPrimitive::Group(
+        splitters
+            .iter()
+            .map(|&(axis, bounds)| {
+                let active = is_splitter_hovered(axis, bounds, cursor_position, style)
+                    && resizing.map(|resizing| resizing == axis).unwrap_or(true);
+                let (width, color) = if active {
+                    (style.splitter_hovered_width, style.splitter_hovered_color)
+                } else {
+                    (style.splitter_width, style.splitter_color)
+                };
+                let bar = match axis {
+                    Axis::Vertical => Rectangle {
+                        x: bounds.x + (bounds.width - width) / 2.,
+                        width,
+                        ..bounds
+                    },
+                    Axis::Horizontal => Rectangle {
+                        y: bounds.y + (bounds.height - width) / 2.,
+                        height: width,
+                        ..bounds
+                    },
+                };
+                AmethystIcedPrimitive::Quad(bar, Some(color.into()))
+            })
+            .collect(),
+    )
+}
+
+/// A header displayed on top of the content of a pane.
+///
+/// PaneGrid has no notion of title bars yet, so the title bar is simply
+/// stacked above the content when calling `view`.
+pub struct TitleBar<'a, 'r, Message> {
+    title: String,
+    controls: Option<crate::Element<'a, 'r, Message>>,
+    focused: bool,
+    padding: u16,
+}
+
+impl<'a, 'r, Message: 'a> TitleBar<'a, 'r, Message> {
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        TitleBar {
+            title: title.into(),
+            controls: None,
+            focused: false,
+            padding: 5,
+        }
+    }
+
+    /// Sets the elements displayed on the right of the title
+    pub fn controls<E: Into<crate::Element<'a, 'r, Message>>>(mut self, controls: E) -> Self {
+        self.controls = Some(controls.into());
+        self
+    }
+
+    /// Highlights the title bar, typically when its pane has focus
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Stacks the title bar on top of the given pane content
+    pub fn view<E: Into<crate::Element<'a, 'r, Message>>>(self, content: E) -> crate::Element<'a, 'r, Message> {
+        let mut header = Row::new()
+            .width(Length::Fill)
+            .align_items(Align::Center)
+            .push(Text::new(self.title).width(Length::Fill));
+        if let Some(controls) = self.controls {
+            header = header.push(controls);
+        }
+
+        let background = if self.focused {
+            colors::PRIMARY
+        } else {
+            [0.2, 0.2, 0.22, 1.]
+        };
+
+        Column::new()
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .push(
+                Container::new(header)
+                    .width(Length::Fill)
+                    .padding(self.padding)
+                    .style(ContainerStyle {
+                        background: Some(background.into()),
+                        text_color: Some(colors::WHITE),
+                        ..ContainerStyle::default()
+                    }),
+            )
+            .push(content)
+            .into()
     }
 }
@@ -1,11 +1,14 @@
 use iced_native::progress_bar::Renderer as Renderer;
 use iced_native::Rectangle;
 
+use crate::animation::{Easing, Tween};
 use crate::backend::IcedRenderer;
+use crate::resources::IcedAnimations;
 use crate::primitive::AmethystIcedPrimitive;
+use crate::style::{Background, BorderStyle};
 
 impl<'a> Renderer for IcedRenderer<'a> {
-    type Style = ();
+    type Style = ProgressBarStyle;
 
     const DEFAULT_HEIGHT: u16 = 30;
 
@@ -14,31 +17,239 @@ impl<'a> Renderer for IcedRenderer<'a> {
         bounds: Rectangle,
         range: std::ops::RangeInclusive<f32>,
         value: f32,
-        _style_sheet: &Self::Style,
+        style: &Self::Style,
     ) -> Self::Output {
         let (range_start, range_end) = range.into_inner();
-        let active_progress_width = bounds.width
-            * ((value - range_start) / (range_end - range_start).max(1.0));
-
-        let background = AmethystIcedPrimitive::Quad(
-            bounds,
-            Some([1.,1.,1.,1.].into()),
-        );
-
-        if active_progress_width > 0.0 {
-            let bar = AmethystIcedPrimitive::Quad(
-                Rectangle {
-                    width: active_progress_width,
+        let span = range_end - range_start;
+        let progress = if span > 0.0 {
+            ((value - range_start) / span).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let progress = match style.transition {
+            Some(tween) => self.animate(
+                IcedAnimations::widget_key("progress_bar", bounds),
+                progress,
+                progress,
+                tween,
+            ),
+            None => progress,
+        };
+
+        let background = style.background.primitive(bounds);
+
+        let bar = match &style.segments {
+            None => {
+                if progress > 0.0 {
+                    style.bar.primitive(style.direction.fill(bounds, progress))
+                } else {
+                    AmethystIcedPrimitive::None
+                }
+            }
+            Some(segments) => draw_segments(bounds, progress, style, segments),
+        };
+
+        let border = AmethystIcedPrimitive::border(bounds, &style.border);
+
+        AmethystIcedPrimitive::Group(vec![background, bar, border])
+    }
+}
+
+fn draw_segments(
+    bounds: Rectangle,
+    progress: f32,
+    style: &ProgressBarStyle,
+    segments: &Segments,
+) -> AmethystIcedPrimitive {
+    let count = segments.count.max(1) as f32;
+    let notch = segments.notch_width as f32;
+    let vertical = style.direction.is_vertical();
+    let length = if vertical { bounds.height } else { bounds.width };
+    let segment_length = ((length - notch * (count - 1.)) / count).max(0.);
+
+    let mut primitives = Vec::new();
+    for i in 0..segments.count.max(1) {
+        let i = i as f32;
+        let segment_progress = (progress * count - i).max(0.).min(1.);
+
+        // Segments are laid out starting from where the bar starts filling
+        let offset = i * (segment_length + notch);
+        let segment_bounds = match style.direction {
+            FillDirection::LeftToRight => Rectangle {
+                x: bounds.x + offset,
+                width: segment_length,
+                ..bounds
+            },
+            FillDirection::RightToLeft => Rectangle {
+                x: bounds.x + bounds.width - offset - segment_length,
+                width: segment_length,
+                ..bounds
+            },
+            FillDirection::BottomToTop => Rectangle {
+                y: bounds.y + bounds.height - offset - segment_length,
+                height: segment_length,
+                ..bounds
+            },
+            FillDirection::TopToBottom => Rectangle {
+                y: bounds.y + offset,
+                height: segment_length,
+                ..bounds
+            },
+        };
+
+        if segment_progress > 0. {
+            primitives.push(
+                style
+                    .bar
+                    .primitive(style.direction.fill(segment_bounds, segment_progress)),
+            );
+        }
+
+        if i > 0. && notch > 0. {
+            let notch_bounds = match style.direction {
+                FillDirection::LeftToRight => Rectangle {
+                    x: segment_bounds.x - notch,
+                    width: notch,
                     ..bounds
                 },
-                Some([0.,1.,0.,1.].into())
-            );
+                FillDirection::RightToLeft => Rectangle {
+                    x: segment_bounds.x + segment_bounds.width,
+                    width: notch,
+                    ..bounds
+                },
+                FillDirection::BottomToTop => Rectangle {
+                    y: segment_bounds.y + segment_bounds.height,
+                    height: notch,
+                    ..bounds
+                },
+                FillDirection::TopToBottom => Rectangle {
+                    y: segment_bounds.y - notch,
+                    height: notch,
+                    ..bounds
+                },
+            };
+            primitives.push(AmethystIcedPrimitive::Quad(
+                notch_bounds,
+                Some(segments.notch_color.into()),
+            ));
+        }
+    }
+    AmethystIcedPrimitive::Group(primitives)
+}
 
-            AmethystIcedPrimitive::Group(
-                vec![background, bar],
-            )
-        } else {
-            background
+/// The direction in which a ProgressBar fills up.
+///
+/// Vertical directions are best used with a ProgressBar
+/// which is taller than it is wide.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillDirection {
+    LeftToRight,
+    RightToLeft,
+    BottomToTop,
+    TopToBottom,
+}
+
+impl FillDirection {
+    fn is_vertical(self) -> bool {
+        match self {
+            FillDirection::BottomToTop | FillDirection::TopToBottom => true,
+            FillDirection::LeftToRight | FillDirection::RightToLeft => false,
+        }
+    }
+
+    /// Returns the part of the bounds covered by the given progress, between 0 and 1
+    fn fill(self, bounds: Rectangle, progress: f32) -> Rectangle {
+        match self {
+            FillDirection::LeftToRight => Rectangle {
+                width: bounds.width * progress,
+                ..bounds
+            },
+            FillDirection::RightToLeft => Rectangle {
+                x: bounds.x + bounds.width * (1. - progress),
+                width: bounds.width * progress,
+                ..bounds
+            },
+            FillDirection::BottomToTop => Rectangle {
+                y: bounds.y + bounds.height * (1. - progress),
+                height: bounds.height * progress,
+                ..bounds
+            },
+            FillDirection::TopToBottom => Rectangle {
+                height: bounds.height * progress,
+                ..bounds
+            },
+        }
+    }
+}
+
+/// Splits a ProgressBar in evenly sized segments separated by notches,
+/// as commonly seen on health or stamina bars.
+#[derive(Clone)]
+pub struct Segments {
+    pub count: u16,
+    pub notch_width: u16,
+    pub notch_color: [f32; 4],
+}
+
+#[derive(Clone)]
+pub struct ProgressBarStyle {
+    pub background: Background,
+    pub bar: Background,
+    pub border: BorderStyle,
+    pub direction: FillDirection,
+    pub segments: Option<Segments>,
+    /// Animates the bar when the value changes, when set
+    pub transition: Option<Tween>,
+}
+
+impl ProgressBarStyle {
+    /// A segmented red bar, filling from the left
+    pub fn health(segments: u16) -> Self {
+        ProgressBarStyle {
+            background: [0.15, 0.15, 0.15, 1.].into(),
+            bar: [0.8, 0.1, 0.1, 1.].into(),
+            border: BorderStyle {
+                width: 1,
+                color: [0., 0., 0., 1.],
+            },
+            direction: FillDirection::LeftToRight,
+            segments: Some(Segments {
+                count: segments,
+                notch_width: 2,
+                notch_color: [0., 0., 0., 1.],
+            }),
+            transition: Some(Tween::new(
+                std::time::Duration::from_millis(300),
+                Easing::EaseOutCubic,
+            )),
+        }
+    }
+
+    /// A vertical yellow bar, filling from the bottom
+    pub fn stamina() -> Self {
+        ProgressBarStyle {
+            background: [0.15, 0.15, 0.15, 1.].into(),
+            bar: [0.9, 0.8, 0.1, 1.].into(),
+            border: BorderStyle {
+                width: 1,
+                color: [0., 0., 0., 1.],
+            },
+            direction: FillDirection::BottomToTop,
+            segments: None,
+            transition: None,
+        }
+    }
+}
+
+impl Default for ProgressBarStyle {
+    fn default() -> Self {
+        ProgressBarStyle {
+            background: [1., 1., 1., 1.].into(),
+            bar: [0., 1., 0., 1.].into(),
+            border: BorderStyle::default(),
+            direction: FillDirection::LeftToRight,
+            segments: None,
+            transition: None,
         }
     }
-}
\ No newline at end of file
+}
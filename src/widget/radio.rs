@@ -1,13 +1,16 @@
 use iced_native::radio::Renderer;
 use iced_native::Rectangle;
 
+use crate::animation::StateTransition;
 use crate::backend::IcedRenderer;
 use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::{IcedAnimations, MouseCursor};
+use crate::widget::checkbox::check_mark;
 
 const RADIO_DEFAULT_SIZE: f32 = 20.;
 
 impl<'a> Renderer for IcedRenderer<'a> {
-    type Style = ();
+    type Style = RadioStyle;
 
     fn default_size(&self) -> u32 {
         RADIO_DEFAULT_SIZE as u32
@@ -17,26 +20,52 @@ impl<'a> Renderer for IcedRenderer<'a> {
         &mut self,
         bounds: Rectangle,
         is_selected: bool,
-        _is_mouse_over: bool,
+        is_mouse_over: bool,
         label: Self::Output,
-        _style: &Self::Style,
+        style: &Self::Style,
     ) -> Self::Output {
-        // TODO: Style background color & radio color, outline
-        println!("drawing radio");
-        let background = AmethystIcedPrimitive::Quad(bounds, Some([1., 1., 1., 1.].into()));
-        let selected = if is_selected {
-            AmethystIcedPrimitive::Quad(
-                Rectangle {
-                    x: bounds.x + RADIO_DEFAULT_SIZE / 4.,
-                    y: bounds.y + RADIO_DEFAULT_SIZE / 4.,
-                    width: bounds.width - RADIO_DEFAULT_SIZE / 2.,
-                    height: bounds.height - RADIO_DEFAULT_SIZE / 2.,
-                },
-                Some([0., 1., 0., 1.].into()),
-            )
-        } else {
-            AmethystIcedPrimitive::None
-        };
-        AmethystIcedPrimitive::Group(vec![background, selected, label])
+        // TODO: outline
+        self.register_focusable(bounds);
+        if is_mouse_over {
+            self.request_cursor(MouseCursor::Pointer);
+        }
+        let background = AmethystIcedPrimitive::Quad(bounds, Some(style.background.into()));
+        let selected = check_mark(
+            self,
+            "radio_mark",
+            bounds,
+            RADIO_DEFAULT_SIZE,
+            is_selected,
+            style.dot_color,
+            style.transition.as_ref(),
+        );
+        let radio = self.animate_state(
+            IcedAnimations::widget_key("radio", bounds),
+            bounds,
+            style.transition.as_ref(),
+            is_mouse_over,
+            false,
+            AmethystIcedPrimitive::Group(vec![background, selected]),
+        );
+        AmethystIcedPrimitive::Group(vec![radio, label])
+    }
+}
+
+/// The style of a Radio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadioStyle {
+    pub background: [f32; 4],
+    pub dot_color: [f32; 4],
+    /// Eases the button into its hovered look and grows the dot, when set
+    pub transition: Option<StateTransition>,
+}
+
+impl Default for RadioStyle {
+    fn default() -> Self {
+        RadioStyle {
+            background: [1., 1., 1., 1.],
+            dot_color: [0., 1., 0., 1.],
+            transition: Some(StateTransition::default()),
+        }
     }
 }
@@ -1,8 +1,10 @@
 use iced_native::slider::Renderer;
 use iced_native::{Point, Rectangle};
 
+use crate::animation::StateTransition;
 use crate::backend::IcedRenderer;
 use crate::primitive::AmethystIcedPrimitive;
+use crate::resources::{IcedAnimations, MouseCursor};
 
 use std::ops::RangeInclusive;
 
@@ -11,7 +13,7 @@ const HANDLE_WIDTH: f32 = 20.;
 const HANDLE_HEIGHT: f32 = 20.;
 
 impl<'a> Renderer for IcedRenderer<'a> {
-    type Style = ();
+    type Style = SliderStyle;
 
     fn height(&self) -> u32 {
         30
@@ -20,14 +22,19 @@ impl<'a> Renderer for IcedRenderer<'a> {
     fn draw(
         &mut self,
         bounds: Rectangle,
-        _cursor_position: Point,
+        cursor_position: Point,
         range: RangeInclusive<f32>,
         value: f32,
-        _is_dragging: bool,
-        _style: &Self::Style,
+        is_dragging: bool,
+        style: &Self::Style,
     ) -> Self::Output {
-        // TODO: Handle style (knob should be custom, nine-patch & colors for rail)
-        // TODO: Handle cursor position, is_dragging, etc ...
+        // TODO: Handle style (knob should be custom, nine-patch for rail)
+        self.register_draggable(bounds);
+        if is_dragging {
+            self.request_cursor(MouseCursor::Grabbing);
+        } else if bounds.contains(cursor_position) {
+            self.request_cursor(MouseCursor::Grab);
+        }
 
         let rail_y = bounds.y + (bounds.height / 2.0).round();
 
@@ -39,7 +46,7 @@ impl<'a> Renderer for IcedRenderer<'a> {
                     width: bounds.width,
                     height: 2.0,
                 },
-                Some([1., 0., 0., 1.].into()),
+                Some(style.rail_color.into()),
             ),
             AmethystIcedPrimitive::Quad(
                 Rectangle {
@@ -48,7 +55,7 @@ impl<'a> Renderer for IcedRenderer<'a> {
                     width: bounds.width,
                     height: 2.0,
                 },
-                Some([1., 0., 0., 1.].into()),
+                Some(style.rail_color.into()),
             ),
         );
 
@@ -57,16 +64,41 @@ impl<'a> Renderer for IcedRenderer<'a> {
         let handle_offset = (bounds.width as f32 - HANDLE_WIDTH)
             * ((value - range_start) / (range_end - range_start).max(1.0));
 
-        let knob = AmethystIcedPrimitive::Quad(
-            Rectangle {
-                x: bounds.x + handle_offset.round(),
-                y: rail_y - HANDLE_HEIGHT / 2.0,
-                width: HANDLE_WIDTH,
-                height: HANDLE_HEIGHT,
-            },
-            Some([0., 1., 0., 1.].into()),
+        let knob_bounds = Rectangle {
+            x: bounds.x + handle_offset.round(),
+            y: rail_y - HANDLE_HEIGHT / 2.0,
+            width: HANDLE_WIDTH,
+            height: HANDLE_HEIGHT,
+        };
+        // Keyed by the whole slider, so that the knob doesn't start over when dragged
+        let knob = self.animate_state(
+            IcedAnimations::widget_key("slider", bounds),
+            knob_bounds,
+            style.transition.as_ref(),
+            bounds.contains(cursor_position),
+            is_dragging,
+            AmethystIcedPrimitive::Quad(knob_bounds, Some(style.handle_color.into())),
         );
 
         AmethystIcedPrimitive::Group(vec![rail_top, rail_bottom, knob])
     }
 }
+
+/// The style of a Slider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliderStyle {
+    pub rail_color: [f32; 4],
+    pub handle_color: [f32; 4],
+    /// Eases the handle into its hovered and dragged looks, when set
+    pub transition: Option<StateTransition>,
+}
+
+impl Default for SliderStyle {
+    fn default() -> Self {
+        SliderStyle {
+            rail_color: [1., 0., 0., 1.],
+            handle_color: [0., 1., 0., 1.],
+            transition: Some(StateTransition::default()),
+        }
+    }
+}
@@ -7,6 +7,10 @@ impl<'a> TextRenderer for IcedRenderer<'a> {
     const DEFAULT_SIZE: u16 = 16;
 
     fn measure(&self, content: &str, size: u16, font: Font, bounds: Size) -> (f32, f32) {
+        let (font, size) = self.layout_text().inherit(font, size);
+        if let Some(measurer) = self.mock_measurer() {
+            return measurer.measure(content, size, bounds);
+        }
 
         let font_id = match font {
             Font::Default => FontId::default(),
@@ -28,7 +32,7 @@ impl<'a> TextRenderer for IcedRenderer<'a> {
 
     fn draw(
         &mut self,
-        _defaults: &Self::Defaults,
+        defaults: &Self::Defaults,
         bounds: Rectangle,
         content: &str,
         size: u16,
@@ -37,7 +41,8 @@ impl<'a> TextRenderer for IcedRenderer<'a> {
         horizontal_alignment: HorizontalAlignment,
         _vertical_alignment: VerticalAlignment,
     ) -> Self::Output {
-        let color = color.unwrap_or(Color::WHITE);
+        let (font, size) = defaults.text.inherit(font, size);
+        let color = color.unwrap_or(defaults.text.color);
         let color = [color.r, color.g, color.b, color.a];
 
         let font_id = match font {